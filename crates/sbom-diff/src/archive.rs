@@ -0,0 +1,825 @@
+use crate::attestation::is_sbom_predicate_type;
+use anyhow::{anyhow, bail, Context};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// media types an SBOM document is published under, as an OCI artifact or layer.
+const SBOM_MEDIA_TYPES: &[&str] = &[
+    "application/vnd.cyclonedx+json",
+    "application/vnd.cyclonedx+xml",
+    "application/spdx+json",
+    "application/spdx+xml",
+    "text/spdx",
+    "application/vnd.syft+json",
+];
+
+/// media types of in-toto attestation layers; these are SBOMs only when their
+/// predicate-type annotation names one.
+const ATTESTATION_MEDIA_TYPES: &[&str] = &[
+    "application/vnd.in-toto+json",
+    "application/vnd.dsse.envelope.v1+json",
+];
+
+/// annotations carrying the predicate type of an attestation layer
+/// (BuildKit and cosign respectively).
+const PREDICATE_TYPE_ANNOTATIONS: &[&str] = &["in-toto.io/predicate-type", "predicateType"];
+
+/// file name suffixes SBOM generators write by convention.
+const SBOM_FILE_SUFFIXES: &[&str] = &[
+    ".cdx.json",
+    ".cdx.xml",
+    ".spdx.json",
+    ".spdx.xml",
+    ".spdx",
+    ".syft.json",
+    ".intoto.json",
+    ".intoto.jsonl",
+];
+
+/// file names SBOM generators write by convention.
+const SBOM_FILE_NAMES: &[&str] = &["bom.json", "bom.xml", "sbom.json", "sbom.xml"];
+
+/// how deep image indexes may nest before the walk gives up.
+const MAX_INDEX_DEPTH: usize = 8;
+
+/// a container image export or archive holding SBOM documents.
+pub(crate) enum Archive {
+    /// an OCI image layout directory.
+    Dir(PathBuf),
+    /// a tar archive (`docker save`, an OCI layout export), optionally gzip-compressed.
+    Tar {
+        path: PathBuf,
+        gzip: bool,
+        /// regular files by name, with the contents of those [`find_sboms`]
+        /// may read; filled in by one pass over the archive on first use.
+        members: Option<TarMembers>,
+    },
+    Zip(Box<zip::ZipArchive<File>>),
+}
+
+/// the files of a tar archive, mapped to their contents where kept.
+type TarMembers = BTreeMap<String, Option<Vec<u8>>>;
+
+/// an archive entry that holds an SBOM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Candidate {
+    /// `/`-separated path of the entry inside the archive.
+    pub name: String,
+    /// content digest, for entries found through an OCI descriptor.
+    pub digest: Option<String>,
+    /// why the entry was picked: its media type, or "file name".
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    #[serde(default)]
+    media_type: String,
+    digest: String,
+    #[serde(default)]
+    artifact_type: Option<String>,
+    #[serde(default)]
+    annotations: BTreeMap<String, String>,
+}
+
+/// the fields shared by OCI image indexes and image manifests.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    #[serde(default)]
+    artifact_type: Option<String>,
+    #[serde(default)]
+    config: Option<Descriptor>,
+    #[serde(default)]
+    manifests: Vec<Descriptor>,
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+}
+
+impl Archive {
+    /// opens `path` as an archive when it is a directory or a file starting
+    /// with a tar, gzip'd tar, or zip signature; returns `Ok(None)` otherwise.
+    pub(crate) fn open(path: &str) -> anyhow::Result<Option<Archive>> {
+        if path == "-" {
+            return Ok(None);
+        }
+        let path = Path::new(path);
+        if path.is_dir() {
+            if !path.join("oci-layout").is_file() {
+                bail!(
+                    "{} is a directory but not an OCI image layout (no oci-layout file)",
+                    path.display()
+                );
+            }
+            return Ok(Some(Archive::Dir(path.to_path_buf())));
+        }
+
+        let Ok(file) = File::open(path) else {
+            // let the regular loader report the open error.
+            return Ok(None);
+        };
+        let mut head = Vec::with_capacity(512);
+        file.take(512).read_to_end(&mut head)?;
+
+        if head.starts_with(b"PK\x03\x04") || head.starts_with(b"PK\x05\x06") {
+            let zip = zip::ZipArchive::new(File::open(path)?)
+                .with_context(|| format!("could not read zip archive: {}", path.display()))?;
+            return Ok(Some(Archive::Zip(Box::new(zip))));
+        }
+        if head.starts_with(b"\x1f\x8b") {
+            let mut inner = Vec::with_capacity(512);
+            GzDecoder::new(File::open(path)?)
+                .take(512)
+                .read_to_end(&mut inner)
+                .ok();
+            if is_tar_header(&inner) {
+                return Ok(Some(Archive::Tar {
+                    path: path.to_path_buf(),
+                    gzip: true,
+                    members: None,
+                }));
+            }
+            return Ok(None);
+        }
+        if is_tar_header(&head) {
+            return Ok(Some(Archive::Tar {
+                path: path.to_path_buf(),
+                gzip: false,
+                members: None,
+            }));
+        }
+        Ok(None)
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Archive::Dir(_) => "OCI layout",
+            Archive::Tar { .. } => "tar archive",
+            Archive::Zip(_) => "zip archive",
+        }
+    }
+
+    fn tar_reader(path: &Path, gzip: bool) -> io::Result<tar::Archive<Box<dyn Read>>> {
+        let file = File::open(path)?;
+        let reader: Box<dyn Read> = if gzip {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+        Ok(tar::Archive::new(reader))
+    }
+
+    /// the archive's files, scanning it the first time. a rescan re-decompresses
+    /// the whole tar, so the scan keeps the contents of every entry that
+    /// [`find_sboms`] may go on to read: `index.json`, files with an SBOM file
+    /// name, and the blobs that are not binary layer data.
+    fn tar_members<'a>(
+        members: &'a mut Option<TarMembers>,
+        path: &Path,
+        gzip: bool,
+    ) -> anyhow::Result<&'a TarMembers> {
+        if let Some(members) = members {
+            return Ok(members);
+        }
+        let mut scanned = TarMembers::new();
+        let mut tar = Self::tar_reader(path, gzip)?;
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = normalize_name(&entry.path()?.to_string_lossy());
+            let content = if name == "index.json"
+                || name.starts_with("blobs/")
+                || has_sbom_file_name(&name)
+            {
+                let mut content = Vec::new();
+                (&mut entry).take(512).read_to_end(&mut content)?;
+                if is_binary(&content) {
+                    None
+                } else {
+                    entry.read_to_end(&mut content)?;
+                    Some(content)
+                }
+            } else {
+                None
+            };
+            // a tar may repeat a name; the first entry is the one read.
+            scanned.entry(name).or_insert(content);
+        }
+        Ok(members.insert(scanned))
+    }
+
+    /// paths of the regular files in the archive.
+    fn names(&mut self) -> anyhow::Result<BTreeSet<String>> {
+        let mut names = BTreeSet::new();
+        match self {
+            Archive::Dir(root) => collect_dir_names(root, root, &mut names)?,
+            Archive::Tar {
+                path,
+                gzip,
+                members,
+            } => names.extend(Self::tar_members(members, path, *gzip)?.keys().cloned()),
+            Archive::Zip(zip) => {
+                for name in zip.file_names() {
+                    if !name.ends_with('/') {
+                        names.insert(normalize_name(name));
+                    }
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    /// reads one file out of the archive.
+    fn read(&mut self, name: &str) -> anyhow::Result<Vec<u8>> {
+        let mut content = Vec::new();
+        match self {
+            Archive::Dir(root) => {
+                content = std::fs::read(root.join(name))
+                    .with_context(|| format!("could not read {name}"))?;
+            }
+            Archive::Tar {
+                path,
+                gzip,
+                members,
+            } => match Self::tar_members(members, path, *gzip)?.get(name) {
+                Some(Some(kept)) => content.clone_from(kept),
+                // an entry the scan did not keep, e.g. one named with --entry.
+                Some(None) => {
+                    let mut tar = Self::tar_reader(path, *gzip)?;
+                    for entry in tar.entries()? {
+                        let mut entry = entry?;
+                        if normalize_name(&entry.path()?.to_string_lossy()) == name {
+                            entry.read_to_end(&mut content)?;
+                            break;
+                        }
+                    }
+                }
+                None => bail!("archive has no entry {name}"),
+            },
+            Archive::Zip(zip) => {
+                let index = (0..zip.len())
+                    .find(|&i| {
+                        zip.name_for_index(i)
+                            .is_some_and(|n| normalize_name(n) == name)
+                    })
+                    .ok_or_else(|| anyhow!("archive has no entry {name}"))?;
+                zip.by_index(index)?.read_to_end(&mut content)?;
+            }
+        }
+        Ok(content)
+    }
+
+    /// reads the JSON blob a descriptor points at, or `None` when the layout
+    /// does not carry it (e.g. a platform that was not exported).
+    fn read_blob_json<T: for<'de> Deserialize<'de>>(
+        &mut self,
+        names: &BTreeSet<String>,
+        digest: &str,
+    ) -> anyhow::Result<Option<T>> {
+        let name = blob_path(digest)?;
+        if !names.contains(&name) {
+            return Ok(None);
+        }
+        let content = self.read(&name)?;
+        let value = serde_json::from_slice(&content)
+            .with_context(|| format!("malformed OCI blob {name}"))?;
+        Ok(Some(value))
+    }
+}
+
+/// locates the SBOM documents in an archive: OCI descriptors with an SBOM
+/// media type (or an attestation layer annotated with an SBOM predicate), and
+/// files at conventional SBOM paths.
+pub(crate) fn find_sboms(archive: &mut Archive) -> anyhow::Result<Vec<Candidate>> {
+    let names = archive.names()?;
+    let mut candidates: Vec<Candidate> = Vec::new();
+
+    if names.contains("index.json") {
+        let index: Manifest = serde_json::from_slice(&archive.read("index.json")?)
+            .context("malformed OCI index.json")?;
+        walk_manifest(archive, &names, &index, 0, &mut candidates)?;
+        // a descriptor may name a blob the layout does not carry.
+        candidates.retain(|c| names.contains(&c.name));
+    }
+
+    for name in &names {
+        if name.starts_with("blobs/") || !has_sbom_file_name(name) {
+            continue;
+        }
+        candidates.push(Candidate {
+            name: name.clone(),
+            digest: None,
+            reason: "file name".to_string(),
+        });
+    }
+
+    let mut seen = BTreeSet::new();
+    candidates.retain(|c| seen.insert(c.name.clone()));
+    Ok(candidates)
+}
+
+fn walk_manifest(
+    archive: &mut Archive,
+    names: &BTreeSet<String>,
+    manifest: &Manifest,
+    depth: usize,
+    candidates: &mut Vec<Candidate>,
+) -> anyhow::Result<()> {
+    if depth > MAX_INDEX_DEPTH {
+        bail!("OCI image index nests more than {MAX_INDEX_DEPTH} levels deep");
+    }
+
+    // an artifact manifest whose type names an SBOM carries it as its layers,
+    // whatever media type the layers themselves use.
+    let artifact_type = manifest
+        .artifact_type
+        .as_deref()
+        .or_else(|| manifest.config.as_ref().map(|c| c.media_type.as_str()));
+    let sbom_artifact = artifact_type.is_some_and(|t| SBOM_MEDIA_TYPES.contains(&t));
+
+    for layer in &manifest.layers {
+        let reason = if SBOM_MEDIA_TYPES.contains(&layer.media_type.as_str()) {
+            Some(layer.media_type.clone())
+        } else if ATTESTATION_MEDIA_TYPES.contains(&layer.media_type.as_str()) {
+            PREDICATE_TYPE_ANNOTATIONS
+                .iter()
+                .filter_map(|key| layer.annotations.get(*key))
+                .find(|t| is_sbom_predicate_type(t))
+                .map(|t| format!("{} ({t})", layer.media_type))
+        } else if sbom_artifact {
+            artifact_type.map(str::to_string)
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            candidates.push(Candidate {
+                name: blob_path(&layer.digest)?,
+                digest: Some(layer.digest.clone()),
+                reason,
+            });
+        }
+    }
+
+    for child in &manifest.manifests {
+        if SBOM_MEDIA_TYPES.contains(&child.media_type.as_str()) {
+            candidates.push(Candidate {
+                name: blob_path(&child.digest)?,
+                digest: Some(child.digest.clone()),
+                reason: child.media_type.clone(),
+            });
+            continue;
+        }
+        let Some(mut nested) = archive.read_blob_json::<Manifest>(names, &child.digest)? else {
+            continue;
+        };
+        if nested.artifact_type.is_none() {
+            nested.artifact_type = child.artifact_type.clone();
+        }
+        walk_manifest(archive, names, &nested, depth + 1, candidates)?;
+    }
+    Ok(())
+}
+
+/// picks the SBOM to diff out of an archive and reads it.
+///
+/// `entry` names an archive path or an OCI digest; without it the archive
+/// must hold exactly one SBOM candidate.
+pub(crate) fn select_sbom(
+    archive: &mut Archive,
+    entry: Option<&str>,
+) -> anyhow::Result<(Candidate, Vec<u8>)> {
+    let candidates = find_sboms(archive)?;
+    let names = archive.names()?;
+
+    let chosen = match entry {
+        Some(entry) => {
+            let entry_name = normalize_name(entry);
+            match candidates
+                .iter()
+                .find(|c| c.name == entry_name || c.digest.as_deref() == Some(entry))
+            {
+                Some(candidate) => candidate.clone(),
+                // any file may be named explicitly, even one the heuristics skip.
+                None if names.contains(&entry_name) => Candidate {
+                    name: entry_name,
+                    digest: None,
+                    reason: "--entry".to_string(),
+                },
+                None => bail!(
+                    "{} has no entry '{entry}'; SBOM candidates: {}",
+                    archive.label(),
+                    describe(&candidates)
+                ),
+            }
+        }
+        None => match candidates.as_slice() {
+            [] => bail!(
+                "found no SBOM in {}; name the entry to use with --entry",
+                archive.label()
+            ),
+            [only] => only.clone(),
+            _ => bail!(
+                "found {} SBOM candidates in {}: {}; choose one with --entry",
+                candidates.len(),
+                archive.label(),
+                describe(&candidates)
+            ),
+        },
+    };
+
+    // only ever read a path the archive lists, never one built from a digest alone.
+    if !names.contains(&chosen.name) {
+        bail!("{} has no entry {}", archive.label(), chosen.name);
+    }
+    let content = archive.read(&chosen.name)?;
+    Ok((chosen, content))
+}
+
+fn describe(candidates: &[Candidate]) -> String {
+    if candidates.is_empty() {
+        return "<none>".to_string();
+    }
+    candidates
+        .iter()
+        .map(|c| format!("{} ({})", c.name, c.reason))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// the layout path of the blob `digest` names. the digest must have the
+/// `algorithm:hex` shape of the OCI spec (`[a-z0-9+._-]+:[a-f0-9]+`), so it
+/// cannot step out of `blobs/`.
+fn blob_path(digest: &str) -> anyhow::Result<String> {
+    let valid = digest.split_once(':').filter(|(algorithm, hex)| {
+        !algorithm.is_empty()
+            && algorithm
+                .bytes()
+                .all(|b| matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'+' | b'.' | b'_' | b'-'))
+            && !hex.is_empty()
+            && hex.bytes().all(|b| matches!(b, b'a'..=b'f' | b'0'..=b'9'))
+    });
+    match valid {
+        Some((algorithm, hex)) => Ok(format!("blobs/{algorithm}/{hex}")),
+        None => bail!("invalid OCI digest '{digest}'"),
+    }
+}
+
+fn has_sbom_file_name(name: &str) -> bool {
+    let file_name = name.rsplit('/').next().unwrap_or(name).to_ascii_lowercase();
+    SBOM_FILE_NAMES.contains(&file_name.as_str())
+        || SBOM_FILE_SUFFIXES.iter().any(|s| file_name.ends_with(s))
+}
+
+/// strips the `./` prefix `tar -C dir .` leaves on every entry.
+fn normalize_name(name: &str) -> String {
+    let mut name = name;
+    while let Some(rest) = name.strip_prefix("./") {
+        name = rest;
+    }
+    name.trim_start_matches('/').to_string()
+}
+
+/// whether the head of a blob is binary data, such as a compressed or tar
+/// image layer, rather than a JSON, XML, or tag-value document.
+fn is_binary(head: &[u8]) -> bool {
+    head.starts_with(b"\x1f\x8b") || head.contains(&0)
+}
+
+fn is_tar_header(block: &[u8]) -> bool {
+    block.len() >= 262 && &block[257..262] == b"ustar"
+}
+
+fn collect_dir_names(root: &Path, dir: &Path, names: &mut BTreeSet<String>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_dir_names(root, &entry.path(), names)?;
+        } else if file_type.is_file() {
+            let path = entry.path();
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let parts: Vec<_> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            names.insert(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const OCI_FIXTURE: &str = "../../tests/fixtures/oci-layout";
+    const SPDX_LAYER: &str =
+        "sha256:142d7824a86e9b7eeeb77a198bb318d0e390eadbd4f54c2aa1baeef73e47daea";
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sbom-diff-test-archive-{name}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// packs `files` into a tar, the way `tar -C dir -cf out.tar .` names entries.
+    fn write_tar<W: Write>(writer: W, files: &[(String, Vec<u8>)]) {
+        let mut builder = tar::Builder::new(writer);
+        for (name, content) in files {
+            let mut header = tar::Header::new_ustar();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, format!("./{name}"), &content[..])
+                .unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn oci_fixture_files() -> Vec<(String, Vec<u8>)> {
+        let mut archive = Archive::Dir(PathBuf::from(OCI_FIXTURE));
+        archive
+            .names()
+            .unwrap()
+            .into_iter()
+            .map(|name| {
+                let content = archive.read(&name).unwrap();
+                (name, content)
+            })
+            .collect()
+    }
+
+    fn open(path: &Path) -> Archive {
+        Archive::open(path.to_str().unwrap()).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_oci_layout_dir_finds_attested_sbom() {
+        let mut archive = open(Path::new(OCI_FIXTURE));
+        let candidates = find_sboms(&mut archive).unwrap();
+        assert_eq!(candidates.len(), 1, "{candidates:?}");
+        assert_eq!(candidates[0].digest.as_deref(), Some(SPDX_LAYER));
+        assert!(candidates[0].reason.contains("https://spdx.dev/Document"));
+
+        let (chosen, content) = select_sbom(&mut archive, None).unwrap();
+        assert_eq!(chosen, candidates[0]);
+        assert!(content.starts_with(b"{"));
+    }
+
+    #[test]
+    fn test_oci_layout_tar_matches_dir() {
+        let dir = temp_dir("oci-tar");
+        let path = dir.join("image.tar");
+        write_tar(File::create(&path).unwrap(), &oci_fixture_files());
+
+        let mut archive = open(&path);
+        assert!(matches!(archive, Archive::Tar { gzip: false, .. }));
+        let (chosen, content) = select_sbom(&mut archive, None).unwrap();
+        assert_eq!(chosen.digest.as_deref(), Some(SPDX_LAYER));
+
+        let mut dir_archive = open(Path::new(OCI_FIXTURE));
+        assert_eq!(content, select_sbom(&mut dir_archive, None).unwrap().1);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_tar_is_read_in_one_pass() {
+        let dir = temp_dir("one-pass");
+        let path = dir.join("image.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut files = oci_fixture_files();
+        files.push((
+            "blobs/sha256/00ff".to_string(),
+            b"\x1f\x8b\x08\x00".to_vec(),
+        ));
+        write_tar(encoder, &files);
+
+        let mut archive = open(&path);
+        archive.names().unwrap();
+        let Archive::Tar {
+            members: Some(members),
+            ..
+        } = &archive
+        else {
+            panic!("tar was not scanned");
+        };
+        assert_eq!(members["blobs/sha256/00ff"], None);
+        assert!(members["index.json"].is_some());
+
+        // everything find_sboms reads was kept by the first pass.
+        std::fs::remove_file(&path).unwrap();
+        let (chosen, _) = select_sbom(&mut archive, None).unwrap();
+        assert_eq!(chosen.digest.as_deref(), Some(SPDX_LAYER));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_gzip_tar_with_conventional_file_name() {
+        let dir = temp_dir("gzip");
+        let path = dir.join("sboms.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        write_tar(
+            encoder,
+            &[
+                ("usr/share/doc/readme.txt".to_string(), b"hello".to_vec()),
+                ("sbom/app.cdx.json".to_string(), b"{}".to_vec()),
+            ],
+        );
+
+        let mut archive = open(&path);
+        assert!(matches!(archive, Archive::Tar { gzip: true, .. }));
+        let (chosen, content) = select_sbom(&mut archive, None).unwrap();
+        assert_eq!(chosen.name, "sbom/app.cdx.json");
+        assert_eq!(chosen.reason, "file name");
+        assert_eq!(content, b"{}");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_ambiguous_zip_requires_entry() {
+        let dir = temp_dir("zip");
+        let path = dir.join("sboms.zip");
+        write_zip(
+            &path,
+            &[
+                ("app.cdx.json", b"{\"a\": 1}"),
+                ("docs/app.spdx.json", b"{\"b\": 2}"),
+                ("notes.txt", b"not an sbom"),
+            ],
+        );
+
+        let mut archive = open(&path);
+        let err = select_sbom(&mut archive, None).unwrap_err().to_string();
+        assert!(err.contains("found 2 SBOM candidates"), "got {err}");
+        assert!(err.contains("app.cdx.json") && err.contains("docs/app.spdx.json"));
+        assert!(err.contains("--entry"), "got {err}");
+
+        let (chosen, content) = select_sbom(&mut archive, Some("docs/app.spdx.json")).unwrap();
+        assert_eq!(chosen.name, "docs/app.spdx.json");
+        assert_eq!(content, b"{\"b\": 2}");
+
+        // an entry the heuristics skip can still be named explicitly.
+        let (chosen, _) = select_sbom(&mut archive, Some("notes.txt")).unwrap();
+        assert_eq!(chosen.reason, "--entry");
+
+        let err = select_sbom(&mut archive, Some("missing.json"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("no entry 'missing.json'"), "got {err}");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_entry_selects_by_digest() {
+        let mut archive = open(Path::new(OCI_FIXTURE));
+        let (chosen, _) = select_sbom(&mut archive, Some(SPDX_LAYER)).unwrap();
+        assert_eq!(chosen.digest.as_deref(), Some(SPDX_LAYER));
+    }
+
+    #[test]
+    fn test_archive_without_sbom_is_an_error() {
+        let dir = temp_dir("empty-zip");
+        let path = dir.join("nothing.zip");
+        write_zip(&path, &[("readme.txt", b"hello")]);
+
+        let err = select_sbom(&mut open(&path), None).unwrap_err().to_string();
+        assert!(err.contains("found no SBOM in zip archive"), "got {err}");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_plain_directory_is_rejected() {
+        let dir = temp_dir("plain-dir");
+        let err = Archive::open(dir.to_str().unwrap())
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("not an OCI image layout"), "got {err}");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_sbom_documents_are_not_archives() {
+        for fixture in ["old.json", "old.spdx", "golden-old.cdx.xml", "missing.json"] {
+            let path = format!("../../tests/fixtures/{fixture}");
+            assert!(Archive::open(&path).unwrap().is_none(), "{fixture}");
+        }
+        assert!(Archive::open("-").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_has_sbom_file_name() {
+        assert!(has_sbom_file_name("a/b/App.CDX.json"));
+        assert!(has_sbom_file_name("sbom.json"));
+        assert!(has_sbom_file_name("x/bom.xml"));
+        assert!(has_sbom_file_name("build.intoto.jsonl"));
+        assert!(!has_sbom_file_name("package.json"));
+        assert!(!has_sbom_file_name("manifest.json"));
+    }
+
+    #[test]
+    fn test_normalize_name_and_blob_path() {
+        assert_eq!(normalize_name("./././index.json"), "index.json");
+        assert_eq!(normalize_name("/blobs/sha256/ab"), "blobs/sha256/ab");
+        assert_eq!(blob_path("sha256:abcd").unwrap(), "blobs/sha256/abcd");
+        assert_eq!(
+            blob_path("sha512+b3.x_y-z:09af").unwrap(),
+            "blobs/sha512+b3.x_y-z/09af"
+        );
+        for digest in [
+            "sha256:../../../etc/passwd",
+            "../sha256:abcd",
+            "sha256:ABCD",
+            "sha256:",
+            ":abcd",
+            "abcd",
+        ] {
+            assert!(blob_path(digest).is_err(), "{digest}");
+        }
+    }
+
+    #[test]
+    fn test_digest_escaping_the_layout_is_rejected() {
+        let dir = temp_dir("traversal");
+        let layout = dir.join("layout");
+        std::fs::create_dir_all(layout.join("blobs/sha256")).unwrap();
+        std::fs::write(
+            layout.join("oci-layout"),
+            br#"{"imageLayoutVersion": "1.0.0"}"#,
+        )
+        .unwrap();
+        // a file outside the layout that the digest below would point at.
+        std::fs::write(dir.join("secret.cdx.json"), b"{}").unwrap();
+
+        // a nested manifest is read, an SBOM descriptor becomes a candidate.
+        for media_type in [
+            "application/vnd.oci.image.manifest.v1+json",
+            "application/vnd.cyclonedx+json",
+        ] {
+            let index = format!(
+                r#"{{"schemaVersion": 2, "manifests": [{{"mediaType": "{media_type}",
+                    "digest": "sha256:../../secret.cdx.json", "size": 2}}]}}"#
+            );
+            std::fs::write(layout.join("index.json"), index).unwrap();
+
+            let err = select_sbom(&mut open(&layout), None)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("invalid OCI digest"), "got {err}");
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_descriptor_for_a_missing_blob_is_not_a_candidate() {
+        let dir = temp_dir("missing-blob");
+        std::fs::create_dir_all(dir.join("blobs/sha256")).unwrap();
+        std::fs::write(
+            dir.join("oci-layout"),
+            br#"{"imageLayoutVersion": "1.0.0"}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("index.json"),
+            br#"{"schemaVersion": 2, "manifests": [{"mediaType": "application/spdx+json",
+                "digest": "sha256:abcd", "size": 2}]}"#,
+        )
+        .unwrap();
+
+        let err = select_sbom(&mut open(&dir), None).unwrap_err().to_string();
+        assert!(err.contains("found no SBOM"), "got {err}");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
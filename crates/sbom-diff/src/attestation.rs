@@ -0,0 +1,626 @@
+use anyhow::{anyhow, bail};
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use ed25519_dalek::pkcs8::DecodePublicKey;
+use ed25519_dalek::Verifier;
+use sbom_model::AttestationSubject;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// the DSSE payload type every in-toto statement is signed under.
+const IN_TOTO_PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+/// predicate type prefixes for SBOM predicates; producers append a spec
+/// version (`https://cyclonedx.org/bom/v1.5`, `https://spdx.dev/Document/v2.3`).
+const CYCLONEDX_PREDICATE_PREFIX: &str = "https://cyclonedx.org/bom";
+const SPDX_PREDICATE_PREFIXES: &[&str] =
+    &["https://spdx.dev/Document", "https://spdx.org/Document"];
+const SYFT_PREDICATE_PREFIX: &str = "https://syft.dev/bom";
+
+/// padding is optional in DSSE payloads, and some producers use the URL-safe alphabet.
+const BASE64_CONFIG: GeneralPurposeConfig =
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const BASE64_STANDARD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, BASE64_CONFIG);
+const BASE64_URL_SAFE: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, BASE64_CONFIG);
+
+/// SBOM family named by an attestation's `predicateType`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PredicateKind {
+    Cyclonedx,
+    Spdx,
+    Syft,
+}
+
+impl PredicateKind {
+    fn from_predicate_type(predicate_type: &str) -> Option<Self> {
+        if predicate_type.starts_with(CYCLONEDX_PREDICATE_PREFIX) {
+            Some(PredicateKind::Cyclonedx)
+        } else if SPDX_PREDICATE_PREFIXES
+            .iter()
+            .any(|p| predicate_type.starts_with(p))
+        {
+            Some(PredicateKind::Spdx)
+        } else if predicate_type.starts_with(SYFT_PREDICATE_PREFIX) {
+            Some(PredicateKind::Syft)
+        } else {
+            None
+        }
+    }
+}
+
+/// true when an in-toto `predicateType` names a CycloneDX or SPDX document.
+pub(crate) fn is_sbom_predicate_type(predicate_type: &str) -> bool {
+    PredicateKind::from_predicate_type(predicate_type).is_some()
+}
+
+/// the SBOM predicate of an in-toto statement, with the subjects it attests.
+#[derive(Debug)]
+pub(crate) struct Attestation {
+    pub predicate_type: String,
+    pub kind: PredicateKind,
+    /// the predicate document, re-serialized when it was embedded as JSON.
+    pub predicate: Vec<u8>,
+    /// false when the predicate was carried as a string (e.g. tag-value or XML).
+    pub predicate_is_json: bool,
+    pub subjects: Vec<AttestationSubject>,
+    /// the envelope the statement was signed in; `None` for a bare statement.
+    pub envelope: Option<SignedPayload>,
+}
+
+/// the exact bytes a DSSE envelope's signatures cover.
+#[derive(Debug)]
+pub(crate) struct SignedPayload {
+    payload_type: String,
+    payload: Vec<u8>,
+    /// decoded signatures; entries that are not valid base64 are dropped.
+    signatures: Vec<Vec<u8>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope {
+    payload_type: String,
+    payload: String,
+    #[serde(default)]
+    signatures: Vec<EnvelopeSignature>,
+}
+
+#[derive(Deserialize)]
+struct EnvelopeSignature {
+    sig: String,
+}
+
+/// why an input failed `--verify-key`.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum SignatureError {
+    #[error("input is not a signed DSSE envelope")]
+    NotSigned,
+    #[error("DSSE envelope carries no signatures")]
+    NoSignatures,
+    #[error("no signature on the DSSE envelope verifies against the key")]
+    Invalid,
+}
+
+/// a public key attested SBOMs must be signed with.
+#[derive(Debug)]
+pub(crate) enum VerifyingKey {
+    EcdsaP256(p256::ecdsa::VerifyingKey),
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+impl VerifyingKey {
+    /// parses a PEM `PUBLIC KEY` (SubjectPublicKeyInfo) block.
+    pub(crate) fn from_pem(pem: &str) -> anyhow::Result<Self> {
+        if let Ok(key) = ed25519_dalek::VerifyingKey::from_public_key_pem(pem) {
+            return Ok(VerifyingKey::Ed25519(key));
+        }
+        if let Ok(key) = p256::ecdsa::VerifyingKey::from_public_key_pem(pem) {
+            return Ok(VerifyingKey::EcdsaP256(key));
+        }
+        Err(anyhow!(
+            "unsupported public key: expected an ECDSA P-256 or Ed25519 key in PEM \
+             (\"BEGIN PUBLIC KEY\") form"
+        ))
+    }
+
+    fn verifies(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            // cosign and most DSSE signers emit DER; accept the fixed-size form too.
+            VerifyingKey::EcdsaP256(key) => p256::ecdsa::Signature::from_der(signature)
+                .or_else(|_| p256::ecdsa::Signature::from_slice(signature))
+                .is_ok_and(|sig| key.verify(message, &sig).is_ok()),
+            VerifyingKey::Ed25519(key) => ed25519_dalek::Signature::from_slice(signature)
+                .is_ok_and(|sig| key.verify(message, &sig).is_ok()),
+        }
+    }
+}
+
+impl Attestation {
+    /// checks that at least one envelope signature verifies against `key`.
+    pub(crate) fn verify(&self, key: &VerifyingKey) -> Result<(), SignatureError> {
+        let envelope = self.envelope.as_ref().ok_or(SignatureError::NotSigned)?;
+        if envelope.signatures.is_empty() {
+            return Err(SignatureError::NoSignatures);
+        }
+        let message = pae(&envelope.payload_type, &envelope.payload);
+        if envelope
+            .signatures
+            .iter()
+            .any(|sig| key.verifies(&message, sig))
+        {
+            Ok(())
+        } else {
+            Err(SignatureError::Invalid)
+        }
+    }
+}
+
+/// DSSE pre-authentication encoding: the message every envelope signature covers.
+fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut message = format!(
+        "DSSEv1 {} {payload_type} {} ",
+        payload_type.len(),
+        payload.len()
+    )
+    .into_bytes();
+    message.extend_from_slice(payload);
+    message
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Statement {
+    #[serde(default)]
+    subject: Vec<Subject>,
+    predicate_type: String,
+    #[serde(default)]
+    predicate: Value,
+}
+
+#[derive(Deserialize)]
+struct Subject {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    digest: BTreeMap<String, String>,
+}
+
+/// unwraps an in-toto attestation carrying an SBOM.
+///
+/// accepts a DSSE envelope, a Sigstore bundle holding one (`dsseEnvelope`), a
+/// bare in-toto statement, or a JSON Lines bundle of any of these. returns
+/// `Ok(None)` when the first document is none of them, so the input can be
+/// handed to the SBOM parsers unchanged.
+pub(crate) fn unwrap_attestation(content: &[u8]) -> anyhow::Result<Option<Attestation>> {
+    let mut sboms = Vec::new();
+    let mut other_types = Vec::new();
+
+    let stream = serde_json::Deserializer::from_slice(content).into_iter::<Value>();
+    for (index, document) in stream.enumerate() {
+        let line = index + 1;
+        let document = match document {
+            Ok(document) => document,
+            Err(_) if index == 0 => return Ok(None),
+            Err(e) => bail!("attestation bundle entry {line} is not valid JSON: {e}"),
+        };
+        // only a bundle needs to say which entry was at fault.
+        let statement = statement_of(document).map_err(|e| match index {
+            0 => e,
+            _ => e.context(format!("attestation bundle entry {line}")),
+        })?;
+        let Some((statement, envelope)) = statement else {
+            if index == 0 {
+                return Ok(None);
+            }
+            bail!("attestation bundle entry {line} is not a DSSE envelope or in-toto statement");
+        };
+        match PredicateKind::from_predicate_type(&statement.predicate_type) {
+            Some(kind) => sboms.push((kind, statement, envelope)),
+            None => other_types.push(statement.predicate_type),
+        }
+    }
+
+    let (kind, statement, envelope) = match sboms.len() {
+        0 if other_types.is_empty() => return Ok(None),
+        0 => {
+            return Err(anyhow!(
+                "attestation carries no SBOM predicate (expected a CycloneDX or SPDX \
+                 predicateType); found: {}",
+                other_types.join(", ")
+            ))
+        }
+        1 => sboms.pop().expect("one SBOM statement"),
+        n => {
+            let types: Vec<&str> = sboms
+                .iter()
+                .map(|(_, s, _)| s.predicate_type.as_str())
+                .collect();
+            return Err(anyhow!(
+                "attestation bundle carries {n} SBOM predicates ({}); extract the one to diff",
+                types.join(", ")
+            ));
+        }
+    };
+
+    let (predicate, predicate_is_json) = match statement.predicate {
+        Value::String(text) => (text.into_bytes(), false),
+        Value::Object(_) => (serde_json::to_vec(&statement.predicate)?, true),
+        _ => bail!(
+            "attestation predicate ({}) is not an SBOM document",
+            statement.predicate_type
+        ),
+    };
+
+    let subjects = statement
+        .subject
+        .into_iter()
+        .map(|s| AttestationSubject {
+            name: s.name,
+            digests: s
+                .digest
+                .into_iter()
+                .map(|(alg, digest)| (alg.to_lowercase(), digest.to_lowercase()))
+                .collect(),
+        })
+        .collect();
+
+    Ok(Some(Attestation {
+        predicate_type: statement.predicate_type,
+        kind,
+        predicate,
+        predicate_is_json,
+        subjects,
+        envelope,
+    }))
+}
+
+/// resolves one JSON document to the in-toto statement it holds, if any,
+/// along with the envelope it was signed in.
+fn statement_of(mut document: Value) -> anyhow::Result<Option<(Statement, Option<SignedPayload>)>> {
+    let Some(object) = document.as_object_mut() else {
+        return Ok(None);
+    };
+
+    // Sigstore bundles nest the envelope under `dsseEnvelope`.
+    if let Some(envelope) = object.remove("dsseEnvelope") {
+        let (statement, signed) = statement_from_envelope(envelope)?;
+        return Ok(Some((statement, Some(signed))));
+    }
+    if object.contains_key("payloadType") {
+        let (statement, signed) = statement_from_envelope(document)?;
+        return Ok(Some((statement, Some(signed))));
+    }
+    if object.contains_key("predicateType") {
+        let statement = serde_json::from_value(document)
+            .map_err(|e| anyhow!("malformed in-toto statement: {e}"))?;
+        return Ok(Some((statement, None)));
+    }
+    Ok(None)
+}
+
+fn statement_from_envelope(envelope: Value) -> anyhow::Result<(Statement, SignedPayload)> {
+    let envelope: Envelope =
+        serde_json::from_value(envelope).map_err(|e| anyhow!("malformed DSSE envelope: {e}"))?;
+    if envelope.payload_type != IN_TOTO_PAYLOAD_TYPE {
+        bail!(
+            "unsupported DSSE payload type '{}' (expected '{IN_TOTO_PAYLOAD_TYPE}')",
+            envelope.payload_type
+        );
+    }
+    let payload = decode_base64(&envelope.payload)
+        .map_err(|e| anyhow!("DSSE payload is not valid base64: {e}"))?;
+    let statement = serde_json::from_slice(&payload)
+        .map_err(|e| anyhow!("malformed in-toto statement: {e}"))?;
+    let signatures = envelope
+        .signatures
+        .iter()
+        .filter_map(|s| decode_base64(&s.sig).ok())
+        .collect();
+    let signed = SignedPayload {
+        payload_type: envelope.payload_type,
+        payload,
+        signatures,
+    };
+    Ok((statement, signed))
+}
+
+fn decode_base64(text: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    BASE64_STANDARD
+        .decode(text)
+        .or_else(|_| BASE64_URL_SAFE.decode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(statement: &Value) -> String {
+        BASE64_STANDARD.encode(serde_json::to_vec(statement).unwrap())
+    }
+
+    fn cyclonedx_statement() -> Value {
+        serde_json::json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [{"name": "ghcr.io/acme/app", "digest": {"sha256": "ABC123"}}],
+            "predicateType": "https://cyclonedx.org/bom",
+            "predicate": {"bomFormat": "CycloneDX", "specVersion": "1.5", "components": []}
+        })
+    }
+
+    fn provenance_statement() -> Value {
+        serde_json::json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [{"name": "app", "digest": {"sha256": "abc123"}}],
+            "predicateType": "https://slsa.dev/provenance/v1",
+            "predicate": {"buildDefinition": {}}
+        })
+    }
+
+    fn envelope(statement: &Value) -> Value {
+        serde_json::json!({
+            "payloadType": IN_TOTO_PAYLOAD_TYPE,
+            "payload": encode(statement),
+            "signatures": []
+        })
+    }
+
+    #[test]
+    fn test_dsse_envelope_unwrapped() {
+        let content = serde_json::to_vec(&envelope(&cyclonedx_statement())).unwrap();
+        let att = unwrap_attestation(&content).unwrap().unwrap();
+        assert_eq!(att.kind, PredicateKind::Cyclonedx);
+        assert_eq!(att.predicate_type, "https://cyclonedx.org/bom");
+        assert!(att.predicate_is_json);
+        let predicate: Value = serde_json::from_slice(&att.predicate).unwrap();
+        assert_eq!(predicate["bomFormat"], "CycloneDX");
+        assert_eq!(att.subjects.len(), 1);
+        assert_eq!(att.subjects[0].name, "ghcr.io/acme/app");
+        assert_eq!(att.subjects[0].digests["sha256"], "abc123");
+    }
+
+    #[test]
+    fn test_bare_statement_unwrapped() {
+        let content = serde_json::to_vec(&cyclonedx_statement()).unwrap();
+        let att = unwrap_attestation(&content).unwrap().unwrap();
+        assert_eq!(att.kind, PredicateKind::Cyclonedx);
+    }
+
+    #[test]
+    fn test_sigstore_bundle_unwrapped() {
+        let bundle = serde_json::json!({
+            "mediaType": "application/vnd.dev.sigstore.bundle.v0.3+json",
+            "dsseEnvelope": envelope(&cyclonedx_statement())
+        });
+        let content = serde_json::to_vec(&bundle).unwrap();
+        let att = unwrap_attestation(&content).unwrap().unwrap();
+        assert_eq!(att.kind, PredicateKind::Cyclonedx);
+    }
+
+    #[test]
+    fn test_url_safe_unpadded_payload_decoded() {
+        let payload = GeneralPurpose::new(
+            &alphabet::URL_SAFE,
+            GeneralPurposeConfig::new().with_encode_padding(false),
+        )
+        .encode(serde_json::to_vec(&cyclonedx_statement()).unwrap());
+        let content = serde_json::to_vec(&serde_json::json!({
+            "payloadType": IN_TOTO_PAYLOAD_TYPE,
+            "payload": payload,
+        }))
+        .unwrap();
+        assert!(unwrap_attestation(&content).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_json_lines_bundle_picks_sbom_predicate() {
+        let mut content = serde_json::to_vec(&envelope(&provenance_statement())).unwrap();
+        content.push(b'\n');
+        let spdx = serde_json::json!({
+            "_type": "https://in-toto.io/Statement/v0.1",
+            "subject": [],
+            "predicateType": "https://spdx.dev/Document/v2.3",
+            "predicate": {"spdxVersion": "SPDX-2.3"}
+        });
+        content.extend(serde_json::to_vec(&envelope(&spdx)).unwrap());
+        content.push(b'\n');
+
+        let att = unwrap_attestation(&content).unwrap().unwrap();
+        assert_eq!(att.kind, PredicateKind::Spdx);
+        assert_eq!(att.predicate_type, "https://spdx.dev/Document/v2.3");
+    }
+
+    #[test]
+    fn test_bundle_with_two_sbom_predicates_rejected() {
+        let mut content = serde_json::to_vec(&cyclonedx_statement()).unwrap();
+        content.push(b'\n');
+        content.extend(serde_json::to_vec(&cyclonedx_statement()).unwrap());
+
+        let err = unwrap_attestation(&content).unwrap_err().to_string();
+        assert!(err.contains("2 SBOM predicates"), "got {err}");
+    }
+
+    #[test]
+    fn test_attestation_without_sbom_predicate_rejected() {
+        let content = serde_json::to_vec(&envelope(&provenance_statement())).unwrap();
+        let err = unwrap_attestation(&content).unwrap_err().to_string();
+        assert!(err.contains("no SBOM predicate"), "got {err}");
+        assert!(err.contains("https://slsa.dev/provenance/v1"), "got {err}");
+    }
+
+    #[test]
+    fn test_non_in_toto_payload_type_rejected() {
+        let content = serde_json::to_vec(&serde_json::json!({
+            "payloadType": "application/vnd.cyclonedx+json",
+            "payload": "e30=",
+        }))
+        .unwrap();
+        let err = unwrap_attestation(&content).unwrap_err().to_string();
+        assert!(err.contains("unsupported DSSE payload type"), "got {err}");
+    }
+
+    #[test]
+    fn test_invalid_base64_payload_rejected() {
+        let content = serde_json::to_vec(&serde_json::json!({
+            "payloadType": IN_TOTO_PAYLOAD_TYPE,
+            "payload": "not base64!",
+        }))
+        .unwrap();
+        let err = format!("{:#}", unwrap_attestation(&content).unwrap_err());
+        assert!(err.contains("not valid base64"), "got {err}");
+    }
+
+    #[test]
+    fn test_string_predicate_kept_verbatim() {
+        let statement = serde_json::json!({
+            "subject": [],
+            "predicateType": "https://spdx.dev/Document",
+            "predicate": "SPDXVersion: SPDX-2.3\n"
+        });
+        let content = serde_json::to_vec(&statement).unwrap();
+        let att = unwrap_attestation(&content).unwrap().unwrap();
+        assert!(!att.predicate_is_json);
+        assert_eq!(att.predicate, b"SPDXVersion: SPDX-2.3\n");
+    }
+
+    #[test]
+    fn test_plain_sbom_is_not_an_attestation() {
+        let content = br#"{"bomFormat": "CycloneDX", "specVersion": "1.4"}"#;
+        assert!(unwrap_attestation(content).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_non_json_is_not_an_attestation() {
+        assert!(unwrap_attestation(b"SPDXVersion: SPDX-2.3\n")
+            .unwrap()
+            .is_none());
+    }
+
+    fn signed_envelope(sign: impl Fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+        let payload = serde_json::to_vec(&cyclonedx_statement()).unwrap();
+        let sig = sign(&pae(IN_TOTO_PAYLOAD_TYPE, &payload));
+        serde_json::to_vec(&serde_json::json!({
+            "payloadType": IN_TOTO_PAYLOAD_TYPE,
+            "payload": BASE64_STANDARD.encode(&payload),
+            "signatures": [{"keyid": "", "sig": BASE64_STANDARD.encode(sig)}]
+        }))
+        .unwrap()
+    }
+
+    fn ed25519_keys(seed: u8) -> (ed25519_dalek::SigningKey, VerifyingKey) {
+        use ed25519_dalek::pkcs8::{spki::der::pem::LineEnding, EncodePublicKey};
+        let signing = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]);
+        let pem = signing
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        (signing, VerifyingKey::from_pem(&pem).unwrap())
+    }
+
+    fn p256_keys(seed: u8) -> (p256::ecdsa::SigningKey, VerifyingKey) {
+        use p256::pkcs8::{EncodePublicKey, LineEnding};
+        let signing = p256::ecdsa::SigningKey::from_slice(&[seed; 32]).unwrap();
+        let pem = signing
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        (signing, VerifyingKey::from_pem(&pem).unwrap())
+    }
+
+    fn attestation_of(content: &[u8]) -> Attestation {
+        unwrap_attestation(content).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_pae_matches_dsse_spec() {
+        assert_eq!(
+            pae("http://example.com/HelloWorld", b"hello world"),
+            b"DSSEv1 29 http://example.com/HelloWorld 11 hello world"
+        );
+    }
+
+    #[test]
+    fn test_ed25519_signature_verifies() {
+        use ed25519_dalek::Signer;
+        let (signing, key) = ed25519_keys(7);
+        let content = signed_envelope(|msg| signing.sign(msg).to_bytes().to_vec());
+        assert!(matches!(key, VerifyingKey::Ed25519(_)));
+        assert!(attestation_of(&content).verify(&key).is_ok());
+    }
+
+    #[test]
+    fn test_p256_der_and_fixed_size_signatures_verify() {
+        use p256::ecdsa::signature::Signer;
+        let (signing, key) = p256_keys(7);
+        assert!(matches!(key, VerifyingKey::EcdsaP256(_)));
+
+        let der = signed_envelope(|msg| {
+            let sig: p256::ecdsa::Signature = signing.sign(msg);
+            sig.to_der().as_bytes().to_vec()
+        });
+        assert!(attestation_of(&der).verify(&key).is_ok());
+
+        let fixed = signed_envelope(|msg| {
+            let sig: p256::ecdsa::Signature = signing.sign(msg);
+            sig.to_bytes().to_vec()
+        });
+        assert!(attestation_of(&fixed).verify(&key).is_ok());
+    }
+
+    #[test]
+    fn test_signature_from_another_key_is_invalid() {
+        use ed25519_dalek::Signer;
+        let (signing, _) = ed25519_keys(7);
+        let (_, other) = ed25519_keys(8);
+        let (_, p256_key) = p256_keys(7);
+        let content = signed_envelope(|msg| signing.sign(msg).to_bytes().to_vec());
+        let att = attestation_of(&content);
+        assert!(matches!(att.verify(&other), Err(SignatureError::Invalid)));
+        assert!(matches!(
+            att.verify(&p256_key),
+            Err(SignatureError::Invalid)
+        ));
+    }
+
+    #[test]
+    fn test_tampered_payload_is_invalid() {
+        use ed25519_dalek::Signer;
+        let (signing, key) = ed25519_keys(7);
+        let content = signed_envelope(|msg| {
+            // sign a different payload than the envelope carries.
+            let mut tampered = msg.to_vec();
+            tampered.push(b' ');
+            signing.sign(&tampered).to_bytes().to_vec()
+        });
+        assert!(matches!(
+            attestation_of(&content).verify(&key),
+            Err(SignatureError::Invalid)
+        ));
+    }
+
+    #[test]
+    fn test_unsigned_envelope_and_bare_statement_are_rejected() {
+        let (_, key) = ed25519_keys(7);
+        let unsigned = serde_json::to_vec(&envelope(&cyclonedx_statement())).unwrap();
+        assert!(matches!(
+            attestation_of(&unsigned).verify(&key),
+            Err(SignatureError::NoSignatures)
+        ));
+
+        let bare = serde_json::to_vec(&cyclonedx_statement()).unwrap();
+        assert!(matches!(
+            attestation_of(&bare).verify(&key),
+            Err(SignatureError::NotSigned)
+        ));
+    }
+
+    #[test]
+    fn test_verifying_key_rejects_unsupported_pem() {
+        let err =
+            VerifyingKey::from_pem("-----BEGIN PUBLIC KEY-----\nAAAA\n-----END PUBLIC KEY-----\n")
+                .unwrap_err()
+                .to_string();
+        assert!(err.contains("ECDSA P-256 or Ed25519"), "got {err}");
+    }
+}
@@ -1,8 +1,10 @@
+use crate::attestation::{unwrap_attestation, PredicateKind, SignatureError, VerifyingKey};
 use anyhow::{anyhow, Context};
 use clap::ValueEnum;
 use sbom_model::Sbom;
 use sbom_model_cyclonedx::CycloneDxReader;
 use sbom_model_spdx::SpdxReader;
+use sbom_model_syft::SyftReader;
 use std::fs::File;
 use std::io::{self, Read};
 
@@ -14,6 +16,7 @@ pub enum Format {
     Spdx,
     SpdxTv,
     SpdxXml,
+    Syft,
 }
 
 /// format detected by content-based heuristics.
@@ -24,6 +27,7 @@ enum DetectedFormat {
     SpdxJson,
     SpdxTv,
     SpdxXml,
+    SyftJson,
     Unknown,
 }
 
@@ -35,6 +39,7 @@ impl DetectedFormat {
             DetectedFormat::SpdxJson => "SPDX JSON",
             DetectedFormat::SpdxTv => "SPDX tag-value",
             DetectedFormat::SpdxXml => "SPDX XML",
+            DetectedFormat::SyftJson => "Syft JSON",
             DetectedFormat::Unknown => "unknown",
         }
     }
@@ -43,7 +48,8 @@ impl DetectedFormat {
 /// pre-scan the first bytes of `content` for well-known SBOM format markers.
 ///
 /// the scan window is capped at 8 KiB — every supported format places its
-/// identifying marker near the top of the document.
+/// identifying marker near the top of the document, except Syft JSON, whose
+/// `schema` object comes last and is looked for in the final 8 KiB as well.
 fn detect_format(content: &[u8]) -> DetectedFormat {
     let window = &content[..content.len().min(8192)];
 
@@ -72,6 +78,10 @@ fn detect_format(content: &[u8]) -> DetectedFormat {
     if find_subsequence(window, b"\"spdxVersion\"").is_some() {
         return DetectedFormat::SpdxJson;
     }
+    let tail = &content[content.len().saturating_sub(8192)..];
+    if trimmed.starts_with(b"{") && (has_syft_schema_url(window) || has_syft_schema_url(tail)) {
+        return DetectedFormat::SyftJson;
+    }
 
     // tag-value: lines starting with SPDXVersion:
     for line in window.split(|&b| b == b'\n') {
@@ -89,6 +99,13 @@ fn detect_format(content: &[u8]) -> DetectedFormat {
     DetectedFormat::Unknown
 }
 
+/// whether `data` holds the `schema.url` syft stamps on its JSON output, e.g.
+/// `https://raw.githubusercontent.com/anchore/syft/main/schema/json/schema-16.0.18.json`.
+fn has_syft_schema_url(data: &[u8]) -> bool {
+    find_subsequence(data, b"anchore/syft").is_some()
+        && find_subsequence(data, b"/schema/json/schema-").is_some()
+}
+
 /// strip a leading UTF-8 BOM and ASCII whitespace from a byte slice.
 fn strip_bom_and_whitespace(data: &[u8]) -> &[u8] {
     let data = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(data);
@@ -123,6 +140,31 @@ fn contains_element(haystack: &[u8], marker: &[u8]) -> bool {
     false
 }
 
+/// true when the window carries a DSSE envelope or in-toto statement key.
+///
+/// an envelope that opens with a large `payload` pushes every other key past
+/// the window, so one whose first key is `payload` is also taken when its
+/// `signatures` show up in the final 8 KiB.
+///
+/// a match only means the input is worth unwrapping; [`unwrap_attestation`]
+/// makes the actual call.
+fn looks_like_attestation(content: &[u8]) -> bool {
+    let window = &content[..content.len().min(8192)];
+    let Some(body) = strip_bom_and_whitespace(window).strip_prefix(b"{") else {
+        return false;
+    };
+    let tail = &content[content.len().saturating_sub(8192)..];
+    [
+        &b"\"payloadType\""[..],
+        b"\"predicateType\"",
+        b"\"dsseEnvelope\"",
+    ]
+    .iter()
+    .any(|marker| find_subsequence(window, marker).is_some())
+        || (trim_ascii_start(body).starts_with(b"\"payload\"")
+            && find_subsequence(tail, b"\"signatures\"").is_some())
+}
+
 /// try a single parser, returning `Ok(sbom)` or appending to `errors`.
 fn try_parse(
     content: &[u8],
@@ -139,18 +181,39 @@ fn try_parse(
     }
 }
 
+/// inputs at least this large go through the streaming JSON readers, which
+/// map components as they are read instead of first building the whole
+/// document model; smaller ones keep the stricter, schema-checked path.
+const STREAMING_THRESHOLD: usize = 32 * 1024 * 1024;
+
+fn read_cyclonedx_json(content: &[u8]) -> Result<Sbom, sbom_model_cyclonedx::Error> {
+    if content.len() >= STREAMING_THRESHOLD {
+        CycloneDxReader::read_json_streaming(content)
+    } else {
+        CycloneDxReader::read_json(content)
+    }
+}
+
+fn read_spdx_json(content: &[u8]) -> Result<Sbom, sbom_model_spdx::Error> {
+    if content.len() >= STREAMING_THRESHOLD {
+        SpdxReader::read_json_streaming(content)
+    } else {
+        SpdxReader::read_json(content)
+    }
+}
+
 type ParseFn = fn(&[u8]) -> Result<Sbom, Box<dyn std::fmt::Display>>;
 
-/// the five parsers in a fixed order, used for fallback iteration.
+/// the six parsers in a fixed order, used for fallback iteration.
 const ALL_PARSERS: &[(&str, ParseFn)] = &[
     ("cyclonedx json", |c| {
-        CycloneDxReader::read_json(c).map_err(|e| Box::new(e) as _)
+        read_cyclonedx_json(c).map_err(|e| Box::new(e) as _)
     }),
     ("cyclonedx xml", |c| {
         CycloneDxReader::read_xml(c).map_err(|e| Box::new(e) as _)
     }),
     ("spdx json", |c| {
-        SpdxReader::read_json(c).map_err(|e| Box::new(e) as _)
+        read_spdx_json(c).map_err(|e| Box::new(e) as _)
     }),
     ("spdx tag-value", |c| {
         SpdxReader::read_tag_value(c).map_err(|e| Box::new(e) as _)
@@ -158,9 +221,23 @@ const ALL_PARSERS: &[(&str, ParseFn)] = &[
     ("spdx xml", |c| {
         SpdxReader::read_xml(c).map_err(|e| Box::new(e) as _)
     }),
+    ("syft json", |c| {
+        SyftReader::read_json(c).map_err(|e| Box::new(e) as _)
+    }),
 ];
 
 pub fn load_sbom(path: &str, format: Format) -> anyhow::Result<Sbom> {
+    load(path, format, None)
+}
+
+/// like [`load_sbom`], but the input must be a DSSE envelope carrying a
+/// signature that verifies against `key`. a missing or invalid signature is
+/// reported as a [`SignatureError`].
+pub fn load_sbom_verified(path: &str, format: Format, key: &VerifyingKey) -> anyhow::Result<Sbom> {
+    load(path, format, Some(key))
+}
+
+fn load(path: &str, format: Format, verify_key: Option<&VerifyingKey>) -> anyhow::Result<Sbom> {
     let mut content = Vec::new();
     if path == "-" {
         io::stdin().read_to_end(&mut content)?;
@@ -168,7 +245,17 @@ pub fn load_sbom(path: &str, format: Format) -> anyhow::Result<Sbom> {
         let mut file = File::open(path).context(format!("could not open file: {}", path))?;
         file.read_to_end(&mut content)?;
     }
+    parse_sbom(content, format, verify_key)
+}
 
+/// parses SBOM bytes already in memory (e.g. an archive entry) exactly as
+/// [`load_sbom`] parses a file, including attestation unwrapping and, when
+/// `verify_key` is given, signature verification.
+pub fn parse_sbom(
+    mut content: Vec<u8>,
+    format: Format,
+    verify_key: Option<&VerifyingKey>,
+) -> anyhow::Result<Sbom> {
     // strip a leading UTF-8 BOM (common from Windows/.NET tooling; serde_json and the SPDX pre-check reject it).
     if content.starts_with(b"\xef\xbb\xbf") {
         content.drain(..3);
@@ -183,25 +270,58 @@ pub fn load_sbom(path: &str, format: Format) -> anyhow::Result<Sbom> {
     if probe.contains(&0) {
         return Err(anyhow!(
             "input appears to be binary (contains null bytes); expected a text-based SBOM \
-             (CycloneDX JSON/XML or SPDX JSON/XML/tag-value)"
+             (CycloneDX JSON/XML, SPDX JSON/XML/tag-value, or Syft JSON)"
         ));
     }
 
+    let attestation = if looks_like_attestation(&content) {
+        unwrap_attestation(&content)?
+    } else {
+        None
+    };
+
+    if let Some(key) = verify_key {
+        match &attestation {
+            Some(attestation) => attestation.verify(key)?,
+            None => return Err(SignatureError::NotSigned.into()),
+        }
+    }
+
+    if let Some(attestation) = attestation {
+        // an embedded JSON predicate is parsed by the reader its predicate
+        // type names; a string predicate goes through the usual dispatch.
+        let predicate_format = match (attestation.predicate_is_json, attestation.kind) {
+            (true, PredicateKind::Cyclonedx) => Format::Cyclonedx,
+            (true, PredicateKind::Spdx) => Format::Spdx,
+            (true, PredicateKind::Syft) => Format::Syft,
+            (false, _) => format,
+        };
+        let mut sbom = parse_content(&attestation.predicate, predicate_format)
+            .with_context(|| format!("attestation predicate ({})", attestation.predicate_type))?;
+        sbom.metadata.subjects = attestation.subjects;
+        return Ok(sbom);
+    }
+
+    parse_content(&content, format)
+}
+
+fn parse_content(content: &[u8], format: Format) -> anyhow::Result<Sbom> {
     match format {
         Format::Cyclonedx => {
-            CycloneDxReader::read_json(&content[..]).map_err(|e| anyhow!("cyclonedx error: {}", e))
+            read_cyclonedx_json(content).map_err(|e| anyhow!("cyclonedx error: {}", e))
+        }
+        Format::CyclonedxXml => {
+            CycloneDxReader::read_xml(content).map_err(|e| anyhow!("cyclonedx xml error: {}", e))
         }
-        Format::CyclonedxXml => CycloneDxReader::read_xml(&content[..])
-            .map_err(|e| anyhow!("cyclonedx xml error: {}", e)),
-        Format::Spdx => {
-            SpdxReader::read_json(&content[..]).map_err(|e| anyhow!("spdx error: {}", e))
+        Format::Spdx => read_spdx_json(content).map_err(|e| anyhow!("spdx error: {}", e)),
+        Format::SpdxTv => {
+            SpdxReader::read_tag_value(content).map_err(|e| anyhow!("spdx tag-value error: {}", e))
         }
-        Format::SpdxTv => SpdxReader::read_tag_value(&content[..])
-            .map_err(|e| anyhow!("spdx tag-value error: {}", e)),
         Format::SpdxXml => {
-            SpdxReader::read_xml(&content[..]).map_err(|e| anyhow!("spdx xml error: {}", e))
+            SpdxReader::read_xml(content).map_err(|e| anyhow!("spdx xml error: {}", e))
         }
-        Format::Auto => auto_detect_and_parse(&content),
+        Format::Syft => SyftReader::read_json(content).map_err(|e| anyhow!("syft error: {}", e)),
+        Format::Auto => auto_detect_and_parse(content),
     }
 }
 
@@ -215,6 +335,7 @@ fn auto_detect_and_parse(content: &[u8]) -> anyhow::Result<Sbom> {
         DetectedFormat::SpdxJson => Some(2),
         DetectedFormat::SpdxTv => Some(3),
         DetectedFormat::SpdxXml => Some(4),
+        DetectedFormat::SyftJson => Some(5),
         DetectedFormat::Unknown => None,
     };
 
@@ -243,8 +364,8 @@ fn auto_detect_and_parse(content: &[u8]) -> anyhow::Result<Sbom> {
         DetectedFormat::Unknown => Err(anyhow!(
             "could not detect SBOM format; the input does not contain \
              any recognized format markers (\"bomFormat\", \"spdxVersion\", \
-             CycloneDX XML namespace, SPDX XML <Document> root, or \
-             SPDXVersion tag-value header).\n\
+             CycloneDX XML namespace, SPDX XML <Document> root, \
+             SPDXVersion tag-value header, or Syft schema.url).\n\
              Parser errors:\n{}",
             errors.join("\n")
         )),
@@ -414,6 +535,33 @@ mod tests {
         assert_eq!(detect_format(input), DetectedFormat::SpdxTv);
     }
 
+    #[test]
+    fn test_detect_syft_json() {
+        let input = br#"{"artifacts": [], "schema": {"version": "16.0.18", "url": "https://raw.githubusercontent.com/anchore/syft/main/schema/json/schema-16.0.18.json"}}"#;
+        assert_eq!(detect_format(input), DetectedFormat::SyftJson);
+    }
+
+    #[test]
+    fn test_detect_syft_json_schema_past_window() {
+        // syft writes `schema` after every artifact, well past the head window.
+        let mut input = br#"{"artifacts": [{"name": ""#.to_vec();
+        input.extend(vec![b'x'; 20_000]);
+        input.extend_from_slice(
+            br#""}], "schema": {"url": "https://raw.githubusercontent.com/anchore/syft/main/schema/json/schema-16.0.18.json"}}"#,
+        );
+        assert_eq!(detect_format(&input), DetectedFormat::SyftJson);
+    }
+
+    #[test]
+    fn test_load_sbom_syft_matches_spdx_components() {
+        let syft = load_sbom("../../tests/fixtures/golden-old.syft.json", Format::Auto).unwrap();
+        let spdx = load_sbom("../../tests/fixtures/golden-old.spdx.json", Format::Auto).unwrap();
+        let ids = |s: &Sbom| s.components.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(ids(&syft), ids(&spdx));
+        assert_eq!(syft.dependencies, spdx.dependencies);
+        assert_eq!(syft.metadata.tools, vec!["syft 1.14.0".to_string()]);
+    }
+
     #[test]
     fn test_detect_unknown_json() {
         let input = br#"{"name": "not an sbom"}"#;
@@ -611,6 +759,115 @@ mod tests {
         assert!(contains_element(b"<DocumentRoot><Document>", b"<Document"));
     }
 
+    #[test]
+    fn test_load_sbom_dsse_envelope_matches_predicate() {
+        let attested = load_sbom(
+            "../../tests/fixtures/attested-golden-old.dsse.json",
+            Format::Auto,
+        )
+        .unwrap();
+        let plain = load_sbom("../../tests/fixtures/golden-old.json", Format::Auto).unwrap();
+        assert_eq!(attested.components, plain.components);
+        assert_eq!(attested.dependencies, plain.dependencies);
+        assert_eq!(attested.metadata.subjects.len(), 1);
+        assert_eq!(attested.metadata.subjects[0].name, "ghcr.io/acme/app");
+        assert!(attested.metadata.subjects[0].digests.contains_key("sha256"));
+    }
+
+    #[test]
+    fn test_load_sbom_json_lines_bundle_picks_sbom() {
+        let attested = load_sbom(
+            "../../tests/fixtures/attested-golden-new.intoto.jsonl",
+            Format::Auto,
+        )
+        .unwrap();
+        let plain = load_sbom("../../tests/fixtures/golden-new.spdx.json", Format::Auto).unwrap();
+        assert_eq!(attested.components, plain.components);
+        assert_eq!(attested.metadata.subjects.len(), 1);
+    }
+
+    #[test]
+    fn test_load_sbom_attestation_predicate_type_overrides_format() {
+        // the predicate type names the reader, so a forced format does not
+        // have to describe the envelope.
+        let attested = load_sbom(
+            "../../tests/fixtures/attested-golden-old.dsse.json",
+            Format::Cyclonedx,
+        )
+        .unwrap();
+        assert!(!attested.components.is_empty());
+    }
+
+    #[test]
+    fn test_load_sbom_attestation_predicate_error_names_predicate_type() {
+        use std::io::Write;
+        let dir = std::env::temp_dir().join("sbom-diff-test-attestation-bad-predicate");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bad.intoto.json");
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(
+                br#"{"subject": [], "predicateType": "https://spdx.dev/Document",
+                    "predicate": {"spdxVersion": "SPDX-3.0"}}"#,
+            )
+            .unwrap();
+
+        let err = format!(
+            "{:#}",
+            load_sbom(path.to_str().unwrap(), Format::Auto).unwrap_err()
+        );
+        assert!(err.contains("https://spdx.dev/Document"), "got {err}");
+        assert!(err.contains("unsupported SPDX version"), "got {err}");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_looks_like_attestation() {
+        assert!(looks_like_attestation(
+            br#"{"payloadType": "application/vnd.in-toto+json"}"#
+        ));
+        assert!(looks_like_attestation(br#"  {"predicateType": "x"}"#));
+        assert!(looks_like_attestation(br#"{"dsseEnvelope": {}}"#));
+        assert!(!looks_like_attestation(br#"{"bomFormat": "CycloneDX"}"#));
+        assert!(!looks_like_attestation(b"<predicateType/>"));
+        assert!(!looks_like_attestation(br#"{"payload": "eyJ9"}"#));
+    }
+
+    #[test]
+    fn test_load_sbom_envelope_with_large_payload_first() {
+        use base64::Engine;
+        use std::io::Write;
+
+        // pad the statement so `payloadType` lands past the detection window.
+        let predicate = std::fs::read_to_string("../../tests/fixtures/golden-old.json").unwrap();
+        let statement = format!(
+            r#"{{"_type": "https://in-toto.io/Statement/v1", "subject": [],
+                "predicateType": "https://cyclonedx.org/bom", "predicate": {predicate}{}}}"#,
+            " ".repeat(16 * 1024)
+        );
+        let payload = base64::engine::general_purpose::STANDARD.encode(statement);
+        let envelope = format!(
+            r#"{{"payload": "{payload}", "payloadType": "application/vnd.in-toto+json",
+                "signatures": [{{"keyid": "", "sig": "AAAA"}}]}}"#
+        );
+        assert!(looks_like_attestation(envelope.as_bytes()));
+
+        let dir = std::env::temp_dir().join("sbom-diff-test-large-payload-first");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("envelope.dsse.json");
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(envelope.as_bytes())
+            .unwrap();
+
+        let sbom = load_sbom(path.to_str().unwrap(), Format::Auto).unwrap();
+        let direct = load_sbom("../../tests/fixtures/golden-old.json", Format::Auto).unwrap();
+        assert_eq!(sbom.components, direct.components);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     /// load `fixture` directly and again with a UTF-8 BOM prepended (via a
     /// uniquely named temp file), asserting both parse to identical SBOMs.
     fn assert_bom_roundtrip(fixture: &str, temp_name: &str) {
@@ -1,7 +1,12 @@
 #![doc = include_str!("../readme.md")]
 
 use sbom_model::versions::{is_version_downgrade_for_ecosystem, Version};
-use sbom_model::{licensings_equivalent, Component, ComponentId, DependencyKind, Sbom};
+use sbom_model::{
+    canonical_algorithm_name, canonical_purl, cpe_vendor_product, cpe_without_version,
+    hash_algorithm_strength, licensings_equivalent, mismatched_digests, purl_qualifiers,
+    purl_without_qualifiers, purl_without_version, Component, ComponentId, DependencyKind,
+    FileEntry, Sbom,
+};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet, HashSet};
@@ -24,12 +29,80 @@ pub struct MetadataChange {
     /// authors changed: (old, new).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub authors: Option<(Vec<String>, Vec<String>)>,
+    /// the described product changed: (old, new), as `name@version`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_component: Option<(Option<String>, Option<String>)>,
 }
 
 impl MetadataChange {
     /// returns true if no metadata fields actually differ.
     pub fn is_empty(&self) -> bool {
-        self.timestamp.is_none() && self.tools.is_none() && self.authors.is_none()
+        self.timestamp.is_none()
+            && self.tools.is_none()
+            && self.authors.is_none()
+            && self.primary_component.is_none()
+    }
+}
+
+/// what one side of a diff is: the document's format and identifiers, and
+/// the product it describes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DocumentIdentity {
+    /// the format the document was read from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// the version of that format's specification or schema.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec_version: Option<String>,
+    /// document name (SPDX).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// document namespace (SPDX).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// serial number (CycloneDX).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+    /// document revision (CycloneDX).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bom_version: Option<u32>,
+    /// id of the component the document describes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_component: Option<ComponentId>,
+    /// that component as `name@version` (or `name`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub describes: Option<String>,
+}
+
+impl DocumentIdentity {
+    /// reads the identity of `sbom` out of its metadata, before
+    /// normalization strips it.
+    pub fn of(sbom: &Sbom) -> Self {
+        let meta = &sbom.metadata;
+        let describes = meta.primary_component.as_ref().map(|id| {
+            sbom.components
+                .get(id)
+                .map(component_label)
+                .unwrap_or_else(|| id.to_string())
+        });
+        Self {
+            format: meta.format.clone(),
+            spec_version: meta.spec_version.clone(),
+            name: meta.name.clone(),
+            namespace: meta.namespace.clone(),
+            serial_number: meta.serial_number.clone(),
+            bom_version: meta.bom_version,
+            primary_component: meta.primary_component.clone(),
+            describes,
+        }
+    }
+}
+
+/// `name@version`, or just the name of a component without a version.
+fn component_label(comp: &Component) -> String {
+    match &comp.version {
+        Some(v) => format!("{}@{}", comp.name, v),
+        None => comp.name.clone(),
     }
 }
 
@@ -70,6 +143,24 @@ pub struct Diff {
     /// diff output is readable without cross-referencing the full component list.
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub component_names: BTreeMap<ComponentId, String>,
+    /// identity of the old document.
+    #[serde(default)]
+    pub old_document: DocumentIdentity,
+    /// identity of the new document.
+    #[serde(default)]
+    pub new_document: DocumentIdentity,
+    /// file and snippet changes, grouped by package. only filled when both
+    /// SBOMs list files.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_diffs: Vec<FileDiff>,
+    /// the distribution release the OS packages moved between, or `None`
+    /// when both SBOMs name the same release (or neither names one).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distro_upgrade: Option<DistroUpgrade>,
+    /// how components were paired, when asked for with
+    /// [`MatchOptions::explain`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub match_decisions: Vec<MatchDecision>,
 }
 
 impl Diff {
@@ -80,6 +171,33 @@ impl Diff {
             && self.changed.is_empty()
             && self.edge_diffs.is_empty()
             && self.metadata_changed.is_none()
+            && self.file_diffs.is_empty()
+            && self.distro_upgrade.is_none()
+    }
+
+    /// splits [`changed`](Self::changed) into the packages that moved with
+    /// the [`distro_upgrade`](Self::distro_upgrade) and everything else.
+    pub fn partition_distro_changes(&self) -> (Vec<&ComponentChange>, Vec<&ComponentChange>) {
+        let grouped: HashSet<&ComponentId> = self
+            .distro_upgrade
+            .iter()
+            .flat_map(|u| &u.components)
+            .collect();
+        self.changed.iter().partition(|c| grouped.contains(&c.id))
+    }
+
+    /// number of changed components whose digests changed at an unchanged
+    /// version (see [`ComponentChange::is_hash_mismatch`]).
+    pub fn hash_mismatch_count(&self) -> usize {
+        self.changed.iter().filter(|c| c.is_hash_mismatch).count()
+    }
+
+    /// number of files added, removed, or changed across all packages.
+    pub fn file_change_count(&self) -> usize {
+        self.file_diffs
+            .iter()
+            .map(|f| f.added.len() + f.removed.len() + f.changed.len())
+            .sum()
     }
 
     /// returns a human-readable display name for a component ID.
@@ -167,18 +285,104 @@ impl Diff {
         filtered_new_total: usize,
         component_ecosystems: &BTreeMap<ComponentId, Option<String>>,
     ) {
-        self.added.retain(|c| matches(c.ecosystem.as_deref()));
-        self.removed.retain(|c| matches(c.ecosystem.as_deref()));
-        self.changed.retain(|c| matches(c.new.ecosystem.as_deref()));
+        let parent_matches = component_ecosystems
+            .iter()
+            .map(|(id, eco)| (id.clone(), matches(eco.as_deref())))
+            .collect();
+        self.filter_components(
+            &|c: &Component| matches(c.ecosystem.as_deref()),
+            filtered_old_total,
+            filtered_new_total,
+            &parent_matches,
+        );
+    }
 
-        // filter edge diffs by parent ecosystem; keep edges whose parent is
-        // unknown (not in the map) as a conservative default.
-        self.edge_diffs.retain(|edge| {
-            component_ecosystems
-                .get(&edge.parent)
-                .map(|eco| matches(eco.as_deref()))
-                .unwrap_or(true)
+    /// filters the diff to only include components that match the given
+    /// predicate, e.g. on ecosystem and kind at once. adjusts `old_total`,
+    /// `new_total`, and `unchanged` the same way as
+    /// [`filter_by_ecosystem`](Self::filter_by_ecosystem).
+    ///
+    /// `parent_matches` records whether each component ID from either SBOM
+    /// passes the predicate, built before the SBOMs are consumed. this is
+    /// used to filter edge diffs by their parent component.
+    pub fn filter_components<F: Fn(&Component) -> bool>(
+        &mut self,
+        matches: &F,
+        filtered_old_total: usize,
+        filtered_new_total: usize,
+        parent_matches: &BTreeMap<ComponentId, bool>,
+    ) {
+        self.filter(
+            &|c: &Component| {
+                if matches(c) {
+                    FilterAction::Keep
+                } else {
+                    FilterAction::Drop
+                }
+            },
+            filtered_old_total,
+            filtered_new_total,
+            parent_matches,
+            &BTreeSet::new(),
+        );
+    }
+
+    /// filters the diff as `decide` says of each component: drop it, keep
+    /// it, or keep it without the changes to some fields. a changed
+    /// component left without changes counts as unchanged. adjusts
+    /// `old_total`, `new_total`, and `unchanged` the same way as
+    /// [`filter_by_ecosystem`](Self::filter_by_ecosystem).
+    ///
+    /// `filtered_old_total`, `filtered_new_total` and `parent_matches`
+    /// (whether the edge diffs of each component ID are kept) come from
+    /// [`filter_basis`], counted before the SBOMs are consumed. the
+    /// components in `dropped_children` also leave the edge diffs of the
+    /// parents that are kept, e.g. the ones an ignore file drops (see
+    /// [`IgnoreFile::dropped`]); an edge diff left empty goes too.
+    pub fn filter<F: Fn(&Component) -> FilterAction>(
+        &mut self,
+        decide: &F,
+        filtered_old_total: usize,
+        filtered_new_total: usize,
+        parent_matches: &BTreeMap<ComponentId, bool>,
+        dropped_children: &BTreeSet<ComponentId>,
+    ) {
+        self.added.retain(|c| decide(c) != FilterAction::Drop);
+        self.removed.retain(|c| decide(c) != FilterAction::Drop);
+        self.changed.retain_mut(|c| match decide(&c.new) {
+            FilterAction::Keep => true,
+            FilterAction::Drop => false,
+            FilterAction::DropFields(fields) => {
+                c.changes.retain(|fc| !fields.contains(&fc.field()));
+                let has = |field: Field| c.changes.iter().any(|fc| fc.field() == field);
+                c.is_downgrade &= has(Field::Version);
+                c.is_hash_mismatch &= has(Field::Hashes);
+                !c.changes.is_empty()
+            }
         });
+        if let Some(upgrade) = &mut self.distro_upgrade {
+            let kept: HashSet<&ComponentId> = self.changed.iter().map(|c| &c.id).collect();
+            let before = upgrade.components.len();
+            upgrade.components.retain(|id| kept.contains(id));
+            // the upgrade only concerned packages the filter hides
+            if before > 0 && upgrade.components.is_empty() {
+                self.distro_upgrade = None;
+            }
+        }
+
+        // filter edge diffs by parent component; keep edges whose parent is
+        // unknown (not in the map) as a conservative default.
+        self.edge_diffs
+            .retain(|edge| parent_matches.get(&edge.parent).copied().unwrap_or(true));
+        if !dropped_children.is_empty() {
+            self.edge_diffs.retain_mut(|edge| {
+                edge.added.retain(|id, _| !dropped_children.contains(id));
+                edge.removed.retain(|id, _| !dropped_children.contains(id));
+                edge.kind_changed
+                    .retain(|id, _| !dropped_children.contains(id));
+                !(edge.added.is_empty() && edge.removed.is_empty() && edge.kind_changed.is_empty())
+            });
+        }
 
         // prune component_names to only IDs still referenced in edge diffs
         let mut referenced_ids = BTreeSet::new();
@@ -203,6 +407,47 @@ impl Diff {
     }
 }
 
+/// what [`Diff::filter`] does with a component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterAction {
+    /// keep the component and all of its changes.
+    Keep,
+    /// leave the component out of the diff and its totals.
+    Drop,
+    /// keep the component, but not the changes to these fields. with
+    /// [`Field::Deps`], its edge diffs go too.
+    DropFields(Vec<Field>),
+}
+
+/// counts what [`Diff::filter`] needs from the two SBOMs before
+/// [`Differ::diff_owned`] consumes them: the number of components each keeps,
+/// and whether the edge diffs of each component ID are kept.
+pub fn filter_basis<F: Fn(&Component) -> FilterAction>(
+    old: &Sbom,
+    new: &Sbom,
+    decide: &F,
+) -> (usize, usize, BTreeMap<ComponentId, bool>) {
+    let mut parent_matches = BTreeMap::new();
+    let mut count = |sbom: &Sbom| {
+        let mut kept = 0;
+        for (id, comp) in &sbom.components {
+            let action = decide(comp);
+            if action != FilterAction::Drop {
+                kept += 1;
+            }
+            let keeps_edges = match &action {
+                FilterAction::Keep => true,
+                FilterAction::Drop => false,
+                FilterAction::DropFields(fields) => !fields.contains(&Field::Deps),
+            };
+            parent_matches.insert(id.clone(), keeps_edges);
+        }
+        kept
+    };
+    let (old_total, new_total) = (count(old), count(new));
+    (old_total, new_total, parent_matches)
+}
+
 /// shared implementation for [`Diff::group_by_ecosystem`] and
 /// [`Diff::into_group_by_ecosystem`]. accepts owned iterators so both the
 /// cloning and consuming callers can share the same loop logic.
@@ -289,12 +534,100 @@ pub struct ComponentChange {
     /// true when the version change is a downgrade (higher to lower).
     #[serde(default, skip_serializing_if = "is_false")]
     pub is_downgrade: bool,
+    /// how the old and new sides were paired; `None` for a change not built
+    /// by [`Differ`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_reason: Option<MatchReason>,
+    /// true when a digest changed while the version did not: the same
+    /// release now has other bytes, a republish or a tampered artifact.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub is_hash_mismatch: bool,
+    /// for a pair matched by [`MatchReason::Graph`], the share of the two
+    /// sides' paired parents and children they have in common, in percent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_confidence: Option<u8>,
 }
 
 fn is_false(b: &bool) -> bool {
     !b
 }
 
+/// a change of the distribution release an SBOM's OS packages were built
+/// for, e.g. a base image moving from debian 11 to 12.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistroUpgrade {
+    /// the old release, as the `distro` purl qualifier spells it (e.g.
+    /// `debian-11`).
+    pub old: String,
+    /// the new release.
+    pub new: String,
+    /// ids of the changed OS packages (and operating-system component),
+    /// listed in [`Diff::changed`], that moved with the release. packages
+    /// whose only change is the release are counted as unchanged.
+    pub components: Vec<ComponentId>,
+}
+
+/// purl types of operating system packages, whose `distro` qualifier names
+/// the release they were built for.
+const OS_PACKAGE_TYPES: &[&str] = &["alpm", "apk", "deb", "rpm"];
+
+fn is_os_package(comp: &Component) -> bool {
+    comp.ecosystem
+        .as_deref()
+        .is_some_and(|e| OS_PACKAGE_TYPES.contains(&e))
+}
+
+/// the distribution release an SBOM's OS packages were built for: the most
+/// common `distro` qualifier among them, or else the `name-version` of its
+/// operating-system component.
+fn distro_of(sbom: &Sbom) -> Option<String> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for comp in sbom.components.values().filter(|c| is_os_package(c)) {
+        let purl = comp.purl.as_deref().unwrap_or_default();
+        if let Some(distro) = purl_qualifiers(purl).remove("distro") {
+            *counts.entry(distro.to_lowercase()).or_default() += 1;
+        }
+    }
+    // ties go to the first release in order, for a stable answer
+    let most_common = counts.into_iter().fold(
+        None,
+        |best: Option<(String, usize)>, (distro, n)| match best {
+            Some((_, m)) if m >= n => best,
+            _ => Some((distro, n)),
+        },
+    );
+    most_common.map(|(distro, _)| distro).or_else(|| {
+        let os = sbom
+            .components
+            .values()
+            .find(|c| c.kind.as_deref() == Some("operating-system"))?;
+        Some(format!("{}-{}", os.name, os.version.as_deref()?).to_lowercase())
+    })
+}
+
+/// file changes within one package, or among the files no package contains.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileDiff {
+    /// name of the package containing the files, or `None` for files the
+    /// documents do not place in a package.
+    pub package: Option<String>,
+    /// files present in the new SBOM but not the old.
+    pub added: Vec<FileEntry>,
+    /// files present in the old SBOM but not the new.
+    pub removed: Vec<FileEntry>,
+    /// files present in both with hash or license changes.
+    pub changed: Vec<FileChange>,
+}
+
+/// a file that exists in both SBOMs with detected changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChange {
+    /// the file's path (a snippet's carries its range).
+    pub path: String,
+    /// [`FieldChange::Hashes`] and [`FieldChange::License`] entries.
+    pub changes: Vec<FieldChange>,
+}
+
 /// the ecosystem a matched pair's versions should be ordered in: the side that
 /// declares one, or `None` when the two sides declare different ecosystems and
 /// neither ruleset applies. feeds
@@ -306,6 +639,92 @@ pub fn pair_ecosystem<'a>(old: &'a Component, new: &'a Component) -> Option<&'a
     }
 }
 
+/// the source repository a `vcs` reference points at, with the parts that
+/// vary between generators and revisions stripped: the `git+` style VCS
+/// prefix, scheme, user, `@revision` and `#path` suffixes, `.git` and case.
+/// `git+https://github.com/Lodash/lodash.git@4.17.21` and
+/// `git@github.com:lodash/lodash` are both `github.com/lodash/lodash`.
+pub fn vcs_repository(url: &str) -> String {
+    let mut rest = url.trim().to_ascii_lowercase();
+    for prefix in ["git+", "hg+", "svn+", "bzr+"] {
+        if let Some(stripped) = rest.strip_prefix(prefix) {
+            rest = stripped.to_string();
+            break;
+        }
+    }
+    if let Some(pos) = rest.find('#') {
+        rest.truncate(pos);
+    }
+    let has_scheme = match rest.find("://") {
+        Some(pos) => {
+            rest.drain(..pos + 3);
+            true
+        }
+        None => false,
+    };
+    // user info, e.g. `git@`, ends before the host.
+    let host_end = rest.find('/').unwrap_or(rest.len());
+    if let Some(at) = rest[..host_end].rfind('@') {
+        rest.drain(..=at);
+    }
+    // scp-like `host:path`.
+    if !has_scheme {
+        if let Some(colon) = rest.find(':').filter(|&c| c < host_end) {
+            rest.replace_range(colon..=colon, "/");
+        }
+    }
+    // a revision pinned onto the last path segment.
+    let last_segment = rest.rfind('/').map_or(0, |s| s + 1);
+    if let Some(at) = rest[last_segment..].find('@') {
+        rest.truncate(last_segment + at);
+    }
+    let rest = rest.trim_end_matches('/');
+    rest.strip_suffix(".git").unwrap_or(rest).to_string()
+}
+
+/// matches `text` against a shell-style glob where `*` matches any run of
+/// characters (including `:`) and `?` any single one; everything else
+/// matches itself. used for property name allowlists such as `cdx:npm:*`.
+///
+/// # Example
+///
+/// ```
+/// use sbom_diff::glob_match;
+///
+/// assert!(glob_match("syft:package:*", "syft:package:foundBy"));
+/// assert!(glob_match("*:development", "cdx:npm:package:development"));
+/// assert!(!glob_match("syft:location:?:path", "syft:location:10:path"));
+/// ```
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // where the last `*` was, and the text position it is currently
+    // standing in for; on a mismatch it absorbs one more character.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 /// a dependency edge change for a single parent component.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EdgeDiff {
@@ -323,6 +742,10 @@ pub struct EdgeDiff {
 /// a specific field that changed between two versions of a component.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum FieldChange {
+    /// name changed: (old, new). reported for components paired by a name
+    /// spelled differently, e.g. pypi's `PyYAML` and `pyyaml` (see
+    /// [`Component::identity_name`]).
+    Name(String, String),
     /// version changed: (old, new).
     Version(Option<String>, Option<String>),
     /// licenses changed: (old, new).
@@ -331,16 +754,69 @@ pub enum FieldChange {
     /// identifier set stayed the same: (old, new). a `WITH` exception being
     /// added or dropped, or `OR` becoming `AND`, shows up here.
     LicenseExpression(Option<String>, Option<String>),
+    /// declared licenses changed: (old, new). only reported when both sides
+    /// declare a license apart from the concluded one.
+    DeclaredLicense(BTreeSet<String>, BTreeSet<String>),
+    /// the declared SPDX license expression changed while its identifier set
+    /// stayed the same: (old, new).
+    DeclaredLicenseExpression(Option<String>, Option<String>),
     /// supplier changed: (old, new).
     Supplier(Option<String>, Option<String>),
-    /// package URL changed: (old, new).
+    /// copyright notice changed: (old, new).
+    Copyright(Option<String>, Option<String>),
+    /// package URL changed: (old, new). with [`MatchOptions::ignore_qualifiers`],
+    /// only reported when the purls differ in more than those qualifiers.
     Purl(Option<String>, Option<String>),
+    /// purl qualifiers ignored for matching changed value: (old, new), each
+    /// holding just the ignored qualifiers that differ. only reported with
+    /// [`MatchOptions::ignore_qualifiers`].
+    Qualifiers(BTreeMap<String, String>, BTreeMap<String, String>),
+    /// CPE names changed: (old, new).
+    Cpes(BTreeSet<String>, BTreeSet<String>),
+    /// external references changed, keyed by reference type: (old, new).
+    ExternalRefs(
+        BTreeMap<String, BTreeSet<String>>,
+        BTreeMap<String, BTreeSet<String>>,
+    ),
     /// description changed: (old, new).
     Description(Option<String>, Option<String>),
     /// hashes changed: (old, new).
     Hashes(BTreeMap<String, String>, BTreeMap<String, String>),
     /// ecosystem changed: (old, new).
     Ecosystem(Option<String>, Option<String>),
+    /// component kind changed: (old, new). only reported when both sides
+    /// state a kind, since SPDX documents often leave it out.
+    Kind(Option<String>, Option<String>),
+    /// a component property was added, removed, or changed its values:
+    /// (name, old values, new values). an added property has no old values,
+    /// a removed one no new values. one entry per changed name; only
+    /// reported when both documents carry properties.
+    Property(String, BTreeSet<String>, BTreeSet<String>),
+}
+
+impl FieldChange {
+    /// the field this change is reported under, as `--only` names it.
+    pub fn field(&self) -> Field {
+        match self {
+            FieldChange::Name(..) => Field::Name,
+            FieldChange::Version(..) => Field::Version,
+            FieldChange::License(..) | FieldChange::LicenseExpression(..) => Field::License,
+            FieldChange::DeclaredLicense(..) | FieldChange::DeclaredLicenseExpression(..) => {
+                Field::DeclaredLicense
+            }
+            FieldChange::Supplier(..) => Field::Supplier,
+            FieldChange::Copyright(..) => Field::Copyright,
+            FieldChange::Purl(..) => Field::Purl,
+            FieldChange::Qualifiers(..) => Field::Qualifiers,
+            FieldChange::Cpes(..) => Field::Cpes,
+            FieldChange::ExternalRefs(..) => Field::ExternalRefs,
+            FieldChange::Description(..) => Field::Description,
+            FieldChange::Hashes(..) => Field::Hashes,
+            FieldChange::Ecosystem(..) => Field::Ecosystem,
+            FieldChange::Kind(..) => Field::Kind,
+            FieldChange::Property(..) => Field::Properties,
+        }
+    }
 }
 
 /// fields that can be compared and filtered.
@@ -348,20 +824,36 @@ pub enum FieldChange {
 /// use with [`Differ::diff`] to limit comparison to specific fields.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
 pub enum Field {
+    /// package name.
+    Name,
     /// package version.
     Version,
     /// license identifiers.
     License,
+    /// declared license identifiers.
+    DeclaredLicense,
     /// supplier/publisher.
     Supplier,
+    /// copyright notice.
+    Copyright,
     /// package URL.
     Purl,
+    /// purl qualifiers ignored for matching (see [`MatchOptions`]).
+    Qualifiers,
+    /// CPE names.
+    Cpes,
+    /// external references (vcs, website, distribution, advisories, ...).
+    ExternalRefs,
     /// human-readable description.
     Description,
     /// checksums.
     Hashes,
     /// package ecosystem.
     Ecosystem,
+    /// component kind (library, application, operating-system, ...).
+    Kind,
+    /// component properties (CycloneDX `properties`, SPDX annotations).
+    Properties,
     /// dependency edges.
     Deps,
 }
@@ -370,6 +862,441 @@ pub enum Field {
 /// the version alignment gives up and pairs them by id.
 const MAX_ALIGNED_CANDIDATES: usize = 256;
 
+/// the weakest hash algorithm (by [`hash_algorithm_strength`]) whose digest
+/// is trusted to identify an artifact on its own: SHA-256 and its tier.
+const MIN_IDENTIFYING_HASH_STRENGTH: u8 = 4;
+
+/// how many paired parents and children two hash-id components must share
+/// to be matched by the dependency graph: a lone shared parent is what a
+/// dependency swapped for another looks like, too.
+const MIN_SHARED_NEIGHBORS: usize = 2;
+
+/// the confidence, in percent, a dependency-graph match needs when
+/// [`MatchOptions::graph_threshold`] is unset.
+pub const DEFAULT_GRAPH_THRESHOLD: u8 = 75;
+
+/// how [`Differ`] pairs components across the two SBOMs.
+#[derive(Debug, Clone, Default)]
+pub struct MatchOptions {
+    /// purl qualifiers (e.g. `distro`, `repository_url`) to leave out when
+    /// pairing components. two components whose purls differ only in these
+    /// are paired, preferring the candidate that agrees on the most of them,
+    /// so per-arch entries stay paired with their own arch; the changed
+    /// values are reported as [`FieldChange::Qualifiers`].
+    pub ignore_qualifiers: BTreeSet<String>,
+    /// user-supplied renames, applied before any other pass (see
+    /// [`MatchRule`]).
+    pub rules: Vec<MatchRule>,
+    /// record every pairing decision in [`Diff::match_decisions`].
+    pub explain: bool,
+    /// the confidence, in percent, a pair of hash-id components needs to
+    /// be matched by their dependency-graph neighborhood (see
+    /// [`MatchReason::Graph`]); `None` means [`DEFAULT_GRAPH_THRESHOLD`].
+    pub graph_threshold: Option<u8>,
+}
+
+/// a user-supplied rename, one line of a `--match-map` file. an unmatched
+/// old component whose purl matches `old` pairs with an unmatched new one
+/// whose purl matches `new`.
+///
+/// patterns name a package without its version and qualifiers, e.g.
+/// `pkg:npm/request`. a `*` in `old` stands for any run of characters, and
+/// a `*` in `new` for the text it matched, so `pkg:maven/org.hibernate/*`
+/// → `pkg:maven/org.hibernate.orm/*` follows a whole group across its move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchRule {
+    /// the pattern the old component's purl matches.
+    pub old: String,
+    /// the pattern the new component's purl matches.
+    pub new: String,
+}
+
+/// a line of a match map that could not be parsed.
+#[derive(Debug, thiserror::Error)]
+#[error("line {line}: {message}")]
+pub struct MatchMapError {
+    /// the 1-based line number.
+    pub line: usize,
+    /// what is wrong with it.
+    pub message: String,
+}
+
+impl MatchRule {
+    /// parses a match map: one `old new` pair of patterns per line,
+    /// optionally separated by `->`. blank lines and `#` comments are
+    /// skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sbom_diff::MatchRule;
+    ///
+    /// let rules = MatchRule::parse_map(
+    ///     "# request was forked\npkg:npm/request -> pkg:npm/@cypress/request\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(rules[0].new, "pkg:npm/@cypress/request");
+    /// assert!(MatchRule::parse_map("pkg:npm/request").is_err());
+    /// ```
+    pub fn parse_map(text: &str) -> Result<Vec<MatchRule>, MatchMapError> {
+        let mut rules = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let error = |message: &str| MatchMapError {
+                line: index + 1,
+                message: message.to_string(),
+            };
+            let fields: Vec<&str> = line
+                .split_whitespace()
+                .take_while(|f| !f.starts_with('#'))
+                .filter(|f| *f != "->")
+                .collect();
+            let (old, new) = match fields.as_slice() {
+                [] => continue,
+                [old, new] => (old.replace("%40", "@"), new.replace("%40", "@")),
+                _ => return Err(error("expected an old and a new purl pattern")),
+            };
+            if !old.starts_with("pkg:") || !new.starts_with("pkg:") {
+                return Err(error("patterns must be purls (pkg:type/...)"));
+            }
+            let (old_stars, new_stars) = (old.matches('*').count(), new.matches('*').count());
+            if old_stars > 1 || new_stars > 1 {
+                return Err(error("a pattern may hold at most one `*`"));
+            }
+            if new_stars > old_stars {
+                return Err(error("a `*` in the new pattern needs one in the old"));
+            }
+            rules.push(MatchRule { old, new });
+        }
+        Ok(rules)
+    }
+
+    /// the package a component named `key` (see [`match_key`]) maps to
+    /// under this rule, or `None` when `old` does not match it.
+    fn target(&self, key: &str) -> Option<String> {
+        let Some((prefix, suffix)) = self.old.split_once('*') else {
+            return (key == self.old).then(|| self.new.clone());
+        };
+        let captured = key.strip_prefix(prefix)?.strip_suffix(suffix)?;
+        Some(self.new.replacen('*', captured, 1))
+    }
+}
+
+/// the form of a purl match rules compare against: canonical, without
+/// version, qualifiers and subpath, and with `@` left unescaped so an npm scope
+/// reads as users write it.
+fn match_key(purl: &str) -> Option<String> {
+    let purl = purl_without_version(purl)?;
+    let purl = purl.split(['?', '#']).next().unwrap_or_default();
+    Some(purl.replace("%40", "@"))
+}
+
+/// the rules of an ignore file (`.sbom-diff-ignore`): components known to be
+/// noisy, left out of a diff (see [`IgnoreFile::action`] and
+/// [`Diff::filter`]).
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreFile {
+    /// the rules, in file order.
+    pub rules: Vec<IgnoreRule>,
+}
+
+/// one line of an ignore file: the components it covers and, optionally,
+/// the only fields of theirs to ignore.
+#[derive(Debug, Clone)]
+pub struct IgnoreRule {
+    /// which components the rule covers.
+    pub pattern: IgnorePattern,
+    /// the fields whose changes are ignored; empty ignores the component
+    /// altogether.
+    pub fields: Vec<Field>,
+}
+
+/// how an [`IgnoreRule`] picks its components.
+#[derive(Debug, Clone)]
+pub enum IgnorePattern {
+    /// a glob (see [`glob_match`]) over the purl, matched with and without
+    /// its version, qualifiers and subpath, e.g. `pkg:generic/*`.
+    Purl(String),
+    /// a regex searched for in the component name.
+    Name(regex::Regex),
+    /// an ecosystem, compared case-insensitively.
+    Ecosystem(String),
+}
+
+/// a line of an ignore file that could not be parsed.
+#[derive(Debug, thiserror::Error)]
+#[error("line {line}: {message}")]
+pub struct IgnoreFileError {
+    /// the 1-based line number.
+    pub line: usize,
+    /// what is wrong with it.
+    pub message: String,
+}
+
+impl IgnoreFile {
+    /// parses an ignore file: one pattern per line, a purl glob
+    /// (`pkg:...`), `name:<regex>` or `ecosystem:<name>`, optionally
+    /// followed by a comma-separated list of fields (as `--only` names
+    /// them) to ignore instead of the whole component. blank lines and `#`
+    /// comments are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sbom_diff::{Field, FilterAction, IgnoreFile};
+    /// use sbom_model::Component;
+    ///
+    /// let ignore = IgnoreFile::parse(
+    ///     "name:^test-\n# firmware blobs churn\npkg:generic/* description,hashes\n",
+    /// )
+    /// .unwrap();
+    /// let mut blob = Component::new("bios".into(), Some("1.0".into()));
+    /// blob.purl = Some("pkg:generic/bios@1.0".into());
+    /// assert_eq!(
+    ///     ignore.action(&blob),
+    ///     FilterAction::DropFields(vec![Field::Description, Field::Hashes])
+    /// );
+    /// let fixture = Component::new("test-utils".into(), None);
+    /// assert_eq!(ignore.action(&fixture), FilterAction::Drop);
+    /// assert!(IgnoreFile::parse("name:[").is_err());
+    /// ```
+    pub fn parse(text: &str) -> Result<IgnoreFile, IgnoreFileError> {
+        let mut rules = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let error = |message: String| IgnoreFileError {
+                line: index + 1,
+                message,
+            };
+            let fields: Vec<&str> = line
+                .split_whitespace()
+                .take_while(|f| !f.starts_with('#'))
+                .collect();
+            let (pattern, scope) = match fields.as_slice() {
+                [] => continue,
+                [pattern] => (*pattern, None),
+                [pattern, scope] => (*pattern, Some(*scope)),
+                _ => {
+                    return Err(error(
+                        "expected a pattern and an optional list of fields".to_string(),
+                    ))
+                }
+            };
+            let pattern = if pattern.starts_with("pkg:") {
+                IgnorePattern::Purl(pattern.replace("%40", "@"))
+            } else if let Some(name) = pattern.strip_prefix("name:") {
+                let regex = regex::Regex::new(name)
+                    .map_err(|e| error(format!("invalid name regex: {e}")))?;
+                IgnorePattern::Name(regex)
+            } else if let Some(ecosystem) = pattern.strip_prefix("ecosystem:") {
+                IgnorePattern::Ecosystem(ecosystem.to_string())
+            } else {
+                return Err(error(
+                    "patterns are purl globs (pkg:...), name:<regex> or ecosystem:<name>"
+                        .to_string(),
+                ));
+            };
+            let fields = scope
+                .into_iter()
+                .flat_map(|scope| scope.split(','))
+                .filter(|name| !name.is_empty())
+                .map(|name| {
+                    <Field as clap::ValueEnum>::from_str(name, true)
+                        .map_err(|_| error(format!("unknown field `{name}`")))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            rules.push(IgnoreRule { pattern, fields });
+        }
+        Ok(IgnoreFile { rules })
+    }
+
+    /// returns `true` when the file has no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// what [`Diff::filter`] should do with `comp`: drop it when a rule
+    /// without fields covers it, else drop the fields the rules covering it
+    /// list, in [`Field`] order.
+    pub fn action(&self, comp: &Component) -> FilterAction {
+        let mut fields: Vec<Field> = Vec::new();
+        for rule in self.rules.iter().filter(|r| r.matches(comp)) {
+            if rule.fields.is_empty() {
+                return FilterAction::Drop;
+            }
+            for field in &rule.fields {
+                if !fields.contains(field) {
+                    fields.push(*field);
+                }
+            }
+        }
+        if fields.is_empty() {
+            FilterAction::Keep
+        } else {
+            fields.sort_by_key(|f| *f as usize);
+            FilterAction::DropFields(fields)
+        }
+    }
+
+    /// the ids of the components in `sboms` a rule without fields covers,
+    /// for [`Diff::filter`] to take out of the edge diffs as well.
+    pub fn dropped<'a>(&self, sboms: impl IntoIterator<Item = &'a Sbom>) -> BTreeSet<ComponentId> {
+        sboms
+            .into_iter()
+            .flat_map(|sbom| &sbom.components)
+            .filter(|(_, comp)| self.action(comp) == FilterAction::Drop)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// returns `true` when the changes to `field` of `comp` are ignored.
+    pub fn ignores(&self, comp: &Component, field: Field) -> bool {
+        match self.action(comp) {
+            FilterAction::Keep => false,
+            FilterAction::Drop => true,
+            FilterAction::DropFields(fields) => fields.contains(&field),
+        }
+    }
+}
+
+impl IgnoreRule {
+    /// returns `true` when the rule covers `comp`.
+    pub fn matches(&self, comp: &Component) -> bool {
+        match &self.pattern {
+            IgnorePattern::Purl(glob) => comp.purl.as_deref().is_some_and(|purl| {
+                glob_match(glob, &purl.replace("%40", "@"))
+                    || match_key(purl).is_some_and(|key| glob_match(glob, &key))
+            }),
+            IgnorePattern::Name(regex) => regex.is_match(&comp.name),
+            IgnorePattern::Ecosystem(ecosystem) => comp
+                .ecosystem
+                .as_deref()
+                .is_some_and(|e| e.eq_ignore_ascii_case(ecosystem)),
+        }
+    }
+}
+
+/// which of [`Differ`]'s passes paired a component's two sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MatchReason {
+    /// the same component id on both sides.
+    Id,
+    /// the same purl once the ignored qualifiers are left out (see
+    /// [`MatchOptions::ignore_qualifiers`]).
+    Qualifiers,
+    /// the only candidates sharing an ecosystem and name.
+    Identity,
+    /// the same name, with the ecosystem unknown on one side.
+    WildcardIdentity,
+    /// one of several candidates sharing an ecosystem and name, picked by
+    /// version order.
+    VersionAligned,
+    /// the same CPE vendor:product, for components without a purl.
+    Cpe,
+    /// an identical strong digest (SHA-256 or better), for components
+    /// without a purl.
+    ContentHash,
+    /// a [`MatchRule`] from the user's match map named the pair.
+    UserMapping,
+    /// the same parents and children once those are paired, for components
+    /// with hash ids (see [`ComponentChange::match_confidence`]).
+    Graph,
+}
+
+impl MatchReason {
+    /// the reason's name as serialized, e.g. `user-mapping`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MatchReason::Id => "id",
+            MatchReason::Qualifiers => "qualifiers",
+            MatchReason::Identity => "identity",
+            MatchReason::WildcardIdentity => "wildcard-identity",
+            MatchReason::VersionAligned => "version-aligned",
+            MatchReason::Cpe => "cpe",
+            MatchReason::ContentHash => "content-hash",
+            MatchReason::UserMapping => "user-mapping",
+            MatchReason::Graph => "graph",
+        }
+    }
+
+    /// a short description for rendering, e.g. "matched by user mapping".
+    pub fn describe(self) -> &'static str {
+        match self {
+            MatchReason::Id => "matched by id",
+            MatchReason::Qualifiers => "matched by purl, ignoring qualifiers",
+            MatchReason::Identity => "matched by ecosystem and name",
+            MatchReason::WildcardIdentity => "matched by name, ecosystem unknown",
+            MatchReason::VersionAligned => "matched by version order among candidates",
+            MatchReason::Cpe => "matched by cpe",
+            MatchReason::ContentHash => "matched by identical content hash",
+            MatchReason::UserMapping => "matched by user mapping",
+            MatchReason::Graph => "matched by dependency graph",
+        }
+    }
+}
+
+/// one of [`Differ`]'s pairing decisions, recorded when
+/// [`MatchOptions::explain`] is set. components are named by purl, or
+/// `name@version` without one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "kebab-case")]
+pub enum MatchDecision {
+    /// an old and a new component were paired; `confidence` is set for a
+    /// [`MatchReason::Graph`] pair.
+    Paired {
+        old: String,
+        new: String,
+        reason: MatchReason,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        confidence: Option<u8>,
+    },
+    /// candidates a bucket of same-identity components left unpaired, and
+    /// no later pass paired either.
+    Unpaired {
+        bucket: String,
+        old: Vec<String>,
+        new: Vec<String>,
+    },
+    /// a bucket with more candidates on a side than version alignment handles
+    /// (256), paired in id order instead of by version.
+    Oversized {
+        bucket: String,
+        old: usize,
+        new: usize,
+    },
+}
+
+/// the bucket-level decisions collected while matching, for
+/// [`Diff::match_decisions`].
+#[derive(Default)]
+struct MatchLog {
+    unpaired: Vec<(String, Vec<ComponentId>, Vec<ComponentId>)>,
+    oversized: Vec<MatchDecision>,
+}
+
+impl MatchLog {
+    /// notes the candidates of a bucket left out of `used_old` and
+    /// `used_new`, if there are any.
+    fn note_unpaired(
+        &mut self,
+        bucket: &str,
+        old_ids: &[ComponentId],
+        new_ids: &[ComponentId],
+        used_old: &HashSet<&ComponentId>,
+        used_new: &HashSet<&ComponentId>,
+    ) {
+        let left = |ids: &[ComponentId], used: &HashSet<&ComponentId>| -> Vec<ComponentId> {
+            ids.iter()
+                .filter(|id| !used.contains(id))
+                .cloned()
+                .collect()
+        };
+        let (old_left, new_left) = (left(old_ids, used_old), left(new_ids, used_new));
+        if !old_left.is_empty() || !new_left.is_empty() {
+            self.unpaired.push((bucket.to_string(), old_left, new_left));
+        }
+    }
+}
+
 /// SBOM comparison engine.
 ///
 /// compares two SBOMs and produces a [`Diff`] describing the changes.
@@ -411,13 +1338,36 @@ impl Differ {
 
     /// consuming variant of [`diff`](Self::diff) that normalizes in place,
     /// avoiding two full SBOM clones.
-    pub fn diff_owned(mut old: Sbom, mut new: Sbom, only: Option<&[Field]>) -> Diff {
+    pub fn diff_owned(old: Sbom, new: Sbom, only: Option<&[Field]>) -> Diff {
+        Self::diff_owned_with(old, new, only, &MatchOptions::default())
+    }
+
+    /// like [`diff`](Self::diff), pairing components as `options` says.
+    pub fn diff_with(
+        old: &Sbom,
+        new: &Sbom,
+        only: Option<&[Field]>,
+        options: &MatchOptions,
+    ) -> Diff {
+        Self::diff_owned_with(old.clone(), new.clone(), only, options)
+    }
+
+    /// consuming variant of [`diff_with`](Self::diff_with).
+    pub fn diff_owned_with(
+        mut old: Sbom,
+        mut new: Sbom,
+        only: Option<&[Field]>,
+        options: &MatchOptions,
+    ) -> Diff {
         // compare metadata before normalize() strips volatile fields
+        let old_document = DocumentIdentity::of(&old);
+        let new_document = DocumentIdentity::of(&new);
         let metadata_changed = {
             let mut mc = MetadataChange {
                 timestamp: None,
                 tools: None,
                 authors: None,
+                primary_component: None,
             };
             if old.metadata.timestamp != new.metadata.timestamp {
                 mc.timestamp = Some((
@@ -431,6 +1381,12 @@ impl Differ {
             if old.metadata.authors != new.metadata.authors {
                 mc.authors = Some((old.metadata.authors.clone(), new.metadata.authors.clone()));
             }
+            if old_document.describes != new_document.describes {
+                mc.primary_component = Some((
+                    old_document.describes.clone(),
+                    new_document.describes.clone(),
+                ));
+            }
             if mc.is_empty() {
                 None
             } else {
@@ -441,6 +1397,32 @@ impl Differ {
         old.normalize();
         new.normalize();
 
+        // a release upgrade moves every OS package's `distro` qualifier,
+        // which would otherwise split their ids and report it per package.
+        let distro = match (distro_of(&old), distro_of(&new)) {
+            (Some(old_distro), Some(new_distro)) if old_distro != new_distro => {
+                Some((old_distro, new_distro))
+            }
+            _ => None,
+        };
+        let upgrade_options;
+        let options = if distro.is_some() && !options.ignore_qualifiers.contains("distro") {
+            let mut with_distro = options.clone();
+            with_distro.ignore_qualifiers.insert("distro".to_string());
+            upgrade_options = with_distro;
+            &upgrade_options
+        } else {
+            options
+        };
+
+        // properties are only compared when both documents carry some:
+        // which ones a document has at all depends on its generator, so
+        // e.g. syft's json against an SPDX document would otherwise report
+        // every component.
+        let has_properties =
+            |sbom: &Sbom| sbom.components.values().any(|c| !c.properties.is_empty());
+        let compare_properties = has_properties(&old) && has_properties(&new);
+
         // phase 1: collect match decisions using only borrows — no component
         // clones. we record (old_id, new_id, field_changes) triples for pairs
         // that actually differ and track all matched IDs for later draining.
@@ -451,34 +1433,87 @@ impl Differ {
         // track old_id -> new_id mappings for edge reconciliation
         let mut id_mapping: BTreeMap<ComponentId, ComponentId> = BTreeMap::new();
 
+        // how each pair was found, by new id
+        let mut reasons: BTreeMap<ComponentId, MatchReason> = BTreeMap::new();
+        let mut confidences: BTreeMap<ComponentId, u8> = BTreeMap::new();
+        let mut log = options.explain.then(MatchLog::default);
+
+        // 0. user-supplied renames come first: they name pairs the passes
+        // below cannot see, e.g. a package that moved to a fork.
+        for (old_id, new_id) in Self::pair_by_rules(&old, &new, &options.rules, log.as_mut()) {
+            let fields = Self::compute_fields(
+                &old.components[&old_id],
+                &new.components[&new_id],
+                only,
+                compare_properties,
+                options,
+            );
+            matched_old.insert(old_id.clone());
+            matched_new.insert(new_id.clone());
+            id_mapping.insert(old_id.clone(), new_id.clone());
+            reasons.insert(new_id.clone(), MatchReason::UserMapping);
+            if !fields.is_empty() {
+                changed_pairs.push((old_id, new_id, fields));
+            }
+        }
+
         // 1. match by ID
         for (id, new_comp) in &new.components {
             if let Some(old_comp) = old.components.get(id) {
                 matched_old.insert(id.clone());
                 matched_new.insert(id.clone());
                 id_mapping.insert(id.clone(), id.clone());
+                reasons.insert(id.clone(), MatchReason::Id);
 
-                let fields = Self::compute_fields(old_comp, new_comp, only);
+                let fields =
+                    Self::compute_fields(old_comp, new_comp, only, compare_properties, options);
                 if !fields.is_empty() {
                     changed_pairs.push((id.clone(), id.clone(), fields));
                 }
             }
         }
 
+        // 1b. purls that differ only in ignored qualifiers (and possibly the
+        // version) name the same package.
+        if !options.ignore_qualifiers.is_empty() {
+            for (old_id, new_id) in Self::pair_by_qualifiers(
+                &old,
+                &new,
+                &matched_old,
+                &matched_new,
+                options,
+                log.as_mut(),
+            ) {
+                let fields = Self::compute_fields(
+                    &old.components[&old_id],
+                    &new.components[&new_id],
+                    only,
+                    compare_properties,
+                    options,
+                );
+                matched_old.insert(old_id.clone());
+                matched_new.insert(new_id.clone());
+                id_mapping.insert(old_id.clone(), new_id.clone());
+                reasons.insert(new_id.clone(), MatchReason::Qualifiers);
+                if !fields.is_empty() {
+                    changed_pairs.push((old_id, new_id, fields));
+                }
+            }
+        }
+
         // 2. reconciliation: match by "identity" (name + ecosystem)
-        // when purls are absent or change, we match by (ecosystem, name).
+        // when purls are absent or change, we match by (ecosystem, name),
+        // the name spelled as the ecosystem compares names.
         // if either ecosystem is None, we treat it as a wildcard and match by name alone.
         //
-        // the map is keyed by name, then by ecosystem, so the wildcard lookup
-        // (new has no ecosystem → match any old with the same name) is O(k)
-        // where k is the number of distinct ecosystems sharing that name,
-        // rather than a linear scan of the entire map.
+        // the map is keyed by name, then by ecosystem, so every version of
+        // one package lands in one bucket.
         let mut old_identity_map: BTreeMap<String, BTreeMap<Option<String>, Vec<ComponentId>>> =
             BTreeMap::new();
         for (id, comp) in &old.components {
             if !matched_old.contains(id) {
                 old_identity_map
-                    .entry(comp.name.clone())
+                    .entry(comp.identity_name())
                     .or_default()
                     .entry(comp.ecosystem.clone())
                     .or_default()
@@ -490,7 +1525,7 @@ impl Differ {
         for (id, comp) in &new.components {
             if !matched_new.contains(id) {
                 new_identity_map
-                    .entry(comp.name.clone())
+                    .entry(comp.identity_name())
                     .or_default()
                     .entry(comp.ecosystem.clone())
                     .or_default()
@@ -500,7 +1535,7 @@ impl Differ {
 
         // 2a. exact (ecosystem, name) matches are resolved a whole bucket at a
         // time, so several versions of one package pair up in version order.
-        let mut identity_pairs: Vec<(ComponentId, ComponentId)> = Vec::new();
+        let mut identity_pairs: Vec<(ComponentId, ComponentId, MatchReason)> = Vec::new();
         for (name, new_eco_map) in &new_identity_map {
             let Some(old_eco_map) = old_identity_map.get_mut(name) else {
                 continue;
@@ -509,69 +1544,281 @@ impl Differ {
                 let Some(old_ids) = old_eco_map.get_mut(ecosystem) else {
                     continue;
                 };
-                let pairs = Self::align_by_version(old_ids, new_ids, &old, &new);
+                let reason = if old_ids.len() == 1 && new_ids.len() == 1 {
+                    MatchReason::Identity
+                } else {
+                    MatchReason::VersionAligned
+                };
+                let bucket = format!(
+                    "{name} ({})",
+                    ecosystem.as_deref().unwrap_or("no ecosystem")
+                );
+                let pairs = Self::align_bucket(&bucket, old_ids, new_ids, &old, &new, log.as_mut());
                 let consumed: HashSet<ComponentId> =
                     pairs.iter().map(|(old_id, _)| old_id.clone()).collect();
                 old_ids.retain(|id| !consumed.contains(id));
-                identity_pairs.extend(pairs);
+                identity_pairs.extend(pairs.into_iter().map(|(o, n)| (o, n, reason)));
             }
         }
-        for (_, new_id) in &identity_pairs {
+        for (_, new_id, _) in &identity_pairs {
             matched_new.insert(new_id.clone());
         }
 
         // 2b. what is left falls through to the wildcard cases, aligned by
         // version as well; an ecosystem-less new component pools every old
         // ecosystem of that name, so the version-nearest candidate wins over
-        // the alphabetically first.
+        // the alphabetically first. the side without an ecosystem has its
+        // name spelled the way the other side's ecosystem compares names
+        // (see `Component::wildcard_name`), so `PyYAML` still meets
+        // `pkg:pypi/pyyaml`.
+        //
+        // old leftovers are keyed by their own ecosystem and name, and those
+        // without an ecosystem also once per ecosystem a new leftover states.
+        let new_ecosystems: BTreeSet<&str> = new_identity_map
+            .values()
+            .flat_map(|eco_map| eco_map.iter())
+            .filter(|(_, ids)| ids.iter().any(|id| !matched_new.contains(id)))
+            .filter_map(|(ecosystem, _)| ecosystem.as_deref())
+            .collect();
+        let mut old_by_own: BTreeMap<(Option<String>, String), Vec<ComponentId>> = BTreeMap::new();
+        let mut old_as_ecosystem: BTreeMap<(&str, String), Vec<ComponentId>> = BTreeMap::new();
+        for id in old_identity_map.values().flat_map(|m| m.values()).flatten() {
+            let comp = &old.components[id];
+            let ecosystem = comp.ecosystem.as_deref();
+            old_by_own
+                .entry((comp.ecosystem.clone(), comp.wildcard_name(ecosystem)))
+                .or_default()
+                .push(id.clone());
+            if ecosystem.is_none() {
+                for &eco in &new_ecosystems {
+                    old_as_ecosystem
+                        .entry((eco, comp.wildcard_name(Some(eco))))
+                        .or_default()
+                        .push(id.clone());
+                }
+            }
+        }
+        let old_ecosystems: BTreeSet<Option<String>> =
+            old_by_own.keys().map(|(eco, _)| eco.clone()).collect();
+        let mut wildcard_consumed: HashSet<ComponentId> = HashSet::new();
         for (name, new_eco_map) in &new_identity_map {
-            let Some(old_eco_map) = old_identity_map.get_mut(name) else {
-                continue;
-            };
             for (ecosystem, new_ids) in new_eco_map {
                 let new_ids: Vec<ComponentId> = new_ids
                     .iter()
                     .filter(|id| !matched_new.contains(*id))
                     .cloned()
                     .collect();
-                if new_ids.is_empty() {
+                let Some(first) = new_ids.first().map(|id| &new.components[id]) else {
                     continue;
-                }
-                let old_ids: Vec<ComponentId> = if ecosystem.is_some() {
-                    old_eco_map.get(&None).cloned().unwrap_or_default()
-                } else {
-                    old_eco_map.values().flatten().cloned().collect()
                 };
-                let pairs = Self::align_by_version(&old_ids, &new_ids, &old, &new);
-                let consumed: HashSet<ComponentId> =
-                    pairs.iter().map(|(old_id, _)| old_id.clone()).collect();
-                for ids in old_eco_map.values_mut() {
-                    ids.retain(|id| !consumed.contains(id));
+                let old_ids: Vec<ComponentId> = match ecosystem.as_deref() {
+                    Some(eco) => old_as_ecosystem
+                        .get(&(eco, first.wildcard_name(Some(eco))))
+                        .cloned()
+                        .unwrap_or_default(),
+                    None => old_ecosystems
+                        .iter()
+                        .filter_map(|eco| {
+                            old_by_own.get(&(eco.clone(), first.wildcard_name(eco.as_deref())))
+                        })
+                        .flatten()
+                        .cloned()
+                        .collect(),
+                };
+                let old_ids: Vec<ComponentId> = old_ids
+                    .into_iter()
+                    .filter(|id| !wildcard_consumed.contains(id))
+                    .collect();
+                if old_ids.is_empty() {
+                    continue;
                 }
-                for (_, new_id) in &pairs {
+                let bucket = format!("{name} (any ecosystem)");
+                let pairs =
+                    Self::align_bucket(&bucket, &old_ids, &new_ids, &old, &new, log.as_mut());
+                for (old_id, new_id) in &pairs {
+                    wildcard_consumed.insert(old_id.clone());
                     matched_new.insert(new_id.clone());
                 }
-                identity_pairs.extend(pairs);
+                identity_pairs.extend(
+                    pairs
+                        .into_iter()
+                        .map(|(o, n)| (o, n, MatchReason::WildcardIdentity)),
+                );
             }
         }
 
-        identity_pairs.sort_by(|a, b| a.1.cmp(&b.1));
-        for (old_id, new_id) in identity_pairs {
-            let (Some(old_comp), Some(new_comp)) =
-                (old.components.get(&old_id), new.components.get(&new_id))
-            else {
+        // 2c. components without a purl on either side — OS packages and
+        // firmware, whose ids are property hashes — are often named
+        // differently by different generators but carry the same CPE. what
+        // is still unmatched pairs up by CPE vendor:product, aligned by
+        // version like the name buckets above.
+        let paired_old: HashSet<ComponentId> = identity_pairs
+            .iter()
+            .map(|(old_id, ..)| old_id.clone())
+            .collect();
+        let old_cpe_buckets = Self::cpe_buckets(&old, |id| {
+            !matched_old.contains(id) && !paired_old.contains(id)
+        });
+        let new_cpe_buckets = Self::cpe_buckets(&new, |id| !matched_new.contains(id));
+        let mut cpe_consumed: HashSet<ComponentId> = HashSet::new();
+        for (key, new_ids) in &new_cpe_buckets {
+            let Some(old_ids) = old_cpe_buckets.get(key) else {
                 continue;
             };
-            matched_old.insert(old_id.clone());
-            matched_new.insert(new_id.clone());
-            id_mapping.insert(old_id.clone(), new_id.clone());
-
-            let fields = Self::compute_fields(old_comp, new_comp, only);
+            // a component with several CPEs sits in several buckets
+            let new_ids: Vec<ComponentId> = new_ids
+                .iter()
+                .filter(|id| !matched_new.contains(*id))
+                .cloned()
+                .collect();
+            let old_ids: Vec<ComponentId> = old_ids
+                .iter()
+                .filter(|id| !cpe_consumed.contains(*id))
+                .cloned()
+                .collect();
+            let bucket = format!("cpe {}:{}", key.0, key.1);
+            let pairs = Self::align_bucket(&bucket, &old_ids, &new_ids, &old, &new, log.as_mut());
+            for (old_id, new_id) in &pairs {
+                cpe_consumed.insert(old_id.clone());
+                matched_new.insert(new_id.clone());
+            }
+            identity_pairs.extend(pairs.into_iter().map(|(o, n)| (o, n, MatchReason::Cpe)));
+        }
+
+        // 2d. a purl-less component still unmatched — vendored code,
+        // firmware blobs — may have been renamed outright. the bytes are
+        // the better identity: an identical strong digest pairs it up.
+        let old_hash_buckets = Self::hash_buckets(&old, |id| {
+            !matched_old.contains(id) && !paired_old.contains(id) && !cpe_consumed.contains(id)
+        });
+        let new_hash_buckets = Self::hash_buckets(&new, |id| !matched_new.contains(id));
+        let mut hash_consumed: HashSet<ComponentId> = HashSet::new();
+        for (key, new_ids) in &new_hash_buckets {
+            let Some(old_ids) = old_hash_buckets.get(key) else {
+                continue;
+            };
+            // a component with several digests sits in several buckets
+            let new_ids: Vec<ComponentId> = new_ids
+                .iter()
+                .filter(|id| !matched_new.contains(*id))
+                .cloned()
+                .collect();
+            let old_ids: Vec<ComponentId> = old_ids
+                .iter()
+                .filter(|id| !hash_consumed.contains(*id))
+                .cloned()
+                .collect();
+            let (algorithm, digest) = key;
+            let bucket = format!(
+                "{algorithm}:{}",
+                digest.chars().take(12).collect::<String>()
+            );
+            let pairs = Self::align_bucket(&bucket, &old_ids, &new_ids, &old, &new, log.as_mut());
+            for (old_id, new_id) in &pairs {
+                hash_consumed.insert(old_id.clone());
+                matched_new.insert(new_id.clone());
+            }
+            identity_pairs.extend(
+                pairs
+                    .into_iter()
+                    .map(|(o, n)| (o, n, MatchReason::ContentHash)),
+            );
+        }
+
+        // 2e. hash-id components whose name and version both changed —
+        // vendored code moved and renamed — have nothing left to match on
+        // but where they sit: an old and a new one with the same paired
+        // parents and children are taken to be the same component.
+        let mut provisional = id_mapping.clone();
+        provisional.extend(
+            identity_pairs
+                .iter()
+                .map(|(o, n, _)| (o.clone(), n.clone())),
+        );
+        let old_ids: Vec<ComponentId> = old
+            .components
+            .keys()
+            .filter(|id| id.as_str().starts_with("h:") && !provisional.contains_key(*id))
+            .cloned()
+            .collect();
+        let new_ids: Vec<ComponentId> = new
+            .components
+            .keys()
+            .filter(|id| id.as_str().starts_with("h:") && !matched_new.contains(*id))
+            .cloned()
+            .collect();
+        let threshold = options.graph_threshold.unwrap_or(DEFAULT_GRAPH_THRESHOLD);
+        for (old_id, new_id, confidence) in Self::pair_by_graph(
+            &old,
+            &new,
+            (old_ids, new_ids),
+            provisional,
+            threshold,
+            log.as_mut(),
+        ) {
+            matched_new.insert(new_id.clone());
+            confidences.insert(new_id.clone(), confidence);
+            identity_pairs.push((old_id, new_id, MatchReason::Graph));
+        }
+
+        identity_pairs.sort_by(|a, b| a.1.cmp(&b.1));
+        for (old_id, new_id, reason) in identity_pairs {
+            let (Some(old_comp), Some(new_comp)) =
+                (old.components.get(&old_id), new.components.get(&new_id))
+            else {
+                continue;
+            };
+            matched_old.insert(old_id.clone());
+            matched_new.insert(new_id.clone());
+            id_mapping.insert(old_id.clone(), new_id.clone());
+            reasons.insert(new_id.clone(), reason);
+
+            let mut fields =
+                Self::compute_fields(old_comp, new_comp, only, compare_properties, options);
+            // paired by a differently spelled name, or by content or place
+            // despite another name: the name is a change
+            let should_include_name = only.is_none_or(|f| f.contains(&Field::Name));
+            if should_include_name
+                && old_comp.name != new_comp.name
+                && (matches!(
+                    reason,
+                    MatchReason::WildcardIdentity | MatchReason::ContentHash | MatchReason::Graph
+                ) || old_comp.identity_name() == new_comp.identity_name())
+            {
+                fields.insert(
+                    0,
+                    FieldChange::Name(old_comp.name.clone(), new_comp.name.clone()),
+                );
+            }
             if !fields.is_empty() {
                 changed_pairs.push((old_id, new_id, fields));
             }
         }
 
+        // the upgrade is reported once: a package moving from the old release
+        // to the new one is no change of its own.
+        if let Some((old_distro, new_distro)) = &distro {
+            let is_upgrade = |old: &BTreeMap<String, String>, new: &BTreeMap<String, String>| {
+                let release =
+                    |q: &BTreeMap<String, String>| q.get("distro").map(|d| d.to_lowercase());
+                release(old).as_ref() == Some(old_distro)
+                    && release(new).as_ref() == Some(new_distro)
+            };
+            for (_, _, fields) in &mut changed_pairs {
+                fields.retain_mut(|f| match f {
+                    FieldChange::Qualifiers(old, new) => {
+                        if is_upgrade(old, new) {
+                            old.remove("distro");
+                            new.remove("distro");
+                        }
+                        !(old.is_empty() && new.is_empty())
+                    }
+                    _ => true,
+                });
+            }
+            changed_pairs.retain(|(_, _, fields)| !fields.is_empty());
+        }
+
         // 3. compute totals (must happen before draining the maps)
         let old_total = old.components.len();
         let new_total = new.components.len();
@@ -588,6 +1835,18 @@ impl Differ {
 
         // 5. build human-readable name map (needs component maps intact)
         let component_names = Self::build_component_names(&old, &new, &edge_diffs);
+        let file_diffs = Self::compute_file_diffs(&old, &new, only);
+        let match_decisions = match log {
+            Some(log) => Self::match_decisions(
+                &old,
+                &new,
+                log,
+                &id_mapping,
+                (&reasons, &confidences),
+                (&matched_old, &matched_new),
+            ),
+            None => Vec::new(),
+        };
 
         // phase 2: drain components by moving them out of the maps, avoiding
         // all Component::clone() calls.
@@ -604,12 +1863,23 @@ impl Differ {
                 }
                 _ => false,
             });
+            let hash_mismatch = old_comp.version.is_some()
+                && old_comp.version == new_comp.version
+                && fields.iter().any(|f| match f {
+                    FieldChange::Hashes(old_hashes, new_hashes) => {
+                        !mismatched_digests(old_hashes, new_hashes).is_empty()
+                    }
+                    _ => false,
+                });
             changed.push(ComponentChange {
                 id: new_comp.id.clone(),
                 old: old_comp,
                 new: new_comp,
                 changes: fields,
                 is_downgrade: downgrade,
+                match_reason: reasons.get(&new_id).copied(),
+                is_hash_mismatch: hash_mismatch,
+                match_confidence: confidences.get(&new_id).copied(),
             });
         }
 
@@ -626,6 +1896,21 @@ impl Differ {
         let added: Vec<Component> = new.components.into_values().collect();
         let removed: Vec<Component> = old.components.into_values().collect();
 
+        let distro_upgrade = distro.map(|(old, new)| DistroUpgrade {
+            old,
+            new,
+            components: changed
+                .iter()
+                .filter(|c| {
+                    let is_os = |c: &Component| c.kind.as_deref() == Some("operating-system");
+                    (is_os_package(&c.old) && is_os_package(&c.new))
+                        || is_os(&c.old)
+                        || is_os(&c.new)
+                })
+                .map(|c| c.id.clone())
+                .collect(),
+        });
+
         Diff {
             added,
             removed,
@@ -636,7 +1921,522 @@ impl Differ {
             new_total,
             unchanged,
             component_names,
+            old_document,
+            new_document,
+            file_diffs,
+            distro_upgrade,
+            match_decisions,
+        }
+    }
+
+    /// turns what matching recorded into [`Diff::match_decisions`]: every
+    /// pair, then the unpaired candidates no later pass picked up, then the
+    /// buckets too large to align.
+    fn match_decisions(
+        old: &Sbom,
+        new: &Sbom,
+        log: MatchLog,
+        pairs: &BTreeMap<ComponentId, ComponentId>,
+        (reasons, confidences): (
+            &BTreeMap<ComponentId, MatchReason>,
+            &BTreeMap<ComponentId, u8>,
+        ),
+        (matched_old, matched_new): (&HashSet<ComponentId>, &HashSet<ComponentId>),
+    ) -> Vec<MatchDecision> {
+        let label = |sbom: &Sbom, id: &ComponentId| match sbom.components.get(id) {
+            Some(comp) => comp.purl.clone().unwrap_or_else(|| component_label(comp)),
+            None => id.to_string(),
+        };
+        let mut decisions: Vec<MatchDecision> = pairs
+            .iter()
+            .map(|(old_id, new_id)| MatchDecision::Paired {
+                old: label(old, old_id),
+                new: label(new, new_id),
+                reason: reasons.get(new_id).copied().unwrap_or(MatchReason::Id),
+                confidence: confidences.get(new_id).copied(),
+            })
+            .collect();
+        for (bucket, old_ids, new_ids) in log.unpaired {
+            let unpaired = |sbom: &Sbom, ids: Vec<ComponentId>, matched: &HashSet<ComponentId>| {
+                ids.iter()
+                    .filter(|id| !matched.contains(*id))
+                    .map(|id| label(sbom, id))
+                    .collect::<Vec<_>>()
+            };
+            let old_ids = unpaired(old, old_ids, matched_old);
+            let new_ids = unpaired(new, new_ids, matched_new);
+            if !old_ids.is_empty() || !new_ids.is_empty() {
+                decisions.push(MatchDecision::Unpaired {
+                    bucket,
+                    old: old_ids,
+                    new: new_ids,
+                });
+            }
+        }
+        decisions.extend(log.oversized);
+        decisions
+    }
+
+    /// compares the file layers of both SBOMs. files are matched by the name
+    /// of the package containing them and their path, so a file keeps its
+    /// identity across a version bump of its package. nothing is reported
+    /// unless both documents list files: which formats and generators emit
+    /// them at all varies.
+    fn compute_file_diffs(old: &Sbom, new: &Sbom, only: Option<&[Field]>) -> Vec<FileDiff> {
+        if old.files.is_empty() || new.files.is_empty() {
+            return Vec::new();
+        }
+        let should_include = |f: Field| only.is_none_or(|fields| fields.contains(&f));
+
+        let old_files = Self::file_index(old);
+        let new_files = Self::file_index(new);
+
+        let mut diffs: BTreeMap<Option<&str>, FileDiff> = BTreeMap::new();
+        for (&(package, path), new_file) in &new_files {
+            let Some(old_file) = old_files.get(&(package, path)) else {
+                diffs
+                    .entry(package)
+                    .or_default()
+                    .added
+                    .push((*new_file).clone());
+                continue;
+            };
+            let mut changes = Vec::new();
+            if should_include(Field::Hashes) && old_file.hashes != new_file.hashes {
+                changes.push(FieldChange::Hashes(
+                    old_file.hashes.clone(),
+                    new_file.hashes.clone(),
+                ));
+            }
+            if should_include(Field::License) && old_file.licenses != new_file.licenses {
+                changes.push(FieldChange::License(
+                    old_file.licenses.clone(),
+                    new_file.licenses.clone(),
+                ));
+            }
+            if !changes.is_empty() {
+                diffs.entry(package).or_default().changed.push(FileChange {
+                    path: path.to_string(),
+                    changes,
+                });
+            }
+        }
+        for (&(package, path), old_file) in &old_files {
+            if !new_files.contains_key(&(package, path)) {
+                diffs
+                    .entry(package)
+                    .or_default()
+                    .removed
+                    .push((*old_file).clone());
+            }
+        }
+        diffs
+            .into_iter()
+            .map(|(package, diff)| FileDiff {
+                package: package.map(str::to_string),
+                ..diff
+            })
+            .collect()
+    }
+
+    /// an SBOM's files keyed by (containing package name, path); the first
+    /// of several entries with one key wins.
+    fn file_index(sbom: &Sbom) -> BTreeMap<(Option<&str>, &str), &FileEntry> {
+        let mut files = BTreeMap::new();
+        for file in &sbom.files {
+            let package = file
+                .package
+                .as_ref()
+                .and_then(|id| sbom.components.get(id))
+                .map(|c| c.name.as_str());
+            files.entry((package, file.path.as_str())).or_insert(file);
+        }
+        files
+    }
+
+    /// pairs components named by the user's match rules. only components
+    /// whose id is missing from the other side take part; the first rule
+    /// matching an old component decides its target package, and each
+    /// target's candidates are aligned by version.
+    fn pair_by_rules(
+        old: &Sbom,
+        new: &Sbom,
+        rules: &[MatchRule],
+        mut log: Option<&mut MatchLog>,
+    ) -> Vec<(ComponentId, ComponentId)> {
+        if rules.is_empty() {
+            return Vec::new();
+        }
+        let key = |comp: &Component| comp.purl.as_deref().and_then(match_key);
+        let mut new_by_key: BTreeMap<String, Vec<ComponentId>> = BTreeMap::new();
+        for (id, comp) in &new.components {
+            if let Some(key) = key(comp).filter(|_| !old.components.contains_key(id)) {
+                new_by_key.entry(key).or_default().push(id.clone());
+            }
+        }
+        let mut old_by_target: BTreeMap<String, Vec<ComponentId>> = BTreeMap::new();
+        for (id, comp) in &old.components {
+            if new.components.contains_key(id) {
+                continue;
+            }
+            let target = key(comp).and_then(|key| rules.iter().find_map(|r| r.target(&key)));
+            if let Some(target) = target.filter(|t| new_by_key.contains_key(t)) {
+                old_by_target.entry(target).or_default().push(id.clone());
+            }
+        }
+        let mut pairs = Vec::new();
+        for (target, old_ids) in &old_by_target {
+            let bucket = format!("match map {target}");
+            let new_ids = &new_by_key[target];
+            pairs.extend(Self::align_bucket(
+                &bucket,
+                old_ids,
+                new_ids,
+                old,
+                new,
+                log.as_deref_mut(),
+            ));
+        }
+        pairs
+    }
+
+    /// pairs the unmatched components whose purls agree once their version
+    /// and the ignored qualifiers are left out. within such a bucket, a
+    /// candidate with the same version wins, then the one agreeing on the
+    /// most ignored qualifier values: with `arch` ignored, the amd64 entry
+    /// still pairs with the amd64 one.
+    fn pair_by_qualifiers(
+        old: &Sbom,
+        new: &Sbom,
+        matched_old: &HashSet<ComponentId>,
+        matched_new: &HashSet<ComponentId>,
+        options: &MatchOptions,
+        mut log: Option<&mut MatchLog>,
+    ) -> Vec<(ComponentId, ComponentId)> {
+        let buckets = |sbom: &Sbom, matched: &HashSet<ComponentId>| {
+            let mut buckets: BTreeMap<String, Vec<ComponentId>> = BTreeMap::new();
+            for (id, comp) in &sbom.components {
+                if matched.contains(id) {
+                    continue;
+                }
+                let key = comp
+                    .purl
+                    .as_deref()
+                    .and_then(|p| purl_without_qualifiers(p, &options.ignore_qualifiers))
+                    .and_then(|p| purl_without_version(&p));
+                if let Some(key) = key {
+                    buckets.entry(key).or_default().push(id.clone());
+                }
+            }
+            buckets
+        };
+        let old_buckets = buckets(old, matched_old);
+        let new_buckets = buckets(new, matched_new);
+
+        let ignored = |comp: &Component| -> BTreeMap<String, String> {
+            let mut qualifiers = purl_qualifiers(comp.purl.as_deref().unwrap_or_default());
+            qualifiers.retain(|k, _| options.ignore_qualifiers.contains(k));
+            qualifiers
+        };
+        let mut pairs = Vec::new();
+        for (key, new_ids) in &new_buckets {
+            let Some(old_ids) = old_buckets.get(key) else {
+                continue;
+            };
+            let mut candidates = Vec::new();
+            for new_id in new_ids {
+                let new_comp = &new.components[new_id];
+                let new_qualifiers = ignored(new_comp);
+                for old_id in old_ids {
+                    let old_comp = &old.components[old_id];
+                    let agreeing = ignored(old_comp)
+                        .iter()
+                        .filter(|(k, v)| new_qualifiers.get(*k) == Some(*v))
+                        .count();
+                    let same_version = old_comp.version == new_comp.version;
+                    candidates.push((same_version, agreeing, new_id, old_id));
+                }
+            }
+            // best candidates first; ties keep id order
+            candidates.sort_by(|a, b| {
+                (b.0, b.1)
+                    .cmp(&(a.0, a.1))
+                    .then_with(|| (a.2, a.3).cmp(&(b.2, b.3)))
+            });
+            let mut used_old = HashSet::new();
+            let mut used_new = HashSet::new();
+            for (_, _, new_id, old_id) in candidates {
+                if used_new.contains(new_id) || used_old.contains(old_id) {
+                    continue;
+                }
+                used_new.insert(new_id);
+                used_old.insert(old_id);
+                pairs.push((old_id.clone(), new_id.clone()));
+            }
+            if let Some(log) = log.as_deref_mut() {
+                log.note_unpaired(key, old_ids, new_ids, &used_old, &used_new);
+            }
+        }
+        pairs
+    }
+
+    /// [`align_by_version`](Self::align_by_version) for one bucket,
+    /// noting in `log` what it left unpaired and whether it was too large
+    /// to align.
+    fn align_bucket(
+        bucket: &str,
+        old_ids: &[ComponentId],
+        new_ids: &[ComponentId],
+        old: &Sbom,
+        new: &Sbom,
+        log: Option<&mut MatchLog>,
+    ) -> Vec<(ComponentId, ComponentId)> {
+        let pairs = Self::align_by_version(old_ids, new_ids, old, new);
+        if let Some(log) = log {
+            if old_ids.len() > MAX_ALIGNED_CANDIDATES || new_ids.len() > MAX_ALIGNED_CANDIDATES {
+                log.oversized.push(MatchDecision::Oversized {
+                    bucket: bucket.to_string(),
+                    old: old_ids.len(),
+                    new: new_ids.len(),
+                });
+            }
+            let used_old: HashSet<&ComponentId> = pairs.iter().map(|(o, _)| o).collect();
+            let used_new: HashSet<&ComponentId> = pairs.iter().map(|(_, n)| n).collect();
+            log.note_unpaired(bucket, old_ids, new_ids, &used_old, &used_new);
+        }
+        pairs
+    }
+
+    /// groups the purl-less components `available` admits by their strong
+    /// digests, keyed by canonical algorithm name and digest.
+    fn hash_buckets(
+        sbom: &Sbom,
+        available: impl Fn(&ComponentId) -> bool,
+    ) -> BTreeMap<(String, String), Vec<ComponentId>> {
+        let mut buckets: BTreeMap<(String, String), Vec<ComponentId>> = BTreeMap::new();
+        for (id, comp) in &sbom.components {
+            if comp.purl.is_some() || comp.hashes.is_empty() || !available(id) {
+                continue;
+            }
+            let keys: BTreeSet<(String, String)> = comp
+                .hashes
+                .iter()
+                .filter(|(algorithm, digest)| {
+                    !digest.is_empty()
+                        && hash_algorithm_strength(algorithm)
+                            .is_some_and(|s| s >= MIN_IDENTIFYING_HASH_STRENGTH)
+                })
+                .map(|(algorithm, digest)| (canonical_algorithm_name(algorithm), digest.clone()))
+                .collect();
+            for key in keys {
+                buckets.entry(key).or_default().push(id.clone());
+            }
+        }
+        buckets
+    }
+
+    /// pairs hash-id components by where they sit in the dependency graph.
+    /// a component's neighborhood is its parents and children that are
+    /// already paired, named by their new ids; a candidate pair's
+    /// confidence is the share of their neighborhoods' union they have in
+    /// common, in percent, and needs at least [`MIN_SHARED_NEIGHBORS`] of
+    /// them. an old component pairs with the new one it
+    /// scores highest with, at or above `threshold`, when that one scores
+    /// highest with it too and neither best is tied. each pair grows its
+    /// neighbors' neighborhoods, so this runs in rounds until one pairs
+    /// nothing.
+    fn pair_by_graph(
+        old: &Sbom,
+        new: &Sbom,
+        (mut old_ids, mut new_ids): (Vec<ComponentId>, Vec<ComponentId>),
+        mut mapping: BTreeMap<ComponentId, ComponentId>,
+        threshold: u8,
+        mut log: Option<&mut MatchLog>,
+    ) -> Vec<(ComponentId, ComponentId, u8)> {
+        type Parents<'a> = BTreeMap<&'a ComponentId, Vec<&'a ComponentId>>;
+        fn parents_by_child(sbom: &Sbom) -> Parents<'_> {
+            let mut parents = Parents::new();
+            for (parent, children) in &sbom.dependencies {
+                for child in children.keys() {
+                    parents.entry(child).or_default().push(parent);
+                }
+            }
+            parents
+        }
+        /// (is parent, neighbor id) for each edge touching `id`.
+        fn neighbors<'a>(
+            sbom: &'a Sbom,
+            parents: &Parents<'a>,
+            id: &ComponentId,
+        ) -> Vec<(bool, &'a ComponentId)> {
+            let parents = parents.get(id).into_iter().flatten().map(|&p| (true, p));
+            let children = sbom.dependencies.get(id).into_iter().flatten();
+            parents.chain(children.map(|(c, _)| (false, c))).collect()
+        }
+        let agree = |a: &Option<String>, b: &Option<String>| a.is_none() || b.is_none() || a == b;
+        let (old_parents, new_parents) = (parents_by_child(old), parents_by_child(new));
+
+        let mut pairs = Vec::new();
+        while !old_ids.is_empty() && !new_ids.is_empty() {
+            let round: Vec<(usize, usize, u8)> = {
+                let paired_new: HashSet<&ComponentId> = mapping.values().collect();
+                let old_hoods: Vec<BTreeSet<(bool, &ComponentId)>> = old_ids
+                    .iter()
+                    .map(|id| {
+                        neighbors(old, &old_parents, id)
+                            .into_iter()
+                            .filter_map(|(is_parent, n)| mapping.get(n).map(|n| (is_parent, n)))
+                            .collect()
+                    })
+                    .collect();
+                let new_hoods: Vec<BTreeSet<(bool, &ComponentId)>> = new_ids
+                    .iter()
+                    .map(|id| {
+                        neighbors(new, &new_parents, id)
+                            .into_iter()
+                            .filter(|(_, n)| paired_new.contains(n))
+                            .collect()
+                    })
+                    .collect();
+                let mut by_neighbor: BTreeMap<(bool, &ComponentId), Vec<usize>> = BTreeMap::new();
+                for (j, hood) in new_hoods.iter().enumerate() {
+                    for &n in hood {
+                        by_neighbor.entry(n).or_default().push(j);
+                    }
+                }
+
+                let mut scores: Vec<(usize, usize, u8)> = Vec::new();
+                for (i, hood) in old_hoods.iter().enumerate() {
+                    let mut shared: BTreeMap<usize, usize> = BTreeMap::new();
+                    for n in hood {
+                        for &j in by_neighbor.get(n).into_iter().flatten() {
+                            *shared.entry(j).or_default() += 1;
+                        }
+                    }
+                    let old_comp = &old.components[&old_ids[i]];
+                    for (j, count) in shared {
+                        if count < MIN_SHARED_NEIGHBORS {
+                            continue;
+                        }
+                        let new_comp = &new.components[&new_ids[j]];
+                        if !agree(&old_comp.ecosystem, &new_comp.ecosystem)
+                            || !agree(&old_comp.kind, &new_comp.kind)
+                        {
+                            continue;
+                        }
+                        let union = hood.len() + new_hoods[j].len() - count;
+                        let confidence = (count * 100 / union) as u8;
+                        if confidence >= threshold {
+                            scores.push((i, j, confidence));
+                        }
+                    }
+                }
+
+                // each side's best candidate: (confidence, candidate, tied)
+                let mut best_old: BTreeMap<usize, (u8, usize, bool)> = BTreeMap::new();
+                let mut best_new: BTreeMap<usize, (u8, usize, bool)> = BTreeMap::new();
+                for &(i, j, confidence) in &scores {
+                    for (best, side, other) in [(&mut best_old, i, j), (&mut best_new, j, i)] {
+                        let entry = best.entry(side).or_insert((confidence, other, false));
+                        if confidence > entry.0 {
+                            *entry = (confidence, other, false);
+                        } else if confidence == entry.0 && other != entry.1 {
+                            entry.2 = true;
+                        }
+                    }
+                }
+                let round: Vec<(usize, usize, u8)> = best_old
+                    .iter()
+                    .filter(|(&i, &(confidence, j, tied))| {
+                        !tied && best_new.get(&j) == Some(&(confidence, i, false))
+                    })
+                    .map(|(&i, &(confidence, j, _))| (i, j, confidence))
+                    .collect();
+
+                // nothing more will pair: note the ties that held it up
+                if let Some(log) = log.as_deref_mut().filter(|_| round.is_empty()) {
+                    let no_one = HashSet::new();
+                    for (&i, &(confidence, _, tied)) in &best_old {
+                        let tied_new: Vec<ComponentId> = scores
+                            .iter()
+                            .filter(|&&(o, _, c)| tied && o == i && c == confidence)
+                            .map(|&(_, j, _)| new_ids[j].clone())
+                            .collect();
+                        if !tied_new.is_empty() {
+                            let old_id = std::slice::from_ref(&old_ids[i]);
+                            log.note_unpaired(
+                                "dependency graph",
+                                old_id,
+                                &tied_new,
+                                &no_one,
+                                &no_one,
+                            );
+                        }
+                    }
+                    for (&j, &(confidence, _, tied)) in &best_new {
+                        let tied_old: Vec<ComponentId> = scores
+                            .iter()
+                            .filter(|&&(_, n, c)| tied && n == j && c == confidence)
+                            .map(|&(i, _, _)| old_ids[i].clone())
+                            .collect();
+                        if !tied_old.is_empty() {
+                            let new_id = std::slice::from_ref(&new_ids[j]);
+                            log.note_unpaired(
+                                "dependency graph",
+                                &tied_old,
+                                new_id,
+                                &no_one,
+                                &no_one,
+                            );
+                        }
+                    }
+                }
+                round
+            };
+            if round.is_empty() {
+                break;
+            }
+
+            let (paired_old, paired_new): (HashSet<usize>, HashSet<usize>) =
+                round.iter().map(|&(i, j, _)| (i, j)).unzip();
+            for (i, j, confidence) in round {
+                mapping.insert(old_ids[i].clone(), new_ids[j].clone());
+                pairs.push((old_ids[i].clone(), new_ids[j].clone(), confidence));
+            }
+            let keep = |ids: Vec<ComponentId>, paired: &HashSet<usize>| -> Vec<ComponentId> {
+                ids.into_iter()
+                    .enumerate()
+                    .filter(|(index, _)| !paired.contains(index))
+                    .map(|(_, id)| id)
+                    .collect()
+            };
+            old_ids = keep(old_ids, &paired_old);
+            new_ids = keep(new_ids, &paired_new);
+        }
+        pairs
+    }
+
+    /// groups the purl-less components `available` admits by the CPE
+    /// vendor:product pairs they carry.
+    fn cpe_buckets(
+        sbom: &Sbom,
+        available: impl Fn(&ComponentId) -> bool,
+    ) -> BTreeMap<(String, String), Vec<ComponentId>> {
+        let mut buckets: BTreeMap<(String, String), Vec<ComponentId>> = BTreeMap::new();
+        for (id, comp) in &sbom.components {
+            if comp.purl.is_some() || comp.cpes.is_empty() || !available(id) {
+                continue;
+            }
+            let keys: BTreeSet<(String, String)> = comp
+                .cpes
+                .iter()
+                .filter_map(|cpe| cpe_vendor_product(cpe))
+                .collect();
+            for key in keys {
+                buckets.entry(key).or_default().push(id.clone());
+            }
         }
+        buckets
     }
 
     /// pairs same-identity candidates by aligning them in version order, so that
@@ -882,11 +2682,7 @@ impl Differ {
             // try new SBOM first (edge diffs use new-SBOM IDs), then old
             let comp = new.components.get(id).or_else(|| old.components.get(id));
             if let Some(comp) = comp {
-                let display = match &comp.version {
-                    Some(v) => format!("{}@{}", comp.name, v),
-                    None => comp.name.clone(),
-                };
-                names.insert(id.clone(), display);
+                names.insert(id.clone(), component_label(comp));
             }
         }
 
@@ -895,7 +2691,8 @@ impl Differ {
 
     /// compares two components field-by-field, returning the list of
     /// [`FieldChange`]s. an empty vector means the components are identical
-    /// (modulo fields excluded by `only`).
+    /// (modulo fields excluded by `only`). properties are only compared when
+    /// `compare_properties` is set.
     ///
     /// this is a pure comparison — it does not construct a [`ComponentChange`]
     /// or clone either component. the caller is responsible for building the
@@ -904,6 +2701,8 @@ impl Differ {
         old: &Component,
         new: &Component,
         only: Option<&[Field]>,
+        compare_properties: bool,
+        options: &MatchOptions,
     ) -> Vec<FieldChange> {
         let mut changes = Vec::new();
 
@@ -930,6 +2729,23 @@ impl Differ {
             }
         }
 
+        if should_include(Field::DeclaredLicense)
+            && !old.declared_licenses.is_empty()
+            && !new.declared_licenses.is_empty()
+        {
+            if old.declared_licenses != new.declared_licenses {
+                changes.push(FieldChange::DeclaredLicense(
+                    old.declared_licenses.clone(),
+                    new.declared_licenses.clone(),
+                ));
+            } else if !licensings_equivalent(old.declared_licensing(), new.declared_licensing()) {
+                changes.push(FieldChange::DeclaredLicenseExpression(
+                    old.declared_license_expression.clone(),
+                    new.declared_license_expression.clone(),
+                ));
+            }
+        }
+
         if should_include(Field::Supplier) && old.supplier != new.supplier {
             changes.push(FieldChange::Supplier(
                 old.supplier.clone(),
@@ -937,10 +2753,73 @@ impl Differ {
             ));
         }
 
-        if should_include(Field::Purl) && old.purl != new.purl {
+        if should_include(Field::Copyright) && old.copyright != new.copyright {
+            changes.push(FieldChange::Copyright(
+                old.copyright.clone(),
+                new.copyright.clone(),
+            ));
+        }
+
+        // two spellings of one purl (a `%40` scope, a default qualifier
+        // stated or left out) are the same package coordinates, and so are
+        // purls differing only in the qualifiers ignored for matching, which
+        // are reported on their own below.
+        let canonical = |purl: &Option<String>| {
+            purl.as_deref().map(|p| {
+                purl_without_qualifiers(p, &options.ignore_qualifiers)
+                    .or_else(|| canonical_purl(p))
+                    .unwrap_or_else(|| p.to_string())
+            })
+        };
+        if should_include(Field::Purl)
+            && old.purl != new.purl
+            && canonical(&old.purl) != canonical(&new.purl)
+        {
             changes.push(FieldChange::Purl(old.purl.clone(), new.purl.clone()));
         }
 
+        if should_include(Field::Qualifiers) && !options.ignore_qualifiers.is_empty() {
+            let ignored = |purl: &Option<String>| {
+                let mut qualifiers = purl_qualifiers(purl.as_deref().unwrap_or_default());
+                qualifiers.retain(|k, _| options.ignore_qualifiers.contains(k));
+                qualifiers
+            };
+            let (mut old_qualifiers, mut new_qualifiers) = (ignored(&old.purl), ignored(&new.purl));
+            let same: Vec<String> = old_qualifiers
+                .iter()
+                .filter(|(k, v)| new_qualifiers.get(*k) == Some(*v))
+                .map(|(k, _)| k.clone())
+                .collect();
+            for k in &same {
+                old_qualifiers.remove(k);
+                new_qualifiers.remove(k);
+            }
+            if old_qualifiers != new_qualifiers {
+                changes.push(FieldChange::Qualifiers(old_qualifiers, new_qualifiers));
+            }
+        }
+
+        // only compared when both sides state CPEs (generators differ in
+        // whether they emit them at all), and not for a moved version alone,
+        // which the version field already reports.
+        let cpe_identities = |cpes: &BTreeSet<String>| -> BTreeSet<String> {
+            cpes.iter().map(|cpe| cpe_without_version(cpe)).collect()
+        };
+        if should_include(Field::Cpes)
+            && !old.cpes.is_empty()
+            && !new.cpes.is_empty()
+            && cpe_identities(&old.cpes) != cpe_identities(&new.cpes)
+        {
+            changes.push(FieldChange::Cpes(old.cpes.clone(), new.cpes.clone()));
+        }
+
+        if should_include(Field::ExternalRefs) && old.external_refs != new.external_refs {
+            changes.push(FieldChange::ExternalRefs(
+                old.external_refs.clone(),
+                new.external_refs.clone(),
+            ));
+        }
+
         if should_include(Field::Description) && old.description != new.description {
             changes.push(FieldChange::Description(
                 old.description.clone(),
@@ -959,6 +2838,34 @@ impl Differ {
             ));
         }
 
+        if should_include(Field::Kind)
+            && old.kind.is_some()
+            && new.kind.is_some()
+            && old.kind != new.kind
+        {
+            changes.push(FieldChange::Kind(old.kind.clone(), new.kind.clone()));
+        }
+
+        if compare_properties
+            && should_include(Field::Properties)
+            && old.properties != new.properties
+        {
+            let none = BTreeSet::new();
+            let names: BTreeSet<&String> =
+                old.properties.keys().chain(new.properties.keys()).collect();
+            for name in names {
+                let old_values = old.properties.get(name).unwrap_or(&none);
+                let new_values = new.properties.get(name).unwrap_or(&none);
+                if old_values != new_values {
+                    changes.push(FieldChange::Property(
+                        name.clone(),
+                        old_values.clone(),
+                        new_values.clone(),
+                    ));
+                }
+            }
+        }
+
         changes
     }
 }
@@ -1294,37 +3201,370 @@ mod tests {
     }
 
     #[test]
-    fn test_diff_multiple_field_changes() {
+    fn test_diff_kind_change() {
         let mut old = Sbom::default();
         let mut new = Sbom::default();
 
-        let mut c1 = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
-        c1.licenses.insert("MIT".into());
-        c1.supplier = Some("Old Corp".into());
-        c1.hashes.insert("sha256".into(), "aaa".into());
-
+        let mut c1 = Component::new("alpine".to_string(), Some("3.19".to_string()));
+        c1.kind = Some("library".to_string());
         let mut c2 = c1.clone();
-        c2.version = Some("2.0".into());
-        c2.licenses = BTreeSet::from(["Apache-2.0".into()]);
-        c2.supplier = Some("New Corp".into());
-        c2.hashes.insert("sha256".into(), "bbb".into());
+        c2.kind = Some("operating-system".to_string());
 
         old.components.insert(c1.id.clone(), c1);
         new.components.insert(c2.id.clone(), c2);
 
         let diff = Differ::diff(&old, &new, None);
         assert_eq!(diff.changed.len(), 1);
-        assert_eq!(diff.changed[0].changes.len(), 4);
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![FieldChange::Kind(
+                Some("library".into()),
+                Some("operating-system".into())
+            )]
+        );
+
+        // excluded by --only
+        let diff = Differ::diff(&old, &new, Some(&[Field::Version]));
+        assert!(diff.changed.is_empty());
     }
 
     #[test]
-    fn test_diff_no_changes() {
+    fn test_diff_kind_missing_on_one_side_is_not_a_change() {
         let mut old = Sbom::default();
         let mut new = Sbom::default();
 
-        let c = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
-        old.components.insert(c.id.clone(), c.clone());
-        new.components.insert(c.id.clone(), c);
+        // e.g. CycloneDX against an SPDX document without primaryPackagePurpose
+        let mut c1 = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
+        c1.kind = Some("library".to_string());
+        let mut c2 = c1.clone();
+        c2.kind = None;
+
+        old.components.insert(c1.id.clone(), c1);
+        new.components.insert(c2.id.clone(), c2);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.changed.is_empty());
+        assert_eq!(diff.unchanged, 1);
+    }
+
+    #[test]
+    fn test_diff_declared_license_change() {
+        let mut old = Sbom::default();
+        let mut new = Sbom::default();
+
+        // the concluded license stays put while the declared one drifts
+        let mut c1 = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
+        c1.licenses.insert("MIT".to_string());
+        c1.declared_licenses.insert("MIT".to_string());
+        let mut c2 = c1.clone();
+        c2.declared_licenses = BTreeSet::from(["GPL-3.0-only".to_string()]);
+
+        old.components.insert(c1.id.clone(), c1);
+        new.components.insert(c2.id.clone(), c2);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![FieldChange::DeclaredLicense(
+                BTreeSet::from(["MIT".into()]),
+                BTreeSet::from(["GPL-3.0-only".into()])
+            )]
+        );
+
+        // --only license compares the concluded license alone
+        let diff = Differ::diff(&old, &new, Some(&[Field::License]));
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_declared_license_expression_change() {
+        let mut old = Sbom::default();
+        let mut new = Sbom::default();
+
+        let mut c1 = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
+        c1.declared_licenses = BTreeSet::from(["Apache-2.0".to_string(), "MIT".to_string()]);
+        c1.declared_license_expression = Some("MIT OR Apache-2.0".to_string());
+        let mut c2 = c1.clone();
+        c2.declared_license_expression = Some("MIT AND Apache-2.0".to_string());
+
+        old.components.insert(c1.id.clone(), c1);
+        new.components.insert(c2.id.clone(), c2);
+
+        let diff = Differ::diff(&old, &new, Some(&[Field::DeclaredLicense]));
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![FieldChange::DeclaredLicenseExpression(
+                Some("MIT OR Apache-2.0".into()),
+                Some("MIT AND Apache-2.0".into())
+            )]
+        );
+    }
+
+    #[test]
+    fn test_diff_declared_license_missing_on_one_side_is_not_a_change() {
+        let mut old = Sbom::default();
+        let mut new = Sbom::default();
+
+        // e.g. SPDX against CycloneDX without license acknowledgements
+        let mut c1 = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
+        c1.licenses.insert("MIT".to_string());
+        c1.declared_licenses.insert("MIT".to_string());
+        let mut c2 = c1.clone();
+        c2.declared_licenses.clear();
+
+        old.components.insert(c1.id.clone(), c1);
+        new.components.insert(c2.id.clone(), c2);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_copyright_change() {
+        let mut old = Sbom::default();
+        let mut new = Sbom::default();
+
+        let mut c1 = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
+        c1.copyright = Some("Copyright 2016 Acme Corp".to_string());
+        let mut c2 = c1.clone();
+        c2.copyright = Some("Copyright 2024 Globex Inc".to_string());
+
+        old.components.insert(c1.id.clone(), c1);
+        new.components.insert(c2.id.clone(), c2);
+
+        let diff = Differ::diff(&old, &new, Some(&[Field::Copyright]));
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![FieldChange::Copyright(
+                Some("Copyright 2016 Acme Corp".into()),
+                Some("Copyright 2024 Globex Inc".into())
+            )]
+        );
+
+        let diff = Differ::diff(&old, &new, Some(&[Field::Version]));
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_external_refs_change() {
+        let mut old = Sbom::default();
+        let mut new = Sbom::default();
+
+        let mut c1 = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
+        c1.external_refs.insert(
+            "vcs".into(),
+            BTreeSet::from(["https://github.com/acme/pkg-a".to_string()]),
+        );
+        let mut c2 = c1.clone();
+        c2.external_refs.insert(
+            "vcs".into(),
+            BTreeSet::from(["https://github.com/fork/pkg-a".to_string()]),
+        );
+
+        old.components.insert(c1.id.clone(), c1.clone());
+        new.components.insert(c2.id.clone(), c2.clone());
+
+        let diff = Differ::diff(&old, &new, Some(&[Field::ExternalRefs]));
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![FieldChange::ExternalRefs(
+                c1.external_refs,
+                c2.external_refs
+            )]
+        );
+
+        let diff = Differ::diff(&old, &new, Some(&[Field::Version]));
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_cpes_change() {
+        let mut old = Sbom::default();
+        let mut new = Sbom::default();
+
+        let c1 = cpe_component("pkg-a", "1.0", "cpe:2.3:a:acme:pkg-a:1.0:*:*:*:*:*:*:*");
+        let mut c2 = c1.clone();
+        c2.cpes = BTreeSet::from(["cpe:2.3:a:globex:pkg-a:1.0:*:*:*:*:*:*:*".to_string()]);
+
+        old.components.insert(c1.id.clone(), c1.clone());
+        new.components.insert(c2.id.clone(), c2.clone());
+
+        let diff = Differ::diff(&old, &new, Some(&[Field::Cpes]));
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![FieldChange::Cpes(c1.cpes.clone(), c2.cpes)]
+        );
+
+        // a version bump moves the CPE's version too; that is the version field's news
+        let mut bumped = cpe_component("pkg-a", "1.1", "cpe:2.3:a:acme:pkg-a:1.1:*:*:*:*:*:*:*");
+        bumped.id = c1.id.clone();
+        let new = sbom_of(vec![bumped.clone()]);
+        let diff = Differ::diff(&old, &new, None);
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![FieldChange::Version(Some("1.0".into()), Some("1.1".into()))]
+        );
+
+        // one side not stating CPEs at all is a generator difference
+        bumped.cpes.clear();
+        let new = sbom_of(vec![bumped]);
+        let diff = Differ::diff(&old, &new, Some(&[Field::Cpes]));
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn test_diff_property_changes() {
+        let props = |pairs: &[(&str, &str)]| -> BTreeMap<String, BTreeSet<String>> {
+            let mut map: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+            for (name, value) in pairs {
+                map.entry(name.to_string())
+                    .or_default()
+                    .insert(value.to_string());
+            }
+            map
+        };
+        let mut c1 = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
+        c1.properties = props(&[("kept", "same"), ("changed", "a"), ("removed", "x")]);
+        let mut c2 = c1.clone();
+        c2.properties = props(&[("kept", "same"), ("changed", "b"), ("added", "y")]);
+
+        let diff = Differ::diff(&sbom_of(vec![c1.clone()]), &sbom_of(vec![c2]), None);
+        let set = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<BTreeSet<_>>();
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![
+                FieldChange::Property("added".into(), set(&[]), set(&["y"])),
+                FieldChange::Property("changed".into(), set(&["a"]), set(&["b"])),
+                FieldChange::Property("removed".into(), set(&["x"]), set(&[])),
+            ]
+        );
+
+        // a component losing its last property is still reported...
+        let mut bare = c1.clone();
+        bare.properties.clear();
+        let other = {
+            let mut c = Component::new("pkg-b".to_string(), Some("1.0".to_string()));
+            c.properties = props(&[("kept", "same")]);
+            c
+        };
+        let diff = Differ::diff(
+            &sbom_of(vec![c1.clone(), other.clone()]),
+            &sbom_of(vec![bare.clone(), other]),
+            None,
+        );
+        assert_eq!(diff.changed[0].changes.len(), 3);
+
+        // ...but a document without any properties says nothing about them
+        let diff = Differ::diff(&sbom_of(vec![c1]), &sbom_of(vec![bare]), None);
+        assert!(diff.changed.is_empty());
+    }
+
+    fn file_in(comp: &Component, path: &str, sha256: &str) -> FileEntry {
+        FileEntry {
+            path: path.to_string(),
+            package: Some(comp.id.clone()),
+            hashes: BTreeMap::from([("SHA-256".to_string(), sha256.to_string())]),
+            licenses: BTreeSet::new(),
+            source_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_diff_files_per_package() {
+        let busybox_old = plain_component("busybox", "1.36.0");
+        let busybox_new = plain_component("busybox", "1.36.1");
+        let mut old = sbom_of(vec![busybox_old.clone()]);
+        old.files = vec![
+            file_in(&busybox_old, "/bin/busybox", "AA"),
+            file_in(&busybox_old, "/etc/inittab", "BB"),
+        ];
+        let mut new = sbom_of(vec![busybox_new.clone()]);
+        let mut inittab = file_in(&busybox_new, "/etc/inittab", "bb");
+        inittab.licenses.insert("MIT".to_string());
+        new.files = vec![
+            file_in(&busybox_new, "/bin/busybox", "cc"),
+            inittab,
+            file_in(&busybox_new, "/etc/fstab", "dd"),
+        ];
+
+        let diff = Differ::diff(&old, &new, None);
+        // files follow their package across the version bump
+        assert_eq!(diff.file_diffs.len(), 1);
+        let files = &diff.file_diffs[0];
+        assert_eq!(files.package.as_deref(), Some("busybox"));
+        assert_eq!(files.added.len(), 1);
+        assert_eq!(files.added[0].path, "/etc/fstab");
+        assert!(files.removed.is_empty());
+        let changed: Vec<_> = files.changed.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(changed, ["/bin/busybox", "/etc/inittab"]);
+        // hashes are compared after normalization, so only the content
+        // change of /bin/busybox shows up as one
+        assert!(matches!(
+            files.changed[0].changes[..],
+            [FieldChange::Hashes(..)]
+        ));
+        assert!(matches!(
+            files.changed[1].changes[..],
+            [FieldChange::License(..)]
+        ));
+        assert_eq!(diff.file_change_count(), 3);
+        assert!(!diff.is_empty());
+
+        // --only limits the file fields compared as well
+        let diff = Differ::diff(&old, &new, Some(&[Field::License]));
+        let changed: Vec<_> = diff.file_diffs[0]
+            .changed
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect();
+        assert_eq!(changed, ["/etc/inittab"]);
+    }
+
+    #[test]
+    fn test_diff_files_only_when_both_sides_list_them() {
+        let comp = plain_component("busybox", "1.36.0");
+        let mut old = sbom_of(vec![comp.clone()]);
+        old.files = vec![file_in(&comp, "/bin/busybox", "aa")];
+        let new = sbom_of(vec![comp]);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.file_diffs.is_empty());
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_multiple_field_changes() {
+        let mut old = Sbom::default();
+        let mut new = Sbom::default();
+
+        let mut c1 = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
+        c1.licenses.insert("MIT".into());
+        c1.supplier = Some("Old Corp".into());
+        c1.hashes.insert("sha256".into(), "aaa".into());
+
+        let mut c2 = c1.clone();
+        c2.version = Some("2.0".into());
+        c2.licenses = BTreeSet::from(["Apache-2.0".into()]);
+        c2.supplier = Some("New Corp".into());
+        c2.hashes.insert("sha256".into(), "bbb".into());
+
+        old.components.insert(c1.id.clone(), c1);
+        new.components.insert(c2.id.clone(), c2);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].changes.len(), 4);
+    }
+
+    #[test]
+    fn test_diff_no_changes() {
+        let mut old = Sbom::default();
+        let mut new = Sbom::default();
+
+        let c = Component::new("pkg-a".to_string(), Some("1.0".to_string()));
+        old.components.insert(c.id.clone(), c.clone());
+        new.components.insert(c.id.clone(), c);
 
         let diff = Differ::diff(&old, &new, None);
         assert!(diff.added.is_empty());
@@ -1415,6 +3655,56 @@ mod tests {
         assert!(mc.authors.is_some());
     }
 
+    #[test]
+    fn test_diff_primary_component_and_document_identity() {
+        let app_v1 = npm_component("acme-app", "1.0.0");
+        let app_v2 = npm_component("acme-app", "1.1.0");
+
+        let mut old = sbom_of(vec![app_v1.clone()]);
+        old.metadata.format = Some("spdx".into());
+        old.metadata.name = Some("acme-app-1.0.0".into());
+        old.metadata.primary_component = Some(app_v1.id.clone());
+        let mut new = sbom_of(vec![app_v2.clone()]);
+        new.metadata.format = Some("cyclonedx".into());
+        new.metadata.serial_number = Some("urn:uuid:1".into());
+        new.metadata.bom_version = Some(3);
+        new.metadata.primary_component = Some(app_v2.id.clone());
+
+        let diff = Differ::diff(&old, &new, None);
+        let mc = diff.metadata_changed.as_ref().unwrap();
+        assert_eq!(
+            mc.primary_component,
+            Some((
+                Some("acme-app@1.0.0".to_string()),
+                Some("acme-app@1.1.0".to_string())
+            ))
+        );
+        assert_eq!(diff.old_document.format.as_deref(), Some("spdx"));
+        assert_eq!(diff.old_document.name.as_deref(), Some("acme-app-1.0.0"));
+        assert_eq!(diff.old_document.primary_component, Some(app_v1.id));
+        assert_eq!(
+            diff.new_document.serial_number.as_deref(),
+            Some("urn:uuid:1")
+        );
+        assert_eq!(diff.new_document.bom_version, Some(3));
+        assert_eq!(
+            diff.new_document.describes.as_deref(),
+            Some("acme-app@1.1.0")
+        );
+
+        // a new serial number or revision of the same product is not a
+        // metadata change.
+        let mut renumbered = old.clone();
+        renumbered.metadata.serial_number = Some("urn:uuid:2".into());
+        renumbered.metadata.bom_version = Some(4);
+        let diff = Differ::diff(&old, &renumbered, None);
+        assert!(diff.metadata_changed.is_none());
+        assert_eq!(
+            diff.new_document.describes.as_deref(),
+            Some("acme-app@1.0.0")
+        );
+    }
+
     #[test]
     fn test_diff_filtering() {
         let mut old = Sbom::default();
@@ -1440,39 +3730,492 @@ mod tests {
     }
 
     #[test]
-    fn test_purl_change_same_ecosystem_name_is_change_not_add_remove() {
-        // component with purl in old, different purl in new (same ecosystem+name)
-        // should be treated as a CHANGE with Purl field change, not add/remove
-        let mut old = Sbom::default();
-        let mut new = Sbom::default();
+    fn test_purl_change_same_ecosystem_name_is_change_not_add_remove() {
+        // component with purl in old, different purl in new (same ecosystem+name)
+        // should be treated as a CHANGE with Purl field change, not add/remove
+        let mut old = Sbom::default();
+        let mut new = Sbom::default();
+
+        // old: lodash with one purl
+        let mut c_old = Component::new("lodash".to_string(), Some("4.17.20".to_string()));
+        c_old.purl = Some("pkg:npm/lodash@4.17.20".to_string());
+        c_old.ecosystem = Some("npm".to_string());
+        c_old.id = ComponentId::new(c_old.purl.as_deref(), &[]);
+
+        // new: lodash with updated purl (version bump)
+        let mut c_new = Component::new("lodash".to_string(), Some("4.17.21".to_string()));
+        c_new.purl = Some("pkg:npm/lodash@4.17.21".to_string());
+        c_new.ecosystem = Some("npm".to_string());
+        c_new.id = ComponentId::new(c_new.purl.as_deref(), &[]);
+
+        old.components.insert(c_old.id.clone(), c_old);
+        new.components.insert(c_new.id.clone(), c_new);
+
+        let diff = Differ::diff(&old, &new, None);
+
+        assert_eq!(diff.added.len(), 0, "Should not have added components");
+        assert_eq!(diff.removed.len(), 0, "Should not have removed components");
+
+        assert_eq!(diff.changed.len(), 1, "Should have one changed component");
+
+        let changes = &diff.changed[0].changes;
+        assert!(changes
+            .iter()
+            .any(|c| matches!(c, FieldChange::Version(_, _))));
+        assert!(changes.iter().any(|c| matches!(c, FieldChange::Purl(_, _))));
+    }
+
+    #[test]
+    fn test_purl_spellings_of_one_package_match_by_id() {
+        let spelled = |purl: &str| {
+            let mut c = plain_component("core", "7.0.0");
+            c.purl = Some(purl.to_string());
+            c.id = ComponentId::new(c.purl.as_deref(), &[]);
+            c
+        };
+        let old = sbom_of(vec![spelled("pkg:npm/%40Babel/core@7.0.0")]);
+        let new = sbom_of(vec![spelled(
+            "pkg:npm/@babel/core@7.0.0?repository_url=https://registry.npmjs.org",
+        )]);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.is_empty(), "{:?}", diff);
+        assert_eq!(diff.unchanged, 1);
+    }
+
+    fn os_package(purl: &str) -> Component {
+        let (path, version) = purl.split_once('@').unwrap();
+        let name = path.rsplit('/').next().unwrap();
+        let mut c = plain_component(name, version.split('?').next().unwrap());
+        c.ecosystem = Some("deb".to_string());
+        c.purl = Some(purl.to_string());
+        c.id = ComponentId::new(c.purl.as_deref(), &[]);
+        c
+    }
+
+    fn ignoring(qualifiers: &[&str]) -> MatchOptions {
+        MatchOptions {
+            ignore_qualifiers: qualifiers.iter().map(|q| q.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_ignored_qualifier_change_pairs_and_reports_qualifiers() {
+        let at = |repository: &str| {
+            os_package(&format!(
+                "pkg:deb/debian/openssl@3.0.11?arch=amd64&repository_url={repository}"
+            ))
+        };
+        let old = sbom_of(vec![at("https://deb.debian.org/debian")]);
+        let new = sbom_of(vec![at("https://mirror.example/debian")]);
+
+        // without the option the repository change shows up as a purl change
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.changed[0]
+            .changes
+            .iter()
+            .any(|c| matches!(c, FieldChange::Purl(_, _))));
+
+        let ignore = ignoring(&["repository_url"]);
+        let diff = Differ::diff_with(&old, &new, None, &ignore);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        let qualifiers = |v: &str| BTreeMap::from([("repository_url".to_string(), v.to_string())]);
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![FieldChange::Qualifiers(
+                qualifiers("https://deb.debian.org/debian"),
+                qualifiers("https://mirror.example/debian")
+            )]
+        );
+
+        let diff = Differ::diff_with(&old, &new, Some(&[Field::Purl]), &ignore);
+        assert!(diff.changed.is_empty(), "{:?}", diff.changed);
+    }
+
+    #[test]
+    fn test_ignored_arch_keeps_per_arch_entries_paired() {
+        let old = sbom_of(vec![
+            os_package("pkg:deb/debian/openssl@3.0.11?arch=amd64&distro=debian-12"),
+            os_package("pkg:deb/debian/openssl@3.0.11?arch=arm64&distro=debian-12"),
+            os_package("pkg:deb/debian/openssl@3.0.11?arch=s390x&distro=debian-12"),
+        ]);
+        let new = sbom_of(vec![
+            os_package("pkg:deb/debian/openssl@3.0.15?arch=arm64&distro=debian-13"),
+            os_package("pkg:deb/debian/openssl@3.0.15?arch=amd64&distro=debian-13"),
+        ]);
+
+        let diff = Differ::diff_with(&old, &new, None, &ignoring(&["arch", "distro"]));
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed.len(), 1);
+        assert!(diff.removed[0].purl.as_deref().unwrap().contains("s390x"));
+        assert_eq!(diff.changed.len(), 2);
+        for change in &diff.changed {
+            let arch = |c: &Component| purl_qualifiers(c.purl.as_deref().unwrap())["arch"].clone();
+            assert_eq!(arch(&change.old), arch(&change.new));
+        }
+    }
+
+    #[test]
+    fn test_distro_upgrade_groups_os_package_changes() {
+        let old = sbom_of(vec![
+            os_package("pkg:deb/debian/openssl@3.0.11-1~deb11u2?arch=amd64&distro=debian-11"),
+            os_package("pkg:deb/debian/zlib1g@1.2.13?arch=amd64&distro=debian-11"),
+            npm_component("lodash", "4.17.20"),
+        ]);
+        let new = sbom_of(vec![
+            os_package("pkg:deb/debian/openssl@3.0.15-1~deb12u1?arch=amd64&distro=debian-12"),
+            os_package("pkg:deb/debian/zlib1g@1.2.13?arch=amd64&distro=debian-12"),
+            npm_component("lodash", "4.17.21"),
+        ]);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        let upgrade = diff.distro_upgrade.clone().unwrap();
+        assert_eq!(
+            (upgrade.old.as_str(), upgrade.new.as_str()),
+            ("debian-11", "debian-12")
+        );
+        assert_eq!(upgrade.components.len(), 1);
+
+        let (upgraded, rest) = diff.partition_distro_changes();
+        assert_eq!(upgraded[0].new.name, "openssl");
+        assert!(!upgraded[0]
+            .changes
+            .iter()
+            .any(|c| matches!(c, FieldChange::Qualifiers(_, _))));
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].new.name, "lodash");
+        // the release is reported once: zlib1g only moved with it
+        assert_eq!(diff.unchanged, 1);
+    }
+
+    #[test]
+    fn test_distro_upgrade_from_operating_system_component() {
+        let os = |version: &str| {
+            let mut c = plain_component("alpine", version);
+            c.kind = Some("operating-system".to_string());
+            c
+        };
+        let apk = |version: &str| purl_component("apk", "musl", version);
+        let old = sbom_of(vec![os("3.18.4"), apk("1.2.4-r2")]);
+        let new = sbom_of(vec![os("3.19.0"), apk("1.2.4_git20230717-r4")]);
+
+        let diff = Differ::diff(&old, &new, None);
+        let upgrade = diff.distro_upgrade.unwrap();
+        assert_eq!(
+            (upgrade.old.as_str(), upgrade.new.as_str()),
+            ("alpine-3.18.4", "alpine-3.19.0")
+        );
+        // musl and the operating system itself
+        assert_eq!(upgrade.components.len(), 2);
+
+        // the same release on both sides is no upgrade
+        let diff = Differ::diff(&old, &sbom_of(vec![os("3.18.4"), apk("1.2.4-r3")]), None);
+        assert!(diff.distro_upgrade.is_none());
+    }
+
+    #[test]
+    fn test_filter_drops_distro_upgrade_with_its_packages() {
+        let old = sbom_of(vec![
+            os_package("pkg:deb/debian/zlib1g@1.2.13?distro=debian-11"),
+            npm_component("lodash", "4.17.20"),
+        ]);
+        let new = sbom_of(vec![
+            os_package("pkg:deb/debian/zlib1g@1.3.1?distro=debian-12"),
+            npm_component("lodash", "4.17.21"),
+        ]);
+        let mut diff = Differ::diff(&old, &new, None);
+        assert!(diff.distro_upgrade.is_some());
+        diff.filter_components(
+            &|c: &Component| c.ecosystem.as_deref() == Some("npm"),
+            1,
+            1,
+            &BTreeMap::new(),
+        );
+        assert!(diff.distro_upgrade.is_none());
+    }
+
+    #[test]
+    fn test_names_reconcile_per_ecosystem() {
+        let old = sbom_of(vec![
+            purl_component("pypi", "PyYAML", "6.0"),
+            purl_component("pypi", "typing_extensions", "4.8.0"),
+            purl_component("nuget", "Newtonsoft.Json", "13.0.1"),
+        ]);
+        let new = sbom_of(vec![
+            purl_component("pypi", "pyyaml", "6.0.1"),
+            purl_component("pypi", "typing-extensions", "4.9.0"),
+            purl_component("nuget", "newtonsoft.json", "13.0.3"),
+        ]);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.added.is_empty(), "{:?}", diff.added);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 3);
+        let yaml = diff
+            .changed
+            .iter()
+            .find(|c| c.new.name == "pyyaml")
+            .unwrap();
+        assert_eq!(
+            yaml.changes[0],
+            FieldChange::Name("PyYAML".into(), "pyyaml".into())
+        );
+        assert!(yaml
+            .changes
+            .iter()
+            .any(|c| matches!(c, FieldChange::Version(_, _))));
+
+        let diff = Differ::diff(&old, &new, Some(&[Field::Version]));
+        assert!(diff.changed.iter().all(|c| !c
+            .changes
+            .iter()
+            .any(|f| matches!(f, FieldChange::Name(_, _)))));
+    }
+
+    #[test]
+    fn test_wildcard_names_are_spelled_per_the_stated_ecosystem() {
+        // the side without an ecosystem is compared as the other side's
+        // ecosystem spells names, whichever side that is.
+        let old = plain_component("PyYAML", "6.0");
+        let mut new = purl_component("pypi", "pyyaml", "6.0.1");
+        new.name = "PyYAML".into();
+        for (old, new) in [(old.clone(), new.clone()), (new, old)] {
+            let diff = Differ::diff(&sbom_of(vec![old]), &sbom_of(vec![new]), None);
+            assert!(diff.added.is_empty() && diff.removed.is_empty(), "{diff:?}");
+            assert_eq!(diff.changed.len(), 1);
+        }
+
+        // a differently spelled name is reported once paired
+        let diff = Differ::diff(
+            &sbom_of(vec![plain_component("PyYAML", "6.0")]),
+            &sbom_of(vec![purl_component("pypi", "pyyaml", "6.0.1")]),
+            None,
+        );
+        assert_eq!(diff.changed.len(), 1, "{diff:?}");
+        assert_eq!(
+            diff.changed[0].changes[0],
+            FieldChange::Name("PyYAML".into(), "pyyaml".into())
+        );
+
+        // a maven name meets a bare one without its group
+        let mut maven = plain_component("slf4j-api", "2.0.9");
+        maven.ecosystem = Some("maven".to_string());
+        maven.purl = Some("pkg:maven/org.slf4j/slf4j-api@2.0.9".to_string());
+        maven.id = ComponentId::new(maven.purl.as_deref(), &[]);
+        let bare = plain_component("slf4j-api", "2.0.7");
+        for (old, new) in [(bare.clone(), maven.clone()), (maven, bare)] {
+            let diff = Differ::diff(&sbom_of(vec![old]), &sbom_of(vec![new]), None);
+            assert!(diff.added.is_empty() && diff.removed.is_empty(), "{diff:?}");
+            assert_eq!(diff.changed.len(), 1);
+            assert_eq!(
+                diff.changed[0].match_reason,
+                Some(MatchReason::WildcardIdentity)
+            );
+        }
+    }
+
+    #[test]
+    fn test_maven_names_reconcile_with_their_group() {
+        let mut qualified = plain_component("org.slf4j:slf4j-api", "2.0.7");
+        qualified.ecosystem = Some("maven".to_string());
+        let mut bare = plain_component("slf4j-api", "2.0.9");
+        bare.ecosystem = Some("maven".to_string());
+        bare.purl = Some("pkg:maven/org.slf4j/slf4j-api@2.0.9".to_string());
+        bare.id = ComponentId::new(bare.purl.as_deref(), &[]);
+
+        let diff = Differ::diff(&sbom_of(vec![qualified]), &sbom_of(vec![bare]), None);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(
+            diff.changed[0].changes[0],
+            FieldChange::Name("org.slf4j:slf4j-api".into(), "slf4j-api".into())
+        );
 
-        // old: lodash with one purl
-        let mut c_old = Component::new("lodash".to_string(), Some("4.17.20".to_string()));
-        c_old.purl = Some("pkg:npm/lodash@4.17.20".to_string());
-        c_old.ecosystem = Some("npm".to_string());
-        c_old.id = ComponentId::new(c_old.purl.as_deref(), &[]);
+        // a different group is a different package
+        let mut other = plain_component("com.example:slf4j-api", "2.0.7");
+        other.ecosystem = Some("maven".to_string());
+        let mut bare = plain_component("slf4j-api", "2.0.9");
+        bare.ecosystem = Some("maven".to_string());
+        bare.purl = Some("pkg:maven/org.slf4j/slf4j-api@2.0.9".to_string());
+        bare.id = ComponentId::new(bare.purl.as_deref(), &[]);
+        let diff = Differ::diff(&sbom_of(vec![other]), &sbom_of(vec![bare]), None);
+        assert_eq!((diff.added.len(), diff.removed.len()), (1, 1));
+    }
 
-        // new: lodash with updated purl (version bump)
-        let mut c_new = Component::new("lodash".to_string(), Some("4.17.21".to_string()));
-        c_new.purl = Some("pkg:npm/lodash@4.17.21".to_string());
-        c_new.ecosystem = Some("npm".to_string());
-        c_new.id = ComponentId::new(c_new.purl.as_deref(), &[]);
+    fn mapping(map: &str) -> MatchOptions {
+        MatchOptions {
+            rules: MatchRule::parse_map(map).unwrap(),
+            ..Default::default()
+        }
+    }
 
-        old.components.insert(c_old.id.clone(), c_old);
-        new.components.insert(c_new.id.clone(), c_new);
+    #[test]
+    fn test_match_rule_pairs_a_package_with_its_fork() {
+        let old = sbom_of(vec![npm_component("request", "2.88.2")]);
+        let mut fork = purl_component("npm", "%40cypress/request", "3.0.1");
+        fork.name = "@cypress/request".to_string();
+        let new = sbom_of(vec![fork]);
 
         let diff = Differ::diff(&old, &new, None);
+        assert_eq!((diff.added.len(), diff.removed.len()), (1, 1));
 
-        assert_eq!(diff.added.len(), 0, "Should not have added components");
-        assert_eq!(diff.removed.len(), 0, "Should not have removed components");
+        let diff = Differ::diff_with(
+            &old,
+            &new,
+            None,
+            &mapping("pkg:npm/request -> pkg:npm/@cypress/request"),
+        );
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        let change = &diff.changed[0];
+        assert_eq!(change.match_reason, Some(MatchReason::UserMapping));
+        assert!(change.changes.contains(&FieldChange::Purl(
+            Some("pkg:npm/request@2.88.2".to_string()),
+            Some("pkg:npm/%40cypress/request@3.0.1".to_string())
+        )));
+    }
 
-        assert_eq!(diff.changed.len(), 1, "Should have one changed component");
+    #[test]
+    fn test_match_rule_wildcard_follows_a_group_move() {
+        let old = sbom_of(vec![
+            purl_component("maven", "org.hibernate/hibernate-core", "5.6.15"),
+            purl_component("maven", "org.hibernate/hibernate-envers", "5.6.15"),
+            npm_component("left-pad", "1.3.0"),
+        ]);
+        let new = sbom_of(vec![
+            purl_component("maven", "org.hibernate.orm/hibernate-core", "6.4.4"),
+            purl_component("maven", "org.hibernate.orm/hibernate-envers", "6.4.4"),
+            npm_component("left-pad", "1.3.0"),
+        ]);
+        let diff = Differ::diff_with(
+            &old,
+            &new,
+            None,
+            &mapping("pkg:maven/org.hibernate/* pkg:maven/org.hibernate.orm/*"),
+        );
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        let mut pairs: Vec<(&str, &str)> = diff
+            .changed
+            .iter()
+            .map(|c| (c.old.name.as_str(), c.new.name.as_str()))
+            .collect();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            vec![
+                (
+                    "org.hibernate/hibernate-core",
+                    "org.hibernate.orm/hibernate-core"
+                ),
+                (
+                    "org.hibernate/hibernate-envers",
+                    "org.hibernate.orm/hibernate-envers"
+                ),
+            ]
+        );
+        assert!(diff
+            .changed
+            .iter()
+            .all(|c| c.match_reason == Some(MatchReason::UserMapping)));
+        assert_eq!(diff.unchanged, 1);
+    }
 
-        let changes = &diff.changed[0].changes;
-        assert!(changes
+    #[test]
+    fn test_match_map_parsing() {
+        let rules = MatchRule::parse_map(
+            "# forks\n\npkg:npm/request pkg:npm/%40cypress/request  # moved\n",
+        )
+        .unwrap();
+        assert_eq!(
+            rules,
+            vec![MatchRule {
+                old: "pkg:npm/request".to_string(),
+                new: "pkg:npm/@cypress/request".to_string(),
+            }]
+        );
+
+        let line = |map: &str| MatchRule::parse_map(map).unwrap_err().line;
+        assert_eq!(line("pkg:npm/a pkg:npm/b\npkg:npm/c"), 2);
+        assert_eq!(line("request cypress-request"), 1);
+        assert_eq!(line("pkg:npm/* pkg:npm/*/*"), 1);
+        assert_eq!(line("pkg:npm/a pkg:npm/*"), 1);
+    }
+
+    #[test]
+    fn test_each_pair_records_the_pass_that_matched_it() {
+        let mut licensed = npm_component("same", "1.0.0");
+        licensed.licenses.insert("MIT".to_string());
+        let mut no_ecosystem = plain_component("left-pad", "1.3.1");
+        no_ecosystem.ecosystem = None;
+        let old = sbom_of(vec![
+            npm_component("same", "1.0.0"),
+            npm_component("bumped", "1.0.0"),
+            npm_component("lodash", "3.10.1"),
+            npm_component("lodash", "4.17.20"),
+            npm_component("left-pad", "1.3.0"),
+        ]);
+        let new = sbom_of(vec![
+            licensed,
+            npm_component("bumped", "1.1.0"),
+            npm_component("lodash", "4.17.21"),
+            no_ecosystem,
+        ]);
+        let diff = Differ::diff(&old, &new, None);
+        let reasons: BTreeMap<&str, Option<MatchReason>> = diff
+            .changed
             .iter()
-            .any(|c| matches!(c, FieldChange::Version(_, _))));
-        assert!(changes.iter().any(|c| matches!(c, FieldChange::Purl(_, _))));
+            .map(|c| (c.new.name.as_str(), c.match_reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            BTreeMap::from([
+                ("bumped", Some(MatchReason::Identity)),
+                ("left-pad", Some(MatchReason::WildcardIdentity)),
+                ("lodash", Some(MatchReason::VersionAligned)),
+                ("same", Some(MatchReason::Id)),
+            ])
+        );
+        assert!(diff.match_decisions.is_empty());
+    }
+
+    #[test]
+    fn test_explain_records_unpaired_candidates_and_oversized_buckets() {
+        let mut old_components = vec![
+            npm_component("lodash", "3.10.1"),
+            npm_component("lodash", "4.17.20"),
+        ];
+        old_components.extend(
+            (0..=MAX_ALIGNED_CANDIDATES).map(|i| npm_component("big", &format!("1.0.{i}"))),
+        );
+        let old = sbom_of(old_components);
+        let new = sbom_of(vec![
+            npm_component("lodash", "4.17.21"),
+            npm_component("big", "2.0.0"),
+        ]);
+        let options = MatchOptions {
+            explain: true,
+            ..Default::default()
+        };
+        let diff = Differ::diff_with(&old, &new, None, &options);
+
+        assert!(diff.match_decisions.contains(&MatchDecision::Paired {
+            old: "pkg:npm/lodash@4.17.20".to_string(),
+            new: "pkg:npm/lodash@4.17.21".to_string(),
+            reason: MatchReason::VersionAligned,
+            confidence: None,
+        }));
+        assert!(diff.match_decisions.contains(&MatchDecision::Unpaired {
+            bucket: "lodash (npm)".to_string(),
+            old: vec!["pkg:npm/lodash@3.10.1".to_string()],
+            new: vec![],
+        }));
+        assert!(diff.match_decisions.contains(&MatchDecision::Oversized {
+            bucket: "big (npm)".to_string(),
+            old: MAX_ALIGNED_CANDIDATES + 1,
+            new: 1,
+        }));
     }
 
     #[test]
@@ -2333,6 +5076,181 @@ mod tests {
         assert_eq!(diff.edge_diffs[0].parent, npm_parent.id);
     }
 
+    #[test]
+    fn test_filter_components_by_kind() {
+        let mut old = Sbom::default();
+        let mut new = Sbom::default();
+
+        let mut os = Component::new("debian".into(), Some("12.4".into()));
+        os.kind = Some("operating-system".into());
+        let mut lib_old = Component::new("libssl".into(), Some("3.0.11".into()));
+        lib_old.kind = Some("library".into());
+        let mut lib_new = lib_old.clone();
+        lib_new.version = Some("3.0.13".into());
+        let mut os_new = os.clone();
+        os_new.version = Some("12.5".into());
+        let untyped = Component::new("mystery".into(), Some("1.0".into()));
+
+        old.components.insert(os.id.clone(), os.clone());
+        old.components.insert(lib_old.id.clone(), lib_old.clone());
+        new.components.insert(os_new.id.clone(), os_new.clone());
+        new.components.insert(lib_new.id.clone(), lib_new.clone());
+        new.components.insert(untyped.id.clone(), untyped.clone());
+
+        // the library depends on something new; the OS component does not.
+        new.dependencies.insert(
+            lib_new.id.clone(),
+            BTreeMap::from([(untyped.id.clone(), DependencyKind::Runtime)]),
+        );
+
+        let is_os = |c: &Component| c.kind.as_deref() == Some("operating-system");
+        let mut parent_matches = BTreeMap::new();
+        for (id, comp) in old.components.iter().chain(new.components.iter()) {
+            parent_matches.insert(id.clone(), is_os(comp));
+        }
+
+        let mut diff = Differ::diff(&old, &new, None);
+        assert_eq!(diff.changed.len(), 2);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.edge_diffs.len(), 1);
+
+        diff.filter_components(&is_os, 1, 1, &parent_matches);
+
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].new.name, "debian");
+        assert!(diff.added.is_empty());
+        assert!(diff.edge_diffs.is_empty());
+        assert_eq!(diff.old_total, 1);
+        assert_eq!(diff.new_total, 1);
+        assert_eq!(diff.unchanged, 0);
+    }
+
+    #[test]
+    fn test_filter_drops_ignored_fields_and_components() {
+        let mut bios_old = purl_component("generic", "bios", "1.0");
+        bios_old.description = Some("nightly build 2026-09-01".into());
+        bios_old.hashes = BTreeMap::from([("sha-256".into(), "1f0a".into())]);
+        let mut bios_new = bios_old.clone();
+        bios_new.description = Some("nightly build 2026-10-01".into());
+        bios_new.hashes = BTreeMap::from([("sha-256".into(), "9c3e".into())]);
+        let mut uefi_old = purl_component("generic", "uefi", "2.0");
+        uefi_old.description = Some("a".into());
+        let mut uefi_new = purl_component("generic", "uefi", "2.1");
+        uefi_new.description = Some("b".into());
+        let old = sbom_of(vec![bios_old, uefi_old, npm_component("@acme/tool", "1.0")]);
+        let new = sbom_of(vec![
+            bios_new,
+            uefi_new,
+            npm_component("@acme/tool", "1.1"),
+            plain_component("test-data", "0.1"),
+        ]);
+
+        let ignore = IgnoreFile::parse(
+            "pkg:npm/@acme/*\nname:^test-\npkg:generic/* description\npkg:generic/bios hashes\n",
+        )
+        .unwrap();
+        let mut diff = Differ::diff(&old, &new, None);
+        assert_eq!((diff.changed.len(), diff.added.len()), (3, 1));
+        assert!(diff.changed.iter().any(|c| c.is_hash_mismatch));
+
+        let decide = |c: &Component| ignore.action(c);
+        let (old_total, new_total, parent_matches) = filter_basis(&old, &new, &decide);
+        assert_eq!((old_total, new_total), (2, 2));
+        diff.filter(
+            &decide,
+            old_total,
+            new_total,
+            &parent_matches,
+            &ignore.dropped([&old, &new]),
+        );
+
+        // uefi keeps its version change; bios has nothing left and counts
+        // as unchanged
+        assert_eq!(diff.changed.len(), 1);
+        let fields: Vec<Field> = diff.changed[0].changes.iter().map(|c| c.field()).collect();
+        assert_eq!(fields, [Field::Version, Field::Purl]);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.unchanged, 1);
+    }
+
+    #[test]
+    fn test_filter_drops_ignored_children_from_edge_diffs() {
+        let app = npm_component("app", "1.0");
+        let lib = npm_component("lib", "1.0");
+        let tool = npm_component("@acme/tool", "1.0");
+        let old = sbom_of(vec![app.clone(), lib.clone()]);
+        let mut new = sbom_of(vec![app.clone(), lib.clone(), tool.clone()]);
+        new.dependencies.insert(
+            app.id.clone(),
+            BTreeMap::from([
+                (lib.id.clone(), DependencyKind::Runtime),
+                (tool.id.clone(), DependencyKind::Runtime),
+            ]),
+        );
+        new.dependencies.insert(
+            lib.id.clone(),
+            BTreeMap::from([(tool.id.clone(), DependencyKind::Dev)]),
+        );
+
+        let ignore = IgnoreFile::parse("pkg:npm/@acme/*\n").unwrap();
+        let mut diff = Differ::diff(&old, &new, None);
+        assert_eq!(diff.edge_diffs.len(), 2);
+
+        let decide = |c: &Component| ignore.action(c);
+        let (old_total, new_total, parent_matches) = filter_basis(&old, &new, &decide);
+        diff.filter(
+            &decide,
+            old_total,
+            new_total,
+            &parent_matches,
+            &ignore.dropped([&old, &new]),
+        );
+
+        // app keeps its new edge to lib; lib's only new edge was to the tool
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.edge_diffs.len(), 1, "{:?}", diff.edge_diffs);
+        assert_eq!(diff.edge_diffs[0].parent, app.id);
+        assert_eq!(
+            diff.edge_diffs[0].added.keys().collect::<Vec<_>>(),
+            [&lib.id]
+        );
+        assert!(!diff.component_names.contains_key(&tool.id));
+    }
+
+    #[test]
+    fn test_ignore_file_parsing() {
+        let ignore = IgnoreFile::parse(
+            "# noisy\n\npkg:npm/%40acme/*   # internal\necosystem:GENERIC hashes,Description\n",
+        )
+        .unwrap();
+        assert_eq!(ignore.rules.len(), 2);
+        assert!(
+            matches!(&ignore.rules[0].pattern, IgnorePattern::Purl(p) if p == "pkg:npm/@acme/*")
+        );
+        assert_eq!(
+            ignore.rules[1].fields,
+            vec![Field::Hashes, Field::Description]
+        );
+        let mut comp = purl_component("generic", "bios", "1.0");
+        comp.ecosystem = Some("generic".into());
+        assert!(ignore.ignores(&comp, Field::Hashes));
+        assert!(!ignore.ignores(&comp, Field::Version));
+        assert_eq!(
+            ignore.action(&npm_component("@acme/tool", "1.0")),
+            FilterAction::Drop
+        );
+
+        for (text, message) in [
+            ("pkg:npm/a hashes extra", "line 1: expected a pattern"),
+            ("\nlodash", "line 2: patterns are purl globs"),
+            ("name:(", "line 1: invalid name regex"),
+            ("ecosystem:npm checksum", "line 1: unknown field `checksum`"),
+        ] {
+            let err = IgnoreFile::parse(text).unwrap_err().to_string();
+            assert!(err.starts_with(message), "{text:?}: {err}");
+        }
+    }
+
     #[test]
     fn test_filter_by_ecosystem_prunes_component_names() {
         let mut old = Sbom::default();
@@ -2566,6 +5484,42 @@ mod tests {
         assert!(!diff.changed.iter().any(|c| c.is_downgrade));
     }
 
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "anything:at:all"));
+        assert!(glob_match(
+            "cdx:*:development",
+            "cdx:npm:package:development"
+        ));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(glob_match("syft:location:?:path", "syft:location:0:path"));
+        assert!(!glob_match("syft:package:*", "syft:location:0:path"));
+        assert!(!glob_match("abc", "abcd"));
+        assert!(!glob_match("a*d", "abc"));
+        // case matters: property names are case-sensitive
+        assert!(!glob_match("Syft:*", "syft:package:foundBy"));
+    }
+
+    #[test]
+    fn vcs_repository_strips_what_does_not_name_the_repository() {
+        for url in [
+            "https://github.com/lodash/lodash",
+            "https://github.com/lodash/lodash.git",
+            "https://github.com/Lodash/lodash/",
+            "git+https://github.com/lodash/lodash.git@4.17.21",
+            "git+ssh://git@github.com/lodash/lodash.git#packages/core",
+            "git@github.com:lodash/lodash.git",
+            "git://github.com/lodash/lodash",
+        ] {
+            assert_eq!(vcs_repository(url), "github.com/lodash/lodash", "{url}");
+        }
+        assert_eq!(
+            vcs_repository("git+https://github.com/someone/lodash.git"),
+            "github.com/someone/lodash"
+        );
+    }
+
     #[test]
     fn pair_ecosystem_needs_the_two_sides_to_agree() {
         let deb = deb_component("libfoo", "1.0");
@@ -2704,6 +5658,283 @@ mod tests {
         assert!(!diff.changed.iter().any(|c| c.is_downgrade));
     }
 
+    fn cpe_component(name: &str, version: &str, cpe: &str) -> Component {
+        let mut comp = Component::new(name.to_string(), Some(version.to_string()));
+        comp.cpes.insert(cpe.to_string());
+        comp
+    }
+
+    #[test]
+    fn test_cpe_reconciliation_pairs_renamed_purl_less_components() {
+        let old = sbom_of(vec![
+            cpe_component(
+                "openssl-libs",
+                "3.0.2",
+                "cpe:2.3:a:openssl:openssl:3.0.2:*:*:*:*:*:*:*",
+            ),
+            cpe_component("busybox", "1.36.0", "cpe:/a:busybox:busybox:1.36.0"),
+        ]);
+        let new = sbom_of(vec![
+            cpe_component("openssl", "3.0.7", "cpe:/a:OpenSSL:openssl:3.0.7"),
+            cpe_component("zlib", "1.3", "cpe:2.3:a:zlib:zlib:1.3:*:*:*:*:*:*:*"),
+        ]);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert_eq!(version_pairs(&diff), expect_pairs(&[("3.0.2", "3.0.7")]));
+        assert_eq!(diff.changed[0].old.name, "openssl-libs");
+        assert_eq!(diff.changed[0].new.name, "openssl");
+        assert!(diff.changed[0]
+            .changes
+            .iter()
+            .any(|c| matches!(c, FieldChange::Cpes(..))));
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+    }
+
+    #[test]
+    fn test_cpe_reconciliation_skips_components_with_a_purl() {
+        let mut old_comp = cpe_component(
+            "openssl-libs",
+            "3.0.2",
+            "cpe:2.3:a:openssl:openssl:3.0.2:*:*:*:*:*:*:*",
+        );
+        old_comp.purl = Some("pkg:rpm/redhat/openssl-libs@3.0.2".to_string());
+        let old = sbom_of(vec![old_comp]);
+        let new = sbom_of(vec![cpe_component(
+            "openssl",
+            "3.0.7",
+            "cpe:2.3:a:openssl:openssl:3.0.7:*:*:*:*:*:*:*",
+        )]);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.changed.is_empty());
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+    }
+
+    fn hashed_component(name: &str, version: &str, hashes: &[(&str, &str)]) -> Component {
+        let mut comp = Component::new(name.to_string(), Some(version.to_string()));
+        comp.hashes = hashes
+            .iter()
+            .map(|(alg, digest)| (alg.to_string(), digest.to_string()))
+            .collect();
+        comp
+    }
+
+    #[test]
+    fn test_content_hash_pairs_renamed_purl_less_components() {
+        let digest = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        let old = sbom_of(vec![
+            hashed_component("vendored-zlib", "1.3", &[("sha256", digest)]),
+            hashed_component("blob", "1", &[("md5", "098f6bcd4621d373cade4e832627b4f6")]),
+        ]);
+        let new = sbom_of(vec![
+            hashed_component("zlib", "1.3.0", &[("SHA-256", &digest.to_uppercase())]),
+            hashed_component(
+                "firmware",
+                "1",
+                &[("MD5", "098f6bcd4621d373cade4e832627b4f6")],
+            ),
+        ]);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert_eq!(diff.changed.len(), 1, "{:?}", diff.changed);
+        let change = &diff.changed[0];
+        assert_eq!(change.match_reason, Some(MatchReason::ContentHash));
+        assert_eq!(
+            change.changes[..2],
+            [
+                FieldChange::Name("vendored-zlib".to_string(), "zlib".to_string()),
+                FieldChange::Version(Some("1.3".to_string()), Some("1.3.0".to_string())),
+            ]
+        );
+        // md5 is too weak to vouch for the bytes
+        assert_eq!((diff.added.len(), diff.removed.len()), (1, 1));
+    }
+
+    #[test]
+    fn test_content_hash_skips_components_with_a_purl() {
+        let digest = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        let mut old_comp = hashed_component("left-pad", "1.3.0", &[("sha-256", digest)]);
+        old_comp.purl = Some("pkg:npm/left-pad@1.3.0".to_string());
+        let old = sbom_of(vec![old_comp]);
+        let new = sbom_of(vec![hashed_component(
+            "pad",
+            "1.3.0",
+            &[("sha-256", digest)],
+        )]);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.changed.is_empty());
+        assert_eq!((diff.added.len(), diff.removed.len()), (1, 1));
+    }
+
+    /// an sbom of `components` with the `(parent, child)` edges given.
+    fn graph_of(components: &[&Component], edges: &[(&Component, &Component)]) -> Sbom {
+        let mut sbom = sbom_of(components.iter().map(|c| (*c).clone()).collect());
+        for (parent, child) in edges {
+            sbom.dependencies
+                .entry(parent.id.clone())
+                .or_default()
+                .insert(child.id.clone(), DependencyKind::Runtime);
+        }
+        sbom
+    }
+
+    #[test]
+    fn test_graph_pairs_a_renamed_vendored_component() {
+        let app = plain_component("app", "1.0");
+        let crc = plain_component("crc32", "1.0");
+        let (vendored, bundled) = (
+            plain_component("vendor-zlib", "1.2.13"),
+            plain_component("bundled-zlib", "1.3.1"),
+        );
+        let old = graph_of(
+            &[&app, &crc, &vendored],
+            &[(&app, &vendored), (&vendored, &crc)],
+        );
+        let new = graph_of(
+            &[&app, &crc, &bundled],
+            &[(&app, &bundled), (&bundled, &crc)],
+        );
+
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert!(diff.edge_diffs.is_empty());
+        let change = &diff.changed[0];
+        assert_eq!(change.match_reason, Some(MatchReason::Graph));
+        assert_eq!(change.match_confidence, Some(100));
+        assert!(change.changes.contains(&FieldChange::Name(
+            "vendor-zlib".into(),
+            "bundled-zlib".into()
+        )));
+    }
+
+    #[test]
+    fn test_graph_pairs_down_a_renamed_tree_in_rounds() {
+        let (app, cli, leaf) = (
+            plain_component("app", "1.0"),
+            plain_component("cli", "1.0"),
+            plain_component("leaf", "1.0"),
+        );
+        // a and b are both renamed; b only pairs once a has
+        let (a_old, a_new) = (plain_component("a", "1"), plain_component("a2", "2"));
+        let (b_old, b_new) = (plain_component("b", "1"), plain_component("b2", "2"));
+        let old = graph_of(
+            &[&app, &cli, &leaf, &a_old, &b_old],
+            &[
+                (&app, &a_old),
+                (&cli, &a_old),
+                (&a_old, &b_old),
+                (&b_old, &leaf),
+            ],
+        );
+        let new = graph_of(
+            &[&app, &cli, &leaf, &a_new, &b_new],
+            &[
+                (&app, &a_new),
+                (&cli, &a_new),
+                (&a_new, &b_new),
+                (&b_new, &leaf),
+            ],
+        );
+
+        let diff = Differ::diff(&old, &new, None);
+        let mut names: Vec<(&str, &str)> = diff
+            .changed
+            .iter()
+            .map(|c| (c.old.name.as_str(), c.new.name.as_str()))
+            .collect();
+        names.sort();
+        assert_eq!(names, [("a", "a2"), ("b", "b2")]);
+        assert!(diff
+            .changed
+            .iter()
+            .all(|c| c.match_reason == Some(MatchReason::Graph)));
+    }
+
+    #[test]
+    fn test_graph_threshold_and_ties() {
+        let (app, x, y) = (
+            plain_component("app", "1.0"),
+            plain_component("x", "1.0"),
+            plain_component("y", "1.0"),
+        );
+        let (a_old, a_new) = (plain_component("a", "1"), plain_component("a2", "2"));
+        // the new side lost the edge to y: two of three neighbors shared
+        let old = graph_of(
+            &[&app, &x, &y, &a_old],
+            &[(&app, &a_old), (&a_old, &x), (&a_old, &y)],
+        );
+        let new = graph_of(&[&app, &x, &y, &a_new], &[(&app, &a_new), (&a_new, &x)]);
+
+        let diff = Differ::diff(&old, &new, None);
+        assert!(diff.changed.is_empty());
+        let options = MatchOptions {
+            graph_threshold: Some(60),
+            ..Default::default()
+        };
+        let diff = Differ::diff_with(&old, &new, None, &options);
+        assert_eq!(diff.changed[0].match_confidence, Some(66));
+
+        // two new candidates in the same place: neither is picked
+        let b_new = plain_component("b2", "2");
+        let new = graph_of(
+            &[&app, &x, &y, &a_new, &b_new],
+            &[
+                (&app, &a_new),
+                (&a_new, &x),
+                (&a_new, &y),
+                (&app, &b_new),
+                (&b_new, &x),
+                (&b_new, &y),
+            ],
+        );
+        let options = MatchOptions {
+            explain: true,
+            ..Default::default()
+        };
+        let diff = Differ::diff_with(&old, &new, None, &options);
+        assert!(diff.changed.is_empty());
+        assert!(diff.match_decisions.contains(&MatchDecision::Unpaired {
+            bucket: "dependency graph".to_string(),
+            old: vec!["a@1".to_string()],
+            new: vec!["a2@2".to_string(), "b2@2".to_string()],
+        }));
+    }
+
+    #[test]
+    fn test_same_version_digest_change_is_a_hash_mismatch() {
+        let with = |version: &str, hashes: &[(&str, &str)]| {
+            let mut comp = npm_component("foo", version);
+            comp.hashes = hashes
+                .iter()
+                .map(|(alg, digest)| (alg.to_string(), digest.to_string()))
+                .collect();
+            comp
+        };
+        let mismatch = |old: Component, new: Component| {
+            let diff = Differ::diff(&sbom_of(vec![old]), &sbom_of(vec![new]), None);
+            assert_eq!(diff.changed.len(), 1);
+            diff.changed[0].is_hash_mismatch
+        };
+
+        assert!(mismatch(
+            with("1.2.3", &[("sha512", "aa")]),
+            with("1.2.3", &[("SHA-512", "bb")])
+        ));
+        // a new release is expected to have other bytes
+        assert!(!mismatch(
+            with("1.2.3", &[("sha512", "aa")]),
+            with("1.2.4", &[("sha512", "bb")])
+        ));
+        // a digest only one side lists proves nothing
+        assert!(!mismatch(
+            with("1.2.3", &[("sha512", "aa")]),
+            with("1.2.3", &[("sha512", "aa"), ("sha256", "cc")])
+        ));
+    }
+
     #[test]
     fn test_identity_reconciliation_more_old_than_new() {
         for (survivor, expected) in [("3.0.1", ("3.0.0", "3.0.1")), ("1.0.1", ("1.0.0", "1.0.1"))] {
@@ -1,20 +1,25 @@
+mod archive;
+mod attestation;
 mod format;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
+use archive::Archive;
+use attestation::{SignatureError, VerifyingKey};
 use clap::{Parser, ValueEnum};
-use format::{load_sbom, Format};
+use format::{load_sbom, load_sbom_verified, parse_sbom, Format};
 use sbom_diff::{
-    pair_ecosystem,
+    filter_basis, glob_match, pair_ecosystem,
     renderer::{
         format_option, format_set, CsvRenderer, JsonRenderer, MarkdownRenderer, RenderOptions,
         Renderer, SarifRenderer, SummaryRenderer, TextRenderer,
     },
-    ComponentChange, Differ, Field, FieldChange,
+    vcs_repository, ComponentChange, Differ, Field, FieldChange, FilterAction, IgnoreFile,
+    MatchDecision, MatchOptions, MatchReason, MatchRule,
 };
 use sbom_model::versions::is_version_downgrade_for_ecosystem;
-use sbom_model::{copyleft_obligations_added, is_hash_algorithm_downgrade};
-use sbom_model::{ComponentId, DependencyKind, LicenseRequirement, Licensing, Sbom};
-use std::collections::{BTreeSet, HashSet};
+use sbom_model::{copyleft_obligations_added, is_hash_algorithm_downgrade, mismatched_digests};
+use sbom_model::{Component, ComponentId, DependencyKind, LicenseRequirement, Licensing, Sbom};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt;
 use std::io;
 
@@ -43,6 +48,10 @@ struct Args {
     #[arg(long)]
     allow_license: Vec<String>,
 
+    /// which license --deny-license and --allow-license check
+    #[arg(long, value_enum, default_value_t = LicenseSource::Concluded)]
+    license_source: LicenseSource,
+
     /// only report changes in these fields
     #[arg(long, value_enum, value_delimiter = ',')]
     only: Vec<Field>,
@@ -59,6 +68,56 @@ struct Args {
     #[arg(long)]
     exclude_ecosystem: Vec<String>,
 
+    /// only show changes for these component kinds, e.g. library,
+    /// application, operating-system (repeatable)
+    #[arg(long)]
+    include_kind: Vec<String>,
+
+    /// exclude changes for these component kinds (repeatable)
+    #[arg(long)]
+    exclude_kind: Vec<String>,
+
+    /// leave out the components an ignore file lists, or just the fields
+    /// it names for them (default: `.sbom-diff-ignore` in the current
+    /// directory, when there is one)
+    #[arg(long, value_name = "FILE")]
+    ignore_file: Option<String>,
+
+    /// only diff component properties whose names match this glob, e.g.
+    /// 'cdx:npm:*' (repeatable; default: all properties)
+    #[arg(long, value_name = "GLOB")]
+    include_property: Vec<String>,
+
+    /// also diff the files and snippets the documents list (SPDX `files`
+    /// and `snippets`), reported per package
+    #[arg(long)]
+    include_files: bool,
+
+    /// pair components whose purls differ only in this qualifier, e.g.
+    /// distro, arch, repository_url, reporting its change as its own field
+    /// (repeatable)
+    #[arg(long, value_name = "NAME")]
+    ignore_qualifier: Vec<String>,
+
+    /// pair components by a file of `old new` purl patterns, one rename per
+    /// line, before any other matching, e.g. `pkg:npm/request
+    /// pkg:npm/@cypress/request`
+    #[arg(long, value_name = "FILE")]
+    match_map: Option<String>,
+
+    /// the confidence, in percent, a renamed hash-id component needs to be
+    /// paired by its place in the dependency graph: the share of paired
+    /// parents and children it has in common with its candidate
+    /// (default: 75)
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(1..=100))]
+    graph_threshold: Option<u8>,
+
+    /// print to stderr how components were paired: by which rule, which
+    /// candidates were left unpaired, and which buckets were too large to
+    /// align by version
+    #[arg(long)]
+    explain_matching: bool,
+
     /// break down counts by package ecosystem (npm, cargo, pypi, etc)
     #[arg(long)]
     group_by_ecosystem: bool,
@@ -74,6 +133,36 @@ struct Args {
     /// include parser warnings in rendered output
     #[arg(long)]
     show_warnings: bool,
+
+    /// require both inputs to be DSSE envelopes signed by this public key
+    /// (ECDSA P-256 or Ed25519 PEM); exits 4 when a signature is missing or invalid
+    #[arg(long, value_name = "PEM")]
+    verify_key: Option<String>,
+
+    /// archive entry (path or OCI digest) to read the sbom from, when an input
+    /// is a tar/zip archive or OCI layout holding several sboms; both inputs
+    /// must then be archives
+    #[arg(long)]
+    entry: Option<String>,
+
+    /// like --entry, for the old input only
+    #[arg(long, value_name = "ENTRY")]
+    old_entry: Option<String>,
+
+    /// like --entry, for the new input only
+    #[arg(long, value_name = "ENTRY")]
+    new_entry: Option<String>,
+}
+
+/// which of a component's licenses the license policy checks.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum LicenseSource {
+    /// the concluded license, or the declared one where none was concluded.
+    Concluded,
+    /// the license the package's authors declared.
+    Declared,
+    /// both; either one can violate the policy.
+    Both,
 }
 
 /// conditions that trigger a non-zero exit code.
@@ -91,7 +180,8 @@ enum FailOn {
     Deps,
     /// fail if any changed component's license changed or any added component introduces licenses.
     LicenseChanged,
-    /// fail if document metadata changed (timestamp, tools, or authors).
+    /// fail if document metadata changed (timestamp, tools, authors, or the
+    /// primary component).
     MetadataChanged,
     /// fail if any changed component's version went from a higher to a lower value.
     VersionDowngrade,
@@ -99,6 +189,8 @@ enum FailOn {
     SupplierChanged,
     /// fail if any changed component's strongest hash algorithm is weaker than before.
     HashAlgorithmDowngrade,
+    /// fail if any changed component's digest changed while its version did not.
+    HashMismatch,
     /// fail if a changed or added component introduces a copyleft license (e.g. GPL, AGPL) not present before.
     CopyleftAdded,
     /// fail if the new SBOM's dependency graph contains cycles.
@@ -107,6 +199,8 @@ enum FailOn {
     PurlChanged,
     /// fail if any changed component's ecosystem changed.
     EcosystemChanged,
+    /// fail if any changed component's vcs reference now points at a different source repository.
+    VcsChanged,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
@@ -178,11 +272,21 @@ enum Violation {
         old: Option<String>,
         new: Option<String>,
     },
+    VcsChanged {
+        id: ComponentId,
+        old: BTreeSet<String>,
+        new: BTreeSet<String>,
+    },
     HashAlgorithmDowngrade {
         id: ComponentId,
         old_algos: Vec<String>,
         new_algos: Vec<String>,
     },
+    HashMismatch {
+        id: ComponentId,
+        version: Option<String>,
+        algos: Vec<String>,
+    },
     DepsAdded {
         parent: ComponentId,
         child: ComponentId,
@@ -200,6 +304,10 @@ enum Violation {
     MetadataTimestampChanged,
     MetadataToolsChanged,
     MetadataAuthorsChanged,
+    MetadataPrimaryComponentChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
 }
 
 impl fmt::Display for Violation {
@@ -303,6 +411,24 @@ impl fmt::Display for Violation {
                     format_option(new)
                 )
             }
+            Violation::VcsChanged { id, old, new } => {
+                write!(
+                    f,
+                    "vcs changed on component {}: {} -> {} (--fail-on vcs-changed)",
+                    id,
+                    format_set(old),
+                    format_set(new)
+                )
+            }
+            Violation::HashMismatch { id, version, algos } => {
+                write!(
+                    f,
+                    "hash mismatch on component {} at unchanged version {}: {} differs (--fail-on hash-mismatch)",
+                    id,
+                    version.as_deref().unwrap_or("<none>"),
+                    algos.join(", "),
+                )
+            }
             Violation::HashAlgorithmDowngrade {
                 id,
                 old_algos,
@@ -360,6 +486,14 @@ impl fmt::Display for Violation {
                     "document metadata authors changed (--fail-on metadata-changed)"
                 )
             }
+            Violation::MetadataPrimaryComponentChanged { old, new } => {
+                write!(
+                    f,
+                    "document primary component changed: {} -> {} (--fail-on metadata-changed)",
+                    old.as_deref().unwrap_or("<none>"),
+                    new.as_deref().unwrap_or("<none>")
+                )
+            }
         }
     }
 }
@@ -371,8 +505,63 @@ fn main() -> anyhow::Result<()> {
         eprintln!("warning: {w}");
     }
 
-    let old_sbom = load_sbom(&args.old, args.format).context("failed to load old sbom")?;
-    let new_sbom = load_sbom(&args.new, args.format).context("failed to load new sbom")?;
+    let verify_key = args
+        .verify_key
+        .as_deref()
+        .map(read_verifying_key)
+        .transpose()?;
+    let rules = args
+        .match_map
+        .as_deref()
+        .map(read_match_map)
+        .transpose()?
+        .unwrap_or_default();
+    let ignore = match args.ignore_file.as_deref() {
+        Some(path) => read_ignore_file(path)?,
+        None if std::path::Path::new(DEFAULT_IGNORE_FILE).is_file() => {
+            read_ignore_file(DEFAULT_IGNORE_FILE)?
+        }
+        None => IgnoreFile::default(),
+    };
+
+    let old_entry = args.old_entry.as_deref().or(args.entry.as_deref());
+    let new_entry = args.new_entry.as_deref().or(args.entry.as_deref());
+    let old_sbom = load_input(&args.old, old_entry, &args, verify_key.as_ref())
+        .context("failed to load old sbom");
+    let new_sbom = load_input(&args.new, new_entry, &args, verify_key.as_ref())
+        .context("failed to load new sbom");
+
+    // report a signature failure on either input before any other load error.
+    let mut signature_violation = false;
+    for err in [&old_sbom, &new_sbom]
+        .into_iter()
+        .filter_map(|r| r.as_ref().err())
+    {
+        if err.downcast_ref::<SignatureError>().is_some() {
+            eprintln!("error: {err:#} (--verify-key)");
+            signature_violation = true;
+        }
+    }
+    if signature_violation {
+        std::process::exit(4);
+    }
+    let mut old_sbom = old_sbom?;
+    let mut new_sbom = new_sbom?;
+
+    if !args.include_property.is_empty() {
+        let keep = |name: &str| {
+            args.include_property
+                .iter()
+                .any(|pattern| glob_match(pattern, name))
+        };
+        old_sbom.retain_properties(keep);
+        new_sbom.retain_properties(keep);
+    }
+
+    if !args.include_files {
+        old_sbom.files.clear();
+        new_sbom.files.clear();
+    }
 
     for w in old_sbom.warnings.iter().chain(new_sbom.warnings.iter()) {
         eprintln!("warning: {}", w);
@@ -387,60 +576,69 @@ fn main() -> anyhow::Result<()> {
         new_warnings: new_sbom.warnings.clone(),
     };
 
-    let license_violation = check_licenses(&new_sbom, &args.deny_license, &args.allow_license);
-    let cycle_violation = check_cyclic_dependencies(&new_sbom, &args.fail_on);
+    let license_violation = check_licenses(
+        &new_sbom,
+        &ignore,
+        &args.deny_license,
+        &args.allow_license,
+        args.license_source,
+    );
 
-    // build ecosystem filter and pre-count filtered totals.
-    let eco_include: HashSet<String> = args
-        .include_ecosystem
-        .iter()
-        .map(|s| s.to_ascii_lowercase())
-        .collect();
-    let eco_exclude: HashSet<String> = args
-        .exclude_ecosystem
-        .iter()
-        .map(|s| s.to_ascii_lowercase())
-        .collect();
-    let eco_filter_active = !eco_include.is_empty() || !eco_exclude.is_empty();
+    // build ecosystem and kind filters and the ignore file into one
+    // decision per component, and pre-count filtered totals.
+    let eco_filter = ValueFilter::new(&args.include_ecosystem, &args.exclude_ecosystem);
+    let kind_filter = ValueFilter::new(&args.include_kind, &args.exclude_kind);
+    let filter_active = eco_filter.is_active() || kind_filter.is_active() || !ignore.is_empty();
 
-    let eco_matches = |eco: Option<&str>| -> bool {
-        let eco_lower = eco.unwrap_or("unknown").to_ascii_lowercase();
-        if !eco_include.is_empty() && !eco_include.contains(&eco_lower) {
-            return false;
-        }
-        if eco_exclude.contains(&eco_lower) {
-            return false;
+    let decide = |c: &Component| -> FilterAction {
+        if eco_filter.matches(c.ecosystem.as_deref()) && kind_filter.matches(c.kind.as_deref()) {
+            ignore.action(c)
+        } else {
+            FilterAction::Drop
         }
-        true
     };
 
-    let (filtered_old_total, filtered_new_total, component_ecosystems) = if eco_filter_active {
-        // build ecosystem map from both SBOMs
-        let mut eco_map = std::collections::BTreeMap::new();
-        for (id, comp) in old_sbom.components.iter() {
-            eco_map.insert(id.clone(), comp.ecosystem.clone());
-        }
-        for (id, comp) in new_sbom.components.iter() {
-            eco_map.insert(id.clone(), comp.ecosystem.clone());
-        }
-        (
-            old_sbom
-                .components
-                .values()
-                .filter(|c| eco_matches(c.ecosystem.as_deref()))
-                .count(),
-            new_sbom
-                .components
-                .values()
-                .filter(|c| eco_matches(c.ecosystem.as_deref()))
-                .count(),
-            eco_map,
-        )
+    // what the ignore file drops leaves the edge diffs of kept parents and
+    // the graph the cycle gate reads as well; the ecosystem and kind filters
+    // only ever judged edges by parent, and never the cycle gate.
+    let ignored = ignore.dropped([&old_sbom, &new_sbom]);
+    let cycle_violation = if !ignored.is_empty() && args.fail_on.contains(&FailOn::CyclicDependency)
+    {
+        check_cyclic_dependencies(&kept_dependency_graph(&new_sbom, &ignored), &args.fail_on)
+    } else {
+        check_cyclic_dependencies(&new_sbom, &args.fail_on)
+    };
+
+    let (filtered_old_total, filtered_new_total, parent_matches) = if filter_active {
+        filter_basis(&old_sbom, &new_sbom, &decide)
     } else {
         (0, 0, std::collections::BTreeMap::new())
     };
 
-    let mut diff = Differ::diff_owned(
+    if filter_active {
+        // a file goes with the package containing it
+        for sbom in [&mut old_sbom, &mut new_sbom] {
+            let components = &sbom.components;
+            sbom.files.retain(|f| {
+                f.package
+                    .as_ref()
+                    .and_then(|id| components.get(id))
+                    .is_none_or(|c| decide(c) != FilterAction::Drop)
+            });
+        }
+    }
+
+    let match_options = MatchOptions {
+        ignore_qualifiers: args
+            .ignore_qualifier
+            .iter()
+            .map(|q| q.to_ascii_lowercase())
+            .collect(),
+        rules,
+        explain: args.explain_matching,
+        graph_threshold: args.graph_threshold,
+    };
+    let mut diff = Differ::diff_owned_with(
         old_sbom,
         new_sbom,
         if args.only.is_empty() {
@@ -448,17 +646,23 @@ fn main() -> anyhow::Result<()> {
         } else {
             Some(&args.only)
         },
+        &match_options,
     );
 
-    if eco_filter_active {
-        diff.filter_by_ecosystem(
-            &eco_matches,
+    if filter_active {
+        diff.filter(
+            &decide,
             filtered_old_total,
             filtered_new_total,
-            &component_ecosystems,
+            &parent_matches,
+            &ignored,
         );
     }
 
+    for line in explain_matching(&diff.match_decisions) {
+        eprintln!("match: {line}");
+    }
+
     let violations = collect_violations(&diff, &args.fail_on);
     for v in &violations {
         eprintln!("error: {v}");
@@ -500,6 +704,36 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// an `--include-*`/`--exclude-*` flag pair over one component attribute,
+/// compared case-insensitively. a component without the attribute is matched
+/// as `unknown`.
+struct ValueFilter {
+    include: HashSet<String>,
+    exclude: HashSet<String>,
+}
+
+impl ValueFilter {
+    fn new(include: &[String], exclude: &[String]) -> Self {
+        let lower = |values: &[String]| values.iter().map(|s| s.to_ascii_lowercase()).collect();
+        Self {
+            include: lower(include),
+            exclude: lower(exclude),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        !self.include.is_empty() || !self.exclude.is_empty()
+    }
+
+    fn matches(&self, value: Option<&str>) -> bool {
+        let value = value.unwrap_or("unknown").to_ascii_lowercase();
+        if !self.include.is_empty() && !self.include.contains(&value) {
+            return false;
+        }
+        !self.exclude.contains(&value)
+    }
+}
+
 /// reports whether a policy list names this requirement.
 ///
 /// an entry can be the bare identifier, its `+` form, or the full
@@ -529,50 +763,213 @@ fn offending_requirements(
         .join(", ")
 }
 
-fn check_licenses(sbom: &Sbom, deny: &[String], allow: &[String]) -> bool {
+fn read_verifying_key(path: &str) -> anyhow::Result<VerifyingKey> {
+    let pem = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read verification key: {path}"))?;
+    VerifyingKey::from_pem(&pem).with_context(|| format!("invalid verification key: {path}"))
+}
+
+/// the lines `--explain-matching` prints. pairs by id are only counted:
+/// they are most of any diff and never surprising.
+fn explain_matching(decisions: &[MatchDecision]) -> Vec<String> {
+    let by_id = decisions
+        .iter()
+        .filter(|d| {
+            matches!(
+                d,
+                MatchDecision::Paired {
+                    reason: MatchReason::Id,
+                    ..
+                }
+            )
+        })
+        .count();
+    let mut lines = vec![format!("{by_id} components paired by id")];
+    for decision in decisions {
+        match decision {
+            MatchDecision::Paired {
+                reason: MatchReason::Id,
+                ..
+            } => {}
+            MatchDecision::Paired {
+                old,
+                new,
+                reason,
+                confidence,
+            } => {
+                let confidence = confidence
+                    .map(|c| format!(", {c}% confidence"))
+                    .unwrap_or_default();
+                lines.push(format!("{old} -> {new} ({}{confidence})", reason.describe()));
+            }
+            MatchDecision::Unpaired { bucket, old, new } => {
+                for (side, ids) in [("old", old), ("new", new)] {
+                    if !ids.is_empty() {
+                        lines.push(format!("{bucket}: unpaired {side} {}", ids.join(", ")));
+                    }
+                }
+            }
+            MatchDecision::Oversized { bucket, old, new } => lines.push(format!(
+                "{bucket}: {old} old and {new} new candidates, too many to align by version; paired in id order"
+            )),
+        }
+    }
+    lines
+}
+
+/// the ignore file read when `--ignore-file` is not given, if it exists.
+const DEFAULT_IGNORE_FILE: &str = ".sbom-diff-ignore";
+
+fn read_ignore_file(path: &str) -> anyhow::Result<IgnoreFile> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read ignore file: {path}"))?;
+    IgnoreFile::parse(&text).with_context(|| format!("invalid ignore file: {path}"))
+}
+
+fn read_match_map(path: &str) -> anyhow::Result<Vec<MatchRule>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read match map: {path}"))?;
+    MatchRule::parse_map(&text).with_context(|| format!("invalid match map: {path}"))
+}
+
+/// loads one input, reading the sbom out of it when it is an archive or OCI
+/// layout. `entry` picks the sbom inside the archive; naming one for an input
+/// that is not an archive is an error rather than silently unused.
+fn load_input(
+    path: &str,
+    entry: Option<&str>,
+    args: &Args,
+    key: Option<&VerifyingKey>,
+) -> anyhow::Result<Sbom> {
+    if let Some(mut archive) = Archive::open(path)? {
+        let (entry, content) = archive::select_sbom(&mut archive, entry)
+            .with_context(|| format!("could not pick an sbom from {path}"))?;
+        if !args.quiet {
+            eprintln!(
+                "note: {path}: using entry {} ({})",
+                entry.name, entry.reason
+            );
+        }
+        return parse_sbom(content, args.format, key)
+            .with_context(|| format!("archive entry {}", entry.name));
+    }
+    if let Some(entry) = entry {
+        bail!("{path} is not an archive, so it has no entry '{entry}' to read");
+    }
+    match key {
+        Some(key) => load_sbom_verified(path, args.format, key),
+        None => load_sbom(path, args.format),
+    }
+}
+
+/// checks the license policy on every component of `sbom`. a component the
+/// ignore file drops is skipped, and a rule scoped to `license` or
+/// `declared-license` skips just that license source.
+fn check_licenses(
+    sbom: &Sbom,
+    ignore: &IgnoreFile,
+    deny: &[String],
+    allow: &[String],
+    source: LicenseSource,
+) -> bool {
     // SPDX license IDs are case-insensitive per spec (Annex E / clause 10.1).
     let deny_lower: HashSet<String> = deny.iter().map(|s| s.to_ascii_lowercase()).collect();
     let allow_lower: HashSet<String> = allow.iter().map(|s| s.to_ascii_lowercase()).collect();
 
     let mut violation = false;
     for comp in sbom.components.values() {
-        // a component with no license information cannot satisfy an allow-list.
-        if !allow.is_empty() && comp.licenses.is_empty() {
-            eprintln!(
-                "error: component {} has no license information (--allow-license requires it)",
-                comp.id
-            );
-            violation = true;
+        if ignore.action(comp) == FilterAction::Drop {
             continue;
         }
+        let mut licensings = match source {
+            LicenseSource::Concluded => vec![("license", comp.licensing())],
+            LicenseSource::Declared => vec![("declared license", comp.declared_licensing())],
+            // where the document does not separate a declared license, the
+            // concluded one is all there is to check.
+            LicenseSource::Both if comp.declared_licenses.is_empty() => {
+                vec![("license", comp.licensing())]
+            }
+            LicenseSource::Both => vec![
+                ("license", comp.licensing()),
+                ("declared license", comp.declared_licensing()),
+            ],
+        };
+        licensings.retain(|(label, _)| {
+            let field = match *label {
+                "license" => Field::License,
+                _ => Field::DeclaredLicense,
+            };
+            !ignore.ignores(comp, field)
+        });
+        let Some((_, primary)) = licensings.first() else {
+            continue;
+        };
 
-        let licensing = comp.licensing();
-
-        if !deny_lower.is_empty()
-            && !licensing.satisfiable(|req| !requirement_listed(req, &deny_lower))
-        {
+        // a component with no license information cannot satisfy an allow-list.
+        if !allow.is_empty() && primary.ids.is_empty() {
+            let missing = match source {
+                LicenseSource::Declared => "declared license",
+                _ => "license information",
+            };
             eprintln!(
-                "error: license {} is denied (component {})",
-                offending_requirements(licensing, |req| requirement_listed(req, &deny_lower)),
-                comp.id
+                "error: component {} has no {} (--allow-license requires it)",
+                comp.id, missing
             );
             violation = true;
+            continue;
         }
 
-        if !allow_lower.is_empty()
-            && !licensing.satisfiable(|req| requirement_listed(req, &allow_lower))
-        {
-            eprintln!(
-                "error: license {} is not allowed (component {})",
-                offending_requirements(licensing, |req| !requirement_listed(req, &allow_lower)),
-                comp.id
-            );
-            violation = true;
+        for (label, licensing) in licensings {
+            if !deny_lower.is_empty()
+                && !licensing.satisfiable(|req| !requirement_listed(req, &deny_lower))
+            {
+                eprintln!(
+                    "error: {} {} is denied (component {})",
+                    label,
+                    offending_requirements(licensing, |req| requirement_listed(req, &deny_lower)),
+                    comp.id
+                );
+                violation = true;
+            }
+
+            if !allow_lower.is_empty()
+                && !licensing.satisfiable(|req| requirement_listed(req, &allow_lower))
+            {
+                eprintln!(
+                    "error: {} {} is not allowed (component {})",
+                    label,
+                    offending_requirements(licensing, |req| !requirement_listed(req, &allow_lower)),
+                    comp.id
+                );
+                violation = true;
+            }
         }
     }
     violation
 }
 
+/// the dependency graph of `sbom` without the `dropped` components, or their
+/// edges.
+fn kept_dependency_graph(sbom: &Sbom, dropped: &BTreeSet<ComponentId>) -> Sbom {
+    let dependencies = sbom
+        .dependencies
+        .iter()
+        .filter(|(parent, _)| !dropped.contains(parent))
+        .map(|(parent, children)| {
+            let children = children
+                .iter()
+                .filter(|(child, _)| !dropped.contains(child))
+                .map(|(child, kind)| (child.clone(), *kind))
+                .collect();
+            (parent.clone(), children)
+        })
+        .collect();
+    Sbom {
+        dependencies,
+        ..Sbom::default()
+    }
+}
+
 fn check_cyclic_dependencies(sbom: &Sbom, fail_on: &[FailOn]) -> bool {
     if !fail_on.contains(&FailOn::CyclicDependency) {
         return false;
@@ -609,6 +1006,29 @@ fn copyleft_violation(change: &ComponentChange, active: bool) -> Option<Violatio
     })
 }
 
+/// builds the `vcs-changed` violation for a changed component, if both sides
+/// name a source repository and they name different ones. a moved revision
+/// pin is not a change; a reference appearing or disappearing is left to the
+/// diff output.
+fn vcs_violation(
+    id: &ComponentId,
+    old: &BTreeMap<String, BTreeSet<String>>,
+    new: &BTreeMap<String, BTreeSet<String>>,
+) -> Option<Violation> {
+    let (old, new) = (old.get("vcs")?, new.get("vcs")?);
+    let repositories = |urls: &BTreeSet<String>| -> BTreeSet<String> {
+        urls.iter().map(|u| vcs_repository(u)).collect()
+    };
+    if old.is_empty() || new.is_empty() || repositories(old) == repositories(new) {
+        return None;
+    }
+    Some(Violation::VcsChanged {
+        id: id.clone(),
+        old: old.clone(),
+        new: new.clone(),
+    })
+}
+
 /// collects all policy violations from a diff in a single pass per collection.
 ///
 /// instead of iterating `diff.changed` once per condition, builds a set of
@@ -629,6 +1049,7 @@ fn collect_violations(diff: &sbom_diff::Diff, fail_on: &[FailOn]) -> Vec<Violati
     let check_supplier_changed = active.contains(&FailOn::SupplierChanged);
     let check_purl_changed = active.contains(&FailOn::PurlChanged);
     let check_ecosystem_changed = active.contains(&FailOn::EcosystemChanged);
+    let check_vcs_changed = active.contains(&FailOn::VcsChanged);
 
     for comp in &diff.added {
         if check_added {
@@ -677,14 +1098,17 @@ fn collect_violations(diff: &sbom_diff::Diff, fail_on: &[FailOn]) -> Vec<Violati
     let check_changed = active.contains(&FailOn::ChangedComponents);
     let check_version_downgrade = active.contains(&FailOn::VersionDowngrade);
     let check_hash_downgrade = active.contains(&FailOn::HashAlgorithmDowngrade);
+    let check_hash_mismatch = active.contains(&FailOn::HashMismatch);
     let any_field_check = check_missing_hashes
         || check_license_changed
         || check_copyleft_added
         || check_version_downgrade
         || check_supplier_changed
         || check_hash_downgrade
+        || check_hash_mismatch
         || check_purl_changed
-        || check_ecosystem_changed;
+        || check_ecosystem_changed
+        || check_vcs_changed;
 
     for change in &diff.changed {
         if check_changed {
@@ -759,15 +1183,28 @@ fn collect_violations(diff: &sbom_diff::Diff, fail_on: &[FailOn]) -> Vec<Violati
                             new: new_eco.clone(),
                         });
                     }
+                    FieldChange::ExternalRefs(old_refs, new_refs) if check_vcs_changed => {
+                        violations.extend(vcs_violation(&change.id, old_refs, new_refs));
+                    }
                     FieldChange::Hashes(old_hashes, new_hashes)
-                        if check_hash_downgrade
-                            && is_hash_algorithm_downgrade(old_hashes, new_hashes) =>
+                        if check_hash_downgrade || check_hash_mismatch =>
                     {
-                        violations.push(Violation::HashAlgorithmDowngrade {
-                            id: change.id.clone(),
-                            old_algos: old_hashes.keys().cloned().collect(),
-                            new_algos: new_hashes.keys().cloned().collect(),
-                        });
+                        if check_hash_downgrade
+                            && is_hash_algorithm_downgrade(old_hashes, new_hashes)
+                        {
+                            violations.push(Violation::HashAlgorithmDowngrade {
+                                id: change.id.clone(),
+                                old_algos: old_hashes.keys().cloned().collect(),
+                                new_algos: new_hashes.keys().cloned().collect(),
+                            });
+                        }
+                        if check_hash_mismatch && change.is_hash_mismatch {
+                            violations.push(Violation::HashMismatch {
+                                id: change.id.clone(),
+                                version: change.new.version.clone(),
+                                algos: mismatched_digests(old_hashes, new_hashes),
+                            });
+                        }
                     }
                     _ => {}
                 }
@@ -811,6 +1248,12 @@ fn collect_violations(diff: &sbom_diff::Diff, fail_on: &[FailOn]) -> Vec<Violati
             if mc.authors.is_some() {
                 violations.push(Violation::MetadataAuthorsChanged);
             }
+            if let Some((old, new)) = &mc.primary_component {
+                violations.push(Violation::MetadataPrimaryComponentChanged {
+                    old: old.clone(),
+                    new: new.clone(),
+                });
+            }
         }
     }
 
@@ -838,18 +1281,29 @@ fn gate_field_dependencies(gate: FailOn) -> &'static [Field] {
         FailOn::SupplierChanged => &[Field::Supplier],
         FailOn::PurlChanged => &[Field::Purl],
         FailOn::EcosystemChanged => &[Field::Ecosystem],
-        FailOn::HashAlgorithmDowngrade | FailOn::MissingHashes => &[Field::Hashes],
+        FailOn::VcsChanged => &[Field::ExternalRefs],
+        FailOn::HashAlgorithmDowngrade | FailOn::HashMismatch | FailOn::MissingHashes => {
+            &[Field::Hashes]
+        }
         FailOn::Deps => &[Field::Deps],
         // a component only counts as "changed" when one of its compared fields
         // differs, so any excluded field can hide a change from this gate.
         FailOn::ChangedComponents => &[
+            Field::Name,
             Field::Version,
             Field::License,
+            Field::DeclaredLicense,
             Field::Supplier,
+            Field::Copyright,
             Field::Purl,
+            Field::Qualifiers,
+            Field::Cpes,
+            Field::ExternalRefs,
             Field::Description,
             Field::Hashes,
             Field::Ecosystem,
+            Field::Kind,
+            Field::Properties,
         ],
         FailOn::AddedComponents
         | FailOn::RemovedComponents
@@ -932,16 +1386,24 @@ mod tests {
             gate_field_dependencies(FailOn::EcosystemChanged),
             &[Field::Ecosystem]
         );
+        assert_eq!(
+            gate_field_dependencies(FailOn::VcsChanged),
+            &[Field::ExternalRefs]
+        );
         assert_eq!(
             gate_field_dependencies(FailOn::HashAlgorithmDowngrade),
             &[Field::Hashes]
         );
+        assert_eq!(
+            gate_field_dependencies(FailOn::HashMismatch),
+            &[Field::Hashes]
+        );
         assert_eq!(
             gate_field_dependencies(FailOn::MissingHashes),
             &[Field::Hashes]
         );
         assert_eq!(gate_field_dependencies(FailOn::Deps), &[Field::Deps]);
-        assert_eq!(gate_field_dependencies(FailOn::ChangedComponents).len(), 7);
+        assert_eq!(gate_field_dependencies(FailOn::ChangedComponents).len(), 15);
         // structural gates read added/removed/metadata/cycles, not filtered fields
         assert!(gate_field_dependencies(FailOn::AddedComponents).is_empty());
         assert!(gate_field_dependencies(FailOn::RemovedComponents).is_empty());
@@ -949,6 +1411,24 @@ mod tests {
         assert!(gate_field_dependencies(FailOn::CyclicDependency).is_empty());
     }
 
+    #[test]
+    fn value_filter_include_exclude_and_unknown() {
+        let none = ValueFilter::new(&[], &[]);
+        assert!(!none.is_active());
+        assert!(none.matches(None));
+
+        let include = ValueFilter::new(&["Operating-System".into(), "unknown".into()], &[]);
+        assert!(include.is_active());
+        assert!(include.matches(Some("operating-system")));
+        assert!(include.matches(None));
+        assert!(!include.matches(Some("library")));
+
+        let exclude = ValueFilter::new(&[], &["library".into()]);
+        assert!(!exclude.matches(Some("LIBRARY")));
+        assert!(exclude.matches(Some("application")));
+        assert!(exclude.matches(None));
+    }
+
     #[test]
     fn no_only_filter_never_warns() {
         assert!(only_masked_gate_warnings(&[], &[FailOn::VersionDowngrade]).is_empty());
@@ -1007,7 +1487,9 @@ mod tests {
         assert!(w[0].contains("fields")); // plural: several fields excluded
         assert!(w[0].contains("version"));
         // license is included, so it must not appear in the excluded list
-        assert!(!w[0].contains("license"));
+        // (declared-license is a separate field, and excluded)
+        assert!(w[0].contains("declared-license"));
+        assert!(!w[0].replace("declared-license", "").contains("license"));
     }
 
     #[test]
@@ -1026,6 +1508,108 @@ mod tests {
         assert!(w[1].contains("--fail-on version-downgrade"));
     }
 
+    #[test]
+    fn explain_matching_counts_id_pairs_and_lists_the_rest() {
+        let paired = |old: &str, new: &str, reason| MatchDecision::Paired {
+            old: old.into(),
+            new: new.into(),
+            reason,
+            confidence: None,
+        };
+        let lines = explain_matching(&[
+            paired("pkg:npm/a@1", "pkg:npm/a@1", MatchReason::Id),
+            paired("pkg:npm/b@1", "pkg:npm/b@1", MatchReason::Id),
+            paired("pkg:npm/c@1", "pkg:npm/c@2", MatchReason::Identity),
+            MatchDecision::Paired {
+                old: "vendor/zlib@1.2".into(),
+                new: "third_party/zlib@1.3".into(),
+                reason: MatchReason::Graph,
+                confidence: Some(80),
+            },
+            MatchDecision::Unpaired {
+                bucket: "c (npm)".into(),
+                old: vec!["pkg:npm/c@0.9".into()],
+                new: vec![],
+            },
+            MatchDecision::Oversized {
+                bucket: "d (npm)".into(),
+                old: 300,
+                new: 2,
+            },
+        ]);
+        assert_eq!(
+            lines,
+            vec![
+                "2 components paired by id",
+                "pkg:npm/c@1 -> pkg:npm/c@2 (matched by ecosystem and name)",
+                "vendor/zlib@1.2 -> third_party/zlib@1.3 (matched by dependency graph, 80% confidence)",
+                "c (npm): unpaired old pkg:npm/c@0.9",
+                "d (npm): 300 old and 2 new candidates, too many to align by version; paired in id order",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_licenses_skips_only_the_ignored_license_source() {
+        let mut sbom = Sbom::default();
+        let mut c = Component::new("foo".into(), Some("1".into()));
+        c.purl = Some("pkg:npm/foo@1".into());
+        c.licenses.insert("GPL-3.0-only".into());
+        c.declared_licenses.insert("AGPL-3.0-only".into());
+        sbom.components.insert(c.id.clone(), c);
+        let rule = |text: &str| IgnoreFile::parse(text).unwrap();
+        let deny = |license: &str| vec![license.to_string()];
+
+        // a declared-only rule leaves the concluded license checked
+        let declared_only = rule("pkg:npm/foo declared-license");
+        let check = |ignore: &IgnoreFile, license: &str| {
+            check_licenses(&sbom, ignore, &deny(license), &[], LicenseSource::Both)
+        };
+        assert!(check(&declared_only, "GPL-3.0-only"));
+        assert!(!check(&declared_only, "AGPL-3.0-only"));
+        // and the reverse
+        let concluded_only = rule("pkg:npm/foo license");
+        assert!(!check(&concluded_only, "GPL-3.0-only"));
+        assert!(check(&concluded_only, "AGPL-3.0-only"));
+        // a dropped component is not checked at all
+        let dropped = rule("pkg:npm/foo");
+        assert!(!check(&dropped, "GPL-3.0-only"));
+        assert!(!check_licenses(
+            &sbom,
+            &dropped,
+            &[],
+            &["MIT".into()],
+            LicenseSource::Concluded
+        ));
+    }
+
+    #[test]
+    fn test_kept_dependency_graph_drops_filtered_components_and_their_edges() {
+        let mut sbom = Sbom::default();
+        let comps: Vec<Component> = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| Component::new(name.into(), Some("1".into())))
+            .collect();
+        for c in &comps {
+            sbom.components.insert(c.id.clone(), c.clone());
+        }
+        // a -> b -> c -> b
+        let edge = |to: &Component| BTreeMap::from([(to.id.clone(), DependencyKind::Runtime)]);
+        sbom.dependencies
+            .insert(comps[0].id.clone(), edge(&comps[1]));
+        sbom.dependencies
+            .insert(comps[1].id.clone(), edge(&comps[2]));
+        sbom.dependencies
+            .insert(comps[2].id.clone(), edge(&comps[1]));
+        let fail_on = [FailOn::CyclicDependency];
+        assert!(check_cyclic_dependencies(&sbom, &fail_on));
+
+        let graph = kept_dependency_graph(&sbom, &BTreeSet::from([comps[2].id.clone()]));
+        assert!(!check_cyclic_dependencies(&graph, &fail_on));
+        assert_eq!(graph.dependencies.len(), 2);
+        assert!(graph.dependencies[&comps[1].id].is_empty());
+    }
+
     #[test]
     fn test_check_licenses() {
         let mut sbom = Sbom::default();
@@ -1033,10 +1617,102 @@ mod tests {
         c.licenses.insert("GPL-3.0-only".into());
         sbom.components.insert(c.id.clone(), c);
 
-        assert!(check_licenses(&sbom, &["GPL-3.0-only".into()], &[]));
-        assert!(!check_licenses(&sbom, &["MIT".into()], &[]));
-        assert!(check_licenses(&sbom, &[], &["MIT".into()]));
-        assert!(!check_licenses(&sbom, &[], &["GPL-3.0-only".into()]));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &["GPL-3.0-only".into()],
+            &[],
+            LicenseSource::Concluded
+        ));
+        assert!(!check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &["MIT".into()],
+            &[],
+            LicenseSource::Concluded
+        ));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &["MIT".into()],
+            LicenseSource::Concluded
+        ));
+        assert!(!check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &["GPL-3.0-only".into()],
+            LicenseSource::Concluded
+        ));
+    }
+
+    #[test]
+    fn test_check_licenses_source() {
+        let mut sbom = Sbom::default();
+        let mut c = Component::new("a".into(), Some("1".into()));
+        c.licenses.insert("MIT".into());
+        c.declared_licenses.insert("GPL-3.0-only".into());
+        sbom.components.insert(c.id.clone(), c);
+        // no declared license: only the concluded one can be checked
+        let mut c = Component::new("b".into(), Some("1".into()));
+        c.licenses.insert("MIT".into());
+        sbom.components.insert(c.id.clone(), c);
+
+        let deny_gpl = ["GPL-3.0-only".to_string()];
+        assert!(!check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &deny_gpl,
+            &[],
+            LicenseSource::Concluded
+        ));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &deny_gpl,
+            &[],
+            LicenseSource::Declared
+        ));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &deny_gpl,
+            &[],
+            LicenseSource::Both
+        ));
+
+        let allow_mit = ["MIT".to_string()];
+        assert!(!check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &allow_mit,
+            LicenseSource::Concluded
+        ));
+        // "a" declares GPL, "b" declares nothing
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &allow_mit,
+            LicenseSource::Declared
+        ));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &allow_mit,
+            LicenseSource::Both
+        ));
+        let allow_both = ["MIT".to_string(), "GPL-3.0-only".to_string()];
+        assert!(!check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &allow_both,
+            LicenseSource::Both
+        ));
     }
 
     #[test]
@@ -1049,14 +1725,34 @@ mod tests {
         sbom.components.insert(c.id.clone(), c);
 
         // either license triggers deny
-        assert!(check_licenses(&sbom, &["MIT".into()], &[]));
-        assert!(check_licenses(&sbom, &["Apache-2.0".into()], &[]));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &["MIT".into()],
+            &[],
+            LicenseSource::Concluded
+        ));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &["Apache-2.0".into()],
+            &[],
+            LicenseSource::Concluded
+        ));
         // both must be in allow list
-        assert!(check_licenses(&sbom, &[], &["MIT".into()])); // Apache-2.0 not allowed
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &["MIT".into()],
+            LicenseSource::Concluded
+        )); // Apache-2.0 not allowed
         assert!(!check_licenses(
             &sbom,
+            &IgnoreFile::default(),
             &[],
-            &["MIT".into(), "Apache-2.0".into()]
+            &["MIT".into(), "Apache-2.0".into()],
+            LicenseSource::Concluded
         ));
     }
 
@@ -1074,17 +1770,33 @@ mod tests {
         // denying the LicenseRef term should trigger a violation
         assert!(check_licenses(
             &sbom,
+            &IgnoreFile::default(),
             &["LicenseRef-proprietary".into()],
-            &[]
+            &[],
+            LicenseSource::Concluded
         ));
         // denying the SPDX term should also trigger
-        assert!(check_licenses(&sbom, &["Apache-2.0".into()], &[]));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &["Apache-2.0".into()],
+            &[],
+            LicenseSource::Concluded
+        ));
         // allow-list must include both
-        assert!(check_licenses(&sbom, &[], &["Apache-2.0".into()]));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &["Apache-2.0".into()],
+            LicenseSource::Concluded
+        ));
         assert!(!check_licenses(
             &sbom,
+            &IgnoreFile::default(),
             &[],
-            &["Apache-2.0".into(), "LicenseRef-proprietary".into()]
+            &["Apache-2.0".into(), "LicenseRef-proprietary".into()],
+            LicenseSource::Concluded
         ));
     }
 
@@ -1212,7 +1924,13 @@ mod tests {
     fn test_check_licenses_empty_lists() {
         let sbom = Sbom::default();
         // no components, no violations
-        assert!(!check_licenses(&sbom, &[], &[]));
+        assert!(!check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &[],
+            LicenseSource::Concluded
+        ));
     }
 
     #[test]
@@ -1224,13 +1942,31 @@ mod tests {
         sbom.components.insert(c.id.clone(), c);
 
         // no allow-list: unlicensed component is not a violation
-        assert!(!check_licenses(&sbom, &[], &[]));
+        assert!(!check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &[],
+            LicenseSource::Concluded
+        ));
 
         // with allow-list: unlicensed component cannot satisfy it → violation
-        assert!(check_licenses(&sbom, &[], &["MIT".into()]));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &["MIT".into()],
+            LicenseSource::Concluded
+        ));
 
         // with deny-list only: unlicensed component is not a violation (nothing to deny)
-        assert!(!check_licenses(&sbom, &["GPL-3.0-only".into()], &[]));
+        assert!(!check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &["GPL-3.0-only".into()],
+            &[],
+            LicenseSource::Concluded
+        ));
     }
 
     #[test]
@@ -1241,15 +1977,45 @@ mod tests {
         sbom.components.insert(c.id.clone(), c);
 
         // deny: different casing still matches
-        assert!(check_licenses(&sbom, &["gpl-3.0-only".into()], &[]));
-        assert!(check_licenses(&sbom, &["Gpl-3.0-Only".into()], &[]));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &["gpl-3.0-only".into()],
+            &[],
+            LicenseSource::Concluded
+        ));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &["Gpl-3.0-Only".into()],
+            &[],
+            LicenseSource::Concluded
+        ));
 
         // allow: different casing is still accepted
-        assert!(!check_licenses(&sbom, &[], &["gpl-3.0-only".into()]));
-        assert!(!check_licenses(&sbom, &[], &["GPL-3.0-ONLY".into()]));
+        assert!(!check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &["gpl-3.0-only".into()],
+            LicenseSource::Concluded
+        ));
+        assert!(!check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &["GPL-3.0-ONLY".into()],
+            LicenseSource::Concluded
+        ));
 
         // allow: wrong license is still rejected regardless of case
-        assert!(check_licenses(&sbom, &[], &["mit".into()]));
+        assert!(check_licenses(
+            &sbom,
+            &IgnoreFile::default(),
+            &[],
+            &["mit".into()],
+            LicenseSource::Concluded
+        ));
     }
 
     #[test]
@@ -1313,6 +2079,9 @@ mod tests {
                     FieldChange::Hashes(old.hashes.clone(), BTreeMap::new()),
                 ],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -1340,6 +2109,9 @@ mod tests {
                     FieldChange::Hashes(old.hashes.clone(), new.hashes.clone()),
                 ],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -1362,6 +2134,9 @@ mod tests {
                 new: new.clone(),
                 changes: vec![FieldChange::Version(Some("1.0".into()), Some("1.1".into()))],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -1438,6 +2213,9 @@ mod tests {
             new,
             changes: vec![FieldChange::Version(Some("1.0".into()), Some("2.0".into()))],
             is_downgrade: false,
+            match_reason: None,
+            is_hash_mismatch: false,
+            match_confidence: None,
         });
         assert!(!collect_violations(&diff, &[FailOn::ChangedComponents]).is_empty());
     }
@@ -1462,6 +2240,9 @@ mod tests {
                     Some("updated description".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             edge_diffs: vec![],
             ..Diff::default()
@@ -1799,6 +2580,9 @@ mod tests {
                     BTreeSet::from(["GPL-3.0-only".into()]),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -1820,6 +2604,9 @@ mod tests {
                 new,
                 changes: vec![FieldChange::Version(Some("1.0".into()), Some("2.0".into()))],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -1883,6 +2670,9 @@ mod tests {
                     BTreeSet::new(),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -1911,6 +2701,9 @@ mod tests {
                     BTreeSet::from(["GPL-3.0-only".into()]),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -1945,6 +2738,9 @@ mod tests {
                     BTreeSet::from(["GPL-3.0-only".into()]),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -1977,6 +2773,9 @@ mod tests {
                     BTreeSet::from(["Apache-2.0".into()]),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2006,6 +2805,9 @@ mod tests {
                     BTreeSet::from(["GPL-3.0-only".into()]),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2058,6 +2860,9 @@ mod tests {
                     BTreeSet::from(["GPL-3.0-only".into()]),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2091,6 +2896,9 @@ mod tests {
                     BTreeSet::from(["GPL-3.0-only".into()]),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2113,6 +2921,7 @@ mod tests {
                 timestamp: Some((Some("2024-01-01".into()), Some("2024-01-02".into()))),
                 tools: None,
                 authors: None,
+                primary_component: None,
             }),
             ..Diff::default()
         };
@@ -2120,6 +2929,28 @@ mod tests {
         assert!(!collect_violations(&diff, &[FailOn::MetadataChanged]).is_empty());
     }
 
+    #[test]
+    fn test_collect_violations_metadata_primary_component_changed() {
+        use sbom_diff::{Diff, MetadataChange};
+
+        let diff = Diff {
+            metadata_changed: Some(MetadataChange {
+                timestamp: None,
+                tools: None,
+                authors: None,
+                primary_component: Some((Some("app@1.0.0".into()), Some("app@1.1.0".into()))),
+            }),
+            ..Diff::default()
+        };
+
+        let violations = collect_violations(&diff, &[FailOn::MetadataChanged]);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "document primary component changed: app@1.0.0 -> app@1.1.0 (--fail-on metadata-changed)"
+        );
+    }
+
     #[test]
     fn test_collect_violations_metadata_changed_no_change() {
         use sbom_diff::Diff;
@@ -2137,6 +2968,7 @@ mod tests {
                 timestamp: None,
                 tools: Some((vec!["syft".into()], vec!["trivy".into()])),
                 authors: None,
+                primary_component: None,
             }),
             ..Diff::default()
         };
@@ -2153,6 +2985,7 @@ mod tests {
                 timestamp: Some((Some("2024-01-01".into()), Some("2024-01-02".into()))),
                 tools: Some((vec!["syft".into()], vec!["trivy".into()])),
                 authors: Some((vec!["alice".into()], vec!["bob".into()])),
+                primary_component: None,
             }),
             ..Diff::default()
         };
@@ -2177,6 +3010,7 @@ mod tests {
                 timestamp: Some((Some("2024-01-01".into()), Some("2024-01-02".into()))),
                 tools: None,
                 authors: None,
+                primary_component: None,
             }),
             ..Diff::default()
         };
@@ -2204,6 +3038,9 @@ mod tests {
                     Some("1.5.0".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2228,6 +3065,9 @@ mod tests {
                     Some("2.0.0".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2261,6 +3101,9 @@ mod tests {
                     Some("New Corp".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2283,6 +3126,9 @@ mod tests {
                 new,
                 changes: vec![FieldChange::Version(None, Some("1.0.0".into()))],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2307,6 +3153,9 @@ mod tests {
                     Some("1.0.0".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2342,6 +3191,9 @@ mod tests {
                     Some("Evil Corp".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2366,6 +3218,9 @@ mod tests {
                     Some("2.0.0".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2396,6 +3251,9 @@ mod tests {
                 new: new.clone(),
                 changes: vec![FieldChange::Supplier(None, Some("New Corp".into()))],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2418,6 +3276,9 @@ mod tests {
                 new: new.clone(),
                 changes: vec![FieldChange::Supplier(Some("Old Corp".into()), None)],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2473,6 +3334,9 @@ mod tests {
                     Some("Evil Corp".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2509,6 +3373,9 @@ mod tests {
                     Some("pkg:npm/pkg-typo@1.0.0".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2536,6 +3403,9 @@ mod tests {
                     Some("2.0.0".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2562,6 +3432,9 @@ mod tests {
                     Some("cargo".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2589,6 +3462,9 @@ mod tests {
                     Some("2.0.0".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2596,6 +3472,124 @@ mod tests {
         assert!(collect_violations(&diff, &[FailOn::EcosystemChanged]).is_empty());
     }
 
+    fn vcs_diff(old_url: &str, new_url: &str) -> sbom_diff::Diff {
+        use sbom_diff::{ComponentChange, Diff, FieldChange};
+
+        let mut old = Component::new("lodash".into(), Some("4.17.21".into()));
+        old.external_refs
+            .insert("vcs".into(), BTreeSet::from([old_url.to_string()]));
+        let mut new = Component::new("lodash".into(), Some("4.17.21".into()));
+        new.external_refs
+            .insert("vcs".into(), BTreeSet::from([new_url.to_string()]));
+
+        Diff {
+            changed: vec![ComponentChange {
+                id: old.id.clone(),
+                changes: vec![FieldChange::ExternalRefs(
+                    old.external_refs.clone(),
+                    new.external_refs.clone(),
+                )],
+                old,
+                new,
+                is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
+            }],
+            ..Diff::default()
+        }
+    }
+
+    #[test]
+    fn test_collect_violations_vcs_changed() {
+        let diff = vcs_diff(
+            "https://github.com/lodash/lodash.git",
+            "https://github.com/someone/lodash.git",
+        );
+
+        let violations = collect_violations(&diff, &[FailOn::VcsChanged]);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(violations[0], Violation::VcsChanged { .. }));
+        assert!(violations[0]
+            .to_string()
+            .contains("https://github.com/someone/lodash.git"));
+    }
+
+    #[test]
+    fn test_collect_violations_vcs_changed_ignores_revision_and_spelling() {
+        // same repository, different pin and url form
+        let diff = vcs_diff(
+            "git+https://github.com/lodash/lodash.git@4.17.20",
+            "git@github.com:lodash/lodash",
+        );
+        assert!(collect_violations(&diff, &[FailOn::VcsChanged]).is_empty());
+    }
+
+    #[test]
+    fn test_collect_violations_hash_mismatch() {
+        use sbom_diff::{ComponentChange, Diff, FieldChange};
+
+        let mut old = Component::new("foo".into(), Some("1.2.3".into()));
+        old.hashes.insert("sha-512".into(), "aa".into());
+        let mut new = old.clone();
+        new.hashes.insert("sha-512".into(), "bb".into());
+        let diff = |is_hash_mismatch| Diff {
+            changed: vec![ComponentChange {
+                id: old.id.clone(),
+                changes: vec![FieldChange::Hashes(old.hashes.clone(), new.hashes.clone())],
+                old: old.clone(),
+                new: new.clone(),
+                is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch,
+                match_confidence: None,
+            }],
+            ..Diff::default()
+        };
+
+        let violations = collect_violations(&diff(true), &[FailOn::HashMismatch]);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            format!(
+                "hash mismatch on component {} at unchanged version 1.2.3: SHA-512 differs (--fail-on hash-mismatch)",
+                old.id
+            )
+        );
+        assert!(collect_violations(&diff(false), &[FailOn::HashMismatch]).is_empty());
+        // the other hash gate does not fire on a same-strength digest change
+        assert!(collect_violations(&diff(true), &[FailOn::HashAlgorithmDowngrade]).is_empty());
+    }
+
+    #[test]
+    fn test_collect_violations_vcs_added_is_not_a_change() {
+        use sbom_diff::{ComponentChange, Diff, FieldChange};
+
+        let old = Component::new("lodash".into(), Some("4.17.21".into()));
+        let mut new = old.clone();
+        new.external_refs.insert(
+            "vcs".into(),
+            BTreeSet::from(["https://github.com/someone/lodash".to_string()]),
+        );
+        let diff = Diff {
+            changed: vec![ComponentChange {
+                id: old.id.clone(),
+                changes: vec![FieldChange::ExternalRefs(
+                    old.external_refs.clone(),
+                    new.external_refs.clone(),
+                )],
+                old,
+                new,
+                is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
+            }],
+            ..Diff::default()
+        };
+        assert!(collect_violations(&diff, &[FailOn::VcsChanged]).is_empty());
+    }
+
     #[test]
     fn test_collect_violations_hash_algorithm_downgrade() {
         use sbom_diff::{ComponentChange, Diff, FieldChange};
@@ -2612,6 +3606,9 @@ mod tests {
                 new: new.clone(),
                 changes: vec![FieldChange::Hashes(old.hashes.clone(), new.hashes.clone())],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2635,6 +3632,9 @@ mod tests {
                 new: new.clone(),
                 changes: vec![FieldChange::Hashes(old.hashes.clone(), new.hashes.clone())],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2658,6 +3658,9 @@ mod tests {
                 new: new.clone(),
                 changes: vec![FieldChange::Hashes(old.hashes.clone(), new.hashes.clone())],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2691,6 +3694,9 @@ mod tests {
                     Some("2.0.0".into()),
                 )],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2716,6 +3722,9 @@ mod tests {
                 new: new.clone(),
                 changes: vec![FieldChange::Hashes(old.hashes.clone(), new.hashes.clone())],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2741,6 +3750,9 @@ mod tests {
                 new: new.clone(),
                 changes: vec![FieldChange::Hashes(old.hashes.clone(), BTreeMap::new())],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
@@ -2764,6 +3776,9 @@ mod tests {
                 new: new.clone(),
                 changes: vec![FieldChange::Hashes(old.hashes.clone(), new.hashes.clone())],
                 is_downgrade: false,
+                match_reason: None,
+                is_hash_mismatch: false,
+                match_confidence: None,
             }],
             ..Diff::default()
         };
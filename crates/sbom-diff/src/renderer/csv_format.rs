@@ -1,8 +1,10 @@
 use super::{
-    format_option, format_set, format_vec_or_none, RenderOptions, Renderer, SummaryRenderer,
+    format_option, format_qualifiers, format_set, format_vec_or_none, noted_match_reason,
+    RenderOptions, Renderer, SummaryRenderer,
 };
 use crate::{Diff, FieldChange};
 use sbom_model::is_hash_algorithm_downgrade;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::Write;
 
 /// creates a [`csv::Writer`] configured for this crate's output conventions
@@ -69,12 +71,37 @@ impl Renderer for CsvRenderer {
             wtr.write_record(["removed", display, eco, "version", ver, ""])?;
         }
 
-        for change in &diff.changed {
+        // packages that moved with a distro upgrade follow its row, under
+        // their own status.
+        let (upgraded, changed) = diff.partition_distro_changes();
+        if let Some(upgrade) = &diff.distro_upgrade {
+            wtr.write_record([
+                "distro-upgrade",
+                "",
+                "",
+                "distro",
+                &upgrade.old,
+                &upgrade.new,
+            ])?;
+        }
+        let rows = upgraded
+            .into_iter()
+            .map(|c| ("distro-changed", c))
+            .chain(changed.into_iter().map(|c| ("changed", c)));
+        for (status, change) in rows {
             let display = change.new.purl.as_deref().unwrap_or(change.id.as_str());
             let eco = change.new.ecosystem.as_deref().unwrap_or("");
+            if let Some(reason) = noted_match_reason(change) {
+                let reason = match change.match_confidence {
+                    Some(confidence) => format!("{} ({confidence}%)", reason.as_str()),
+                    None => reason.as_str().to_string(),
+                };
+                wtr.write_record([status, display, eco, "match", "", &reason])?;
+            }
             for fc in &change.changes {
-                let (field, old, new) = csv_field_change(fc, change.is_downgrade);
-                wtr.write_record(["changed", display, eco, field, &old, &new])?;
+                let (field, old, new) =
+                    csv_field_change(fc, change.is_downgrade, change.is_hash_mismatch);
+                wtr.write_record([status, display, eco, field, &old, &new])?;
             }
         }
 
@@ -108,6 +135,24 @@ impl Renderer for CsvRenderer {
             }
         }
 
+        // file rows name the file in the component column; added and removed
+        // ones carry the containing package as their value.
+        for files in &diff.file_diffs {
+            let package = files.package.as_deref().unwrap_or("");
+            for file in &files.added {
+                wtr.write_record(["file-added", &file.path, "", "package", "", package])?;
+            }
+            for file in &files.removed {
+                wtr.write_record(["file-removed", &file.path, "", "package", package, ""])?;
+            }
+            for file in &files.changed {
+                for fc in &file.changes {
+                    let (field, old, new) = csv_field_change(fc, false, false);
+                    wtr.write_record(["file-changed", &file.path, "", field, &old, &new])?;
+                }
+            }
+        }
+
         if let Some(mc) = &diff.metadata_changed {
             if let Some((ref old, ref new)) = mc.timestamp {
                 wtr.write_record([
@@ -139,6 +184,16 @@ impl Renderer for CsvRenderer {
                     &format_vec_or_none(new),
                 ])?;
             }
+            if let Some((ref old, ref new)) = mc.primary_component {
+                wtr.write_record([
+                    "metadata",
+                    "",
+                    "",
+                    "primary-component",
+                    old.as_deref().unwrap_or(""),
+                    new.as_deref().unwrap_or(""),
+                ])?;
+            }
         }
 
         wtr.flush()?;
@@ -187,6 +242,13 @@ impl SummaryRenderer for CsvRenderer {
         wtr.write_record(["removed", &diff.removed.len().to_string()])?;
         wtr.write_record(["changed", &diff.changed.len().to_string()])?;
         wtr.write_record(["edge_changes", &diff.edge_diffs.len().to_string()])?;
+        wtr.write_record(["file_changes", &diff.file_change_count().to_string()])?;
+        wtr.write_record(["hash_mismatches", &diff.hash_mismatch_count().to_string()])?;
+        let distro_packages = diff
+            .distro_upgrade
+            .as_ref()
+            .map_or(0, |u| u.components.len());
+        wtr.write_record(["distro_upgrade_packages", &distro_packages.to_string()])?;
         wtr.write_record(["metadata_changed", meta_changed])?;
 
         wtr.flush()?;
@@ -215,8 +277,13 @@ impl SummaryRenderer for CsvRenderer {
 }
 
 /// converts a [`FieldChange`] into `(field_name, old_value, new_value)` for CSV output.
-fn csv_field_change(fc: &FieldChange, is_downgrade: bool) -> (&'static str, String, String) {
+fn csv_field_change(
+    fc: &FieldChange,
+    is_downgrade: bool,
+    is_hash_mismatch: bool,
+) -> (&'static str, String, String) {
     match fc {
+        FieldChange::Name(old, new) => ("name", old.clone(), new.clone()),
         FieldChange::Version(old, new) => (
             if is_downgrade {
                 "version-downgrade"
@@ -232,16 +299,44 @@ fn csv_field_change(fc: &FieldChange, is_downgrade: bool) -> (&'static str, Stri
             format_option(old).to_string(),
             format_option(new).to_string(),
         ),
+        FieldChange::DeclaredLicense(old, new) => {
+            ("declared-license", format_set(old), format_set(new))
+        }
+        FieldChange::DeclaredLicenseExpression(old, new) => (
+            "declared-license-expression",
+            format_option(old).to_string(),
+            format_option(new).to_string(),
+        ),
         FieldChange::Supplier(old, new) => (
             "supplier",
             format_option(old).to_string(),
             format_option(new).to_string(),
         ),
+        FieldChange::Copyright(old, new) => (
+            "copyright",
+            format_option(old).to_string(),
+            format_option(new).to_string(),
+        ),
         FieldChange::Purl(old, new) => (
             "purl",
             format_option(old).to_string(),
             format_option(new).to_string(),
         ),
+        FieldChange::Qualifiers(old, new) => {
+            ("qualifiers", format_qualifiers(old), format_qualifiers(new))
+        }
+        FieldChange::Cpes(old, new) => ("cpes", format_set(old), format_set(new)),
+        FieldChange::ExternalRefs(old, new) => {
+            let format_refs = |refs: &BTreeMap<String, BTreeSet<String>>| {
+                refs.iter()
+                    .flat_map(|(ref_type, urls)| {
+                        urls.iter().map(move |u| format!("{}={}", ref_type, u))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            };
+            ("external-refs", format_refs(old), format_refs(new))
+        }
         FieldChange::Description(old, new) => (
             "description",
             format_option(old).to_string(),
@@ -258,7 +353,9 @@ fn csv_field_change(fc: &FieldChange, is_downgrade: bool) -> (&'static str, Stri
                 .map(|(k, v)| format!("{}={}", k, v))
                 .collect::<Vec<_>>()
                 .join("; ");
-            let field = if is_hash_algorithm_downgrade(old, new) {
+            let field = if is_hash_mismatch {
+                "hashes-mismatch"
+            } else if is_hash_algorithm_downgrade(old, new) {
                 "hashes-downgrade"
             } else {
                 "hashes"
@@ -270,5 +367,20 @@ fn csv_field_change(fc: &FieldChange, is_downgrade: bool) -> (&'static str, Stri
             format_option(old).to_string(),
             format_option(new).to_string(),
         ),
+        FieldChange::Kind(old, new) => (
+            "kind",
+            format_option(old).to_string(),
+            format_option(new).to_string(),
+        ),
+        FieldChange::Property(name, old, new) => {
+            let format_values = |values: &BTreeSet<String>| {
+                values
+                    .iter()
+                    .map(|v| format!("{}={}", name, v))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            };
+            ("property", format_values(old), format_values(new))
+        }
     }
 }
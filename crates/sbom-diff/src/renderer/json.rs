@@ -84,6 +84,8 @@ impl SummaryRenderer for JsonRenderer {
             "removed": diff.removed.len(),
             "changed": diff.changed.len(),
             "edge_changes": diff.edge_diffs.len(),
+            "file_changes": diff.file_change_count(),
+            "hash_mismatches": diff.hash_mismatch_count(),
             "metadata_changed": diff.metadata_changed.is_some(),
         });
 
@@ -91,6 +93,17 @@ impl SummaryRenderer for JsonRenderer {
             summary["metadata_changes"] = serde_json::to_value(mc)?;
         }
 
+        if let Some(upgrade) = &diff.distro_upgrade {
+            summary["distro_upgrade"] = serde_json::json!({
+                "old": upgrade.old,
+                "new": upgrade.new,
+                "packages": upgrade.components.len(),
+            });
+        }
+
+        summary["old_document"] = serde_json::to_value(&diff.old_document)?;
+        summary["new_document"] = serde_json::to_value(&diff.new_document)?;
+
         if opts.has_warnings() {
             summary["warnings"] = serde_json::json!({
                 "old": opts.old_warnings,
@@ -1,8 +1,9 @@
 use super::{
-    kind_suffix, write_full, write_summary, FieldChangeFormatter, FullFormatter, RenderOptions,
-    Renderer, SectionKind, SummaryFormatter, SummaryRenderer,
+    describes, file_package, kind_suffix, write_field_changes, write_full, write_summary,
+    FieldChangeFormatter, FullFormatter, RenderOptions, Renderer, SectionKind, SummaryFormatter,
+    SummaryRenderer,
 };
-use crate::{Diff, EcosystemCounts, EdgeDiff};
+use crate::{Diff, DistroUpgrade, EcosystemCounts, EdgeDiff, FileDiff};
 use sbom_model::Component;
 use std::collections::BTreeMap;
 use std::io::Write;
@@ -23,11 +24,10 @@ impl FieldChangeFormatter for MarkdownRenderer {
         writeln!(w, "- **{}**: `{}` &rarr; `{}`", name, old, new)
     }
 
-    fn hash_header<W: Write>(&self, w: &mut W, downgrade: bool) -> std::io::Result<()> {
-        if downgrade {
-            writeln!(w, "- **Hashes (algorithm downgrade)**:")
-        } else {
-            writeln!(w, "- **Hashes**:")
+    fn hash_header<W: Write>(&self, w: &mut W, note: Option<&str>) -> std::io::Result<()> {
+        match note {
+            Some(note) => writeln!(w, "- **Hashes ({})**:", note),
+            None => writeln!(w, "- **Hashes**:"),
         }
     }
 
@@ -52,6 +52,11 @@ impl FieldChangeFormatter for MarkdownRenderer {
     fn component_header<W: Write>(&self, w: &mut W, id: &str) -> std::io::Result<()> {
         writeln!(w, "#### `{}`", id)
     }
+
+    fn match_note<W: Write>(&self, w: &mut W, note: &str) -> std::io::Result<()> {
+        writeln!(w, "_{}_", note)?;
+        writeln!(w)
+    }
 }
 
 impl FullFormatter for MarkdownRenderer {
@@ -125,6 +130,20 @@ impl FullFormatter for MarkdownRenderer {
         writeln!(w)
     }
 
+    fn distro_open<W: Write>(
+        &self,
+        w: &mut W,
+        upgrade: &DistroUpgrade,
+        count: usize,
+    ) -> std::io::Result<()> {
+        writeln!(
+            w,
+            "<details><summary><b>Distro Upgrade: {} &rarr; {} ({})</b></summary>",
+            upgrade.old, upgrade.new, count
+        )?;
+        writeln!(w)
+    }
+
     fn component_list<W: Write>(&self, w: &mut W, components: &[Component]) -> std::io::Result<()> {
         for c in components {
             writeln!(w, "- `{}`", c.purl.as_deref().unwrap_or(c.id.as_str()))?;
@@ -174,6 +193,41 @@ impl FullFormatter for MarkdownRenderer {
         writeln!(w, "</details>")
     }
 
+    fn file_open<W: Write>(&self, w: &mut W, count: usize) -> std::io::Result<()> {
+        writeln!(
+            w,
+            "<details><summary><b>File Changes ({})</b></summary>",
+            count
+        )?;
+        writeln!(w)
+    }
+
+    fn file_entry<W: Write>(&self, w: &mut W, files: &FileDiff) -> std::io::Result<()> {
+        writeln!(w, "#### `{}`", file_package(files))?;
+        if !files.removed.is_empty() {
+            writeln!(w, "**Removed files:**")?;
+            for file in &files.removed {
+                writeln!(w, "- `{}`", file.path)?;
+            }
+        }
+        if !files.added.is_empty() {
+            writeln!(w, "**Added files:**")?;
+            for file in &files.added {
+                writeln!(w, "- `{}`", file.path)?;
+            }
+        }
+        for file in &files.changed {
+            writeln!(w, "**Changed file `{}`:**", file.path)?;
+            write_field_changes(self, w, &file.changes, false, false)?;
+        }
+        writeln!(w)
+    }
+
+    fn file_close<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        writeln!(w, "</details>")?;
+        writeln!(w)
+    }
+
     fn metadata_open<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         writeln!(w, "<details><summary><b>Metadata Changes</b></summary>")?;
         writeln!(w)
@@ -226,6 +280,26 @@ impl SummaryFormatter for MarkdownRenderer {
         writeln!(w, "| Removed | {} |", diff.removed.len())?;
         writeln!(w, "| Changed | {} |", diff.changed.len())?;
         writeln!(w, "| Edge changes | {} |", diff.edge_diffs.len())?;
+        if !diff.file_diffs.is_empty() {
+            writeln!(w, "| File changes | {} |", diff.file_change_count())?;
+        }
+        let hash_mismatches = diff.hash_mismatch_count();
+        if hash_mismatches > 0 {
+            writeln!(
+                w,
+                "| **Hash mismatches** (same version, other digest) | {} |",
+                hash_mismatches
+            )?;
+        }
+        if let Some(upgrade) = &diff.distro_upgrade {
+            writeln!(
+                w,
+                "| Distro upgrade | {} &rarr; {} ({} packages) |",
+                upgrade.old,
+                upgrade.new,
+                upgrade.components.len()
+            )?;
+        }
         writeln!(
             w,
             "| Metadata changed | {} |",
@@ -234,7 +308,11 @@ impl SummaryFormatter for MarkdownRenderer {
             } else {
                 "no"
             }
-        )
+        )?;
+        if let Some(describes) = describes(diff) {
+            writeln!(w, "| Describes | {} |", describes)?;
+        }
+        Ok(())
     }
 
     fn write_ecosystem_breakdown<W: Write>(
@@ -20,9 +20,12 @@ pub use markdown::MarkdownRenderer;
 pub use sarif::SarifRenderer;
 pub use text::TextRenderer;
 
-use crate::{ComponentChange, Diff, EcosystemCounts, EdgeDiff, FieldChange};
-use sbom_model::{is_hash_algorithm_downgrade, Component, DependencyKind};
-use std::collections::{BTreeMap, BTreeSet};
+use crate::{
+    ComponentChange, Diff, DistroUpgrade, EcosystemCounts, EdgeDiff, FieldChange, FileDiff,
+    MatchReason,
+};
+use sbom_model::{is_hash_algorithm_downgrade, Component, ComponentId, DependencyKind};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::io::Write;
 
 /// options controlling how diffs are rendered.
@@ -60,9 +63,53 @@ pub(super) fn kind_suffix(kind: &DependencyKind) -> &'static str {
         DependencyKind::Test => " (test)",
         DependencyKind::Optional => " (optional)",
         DependencyKind::Provided => " (provided)",
+        DependencyKind::Contains => " (contained)",
     }
 }
 
+/// the reference types whose URLs differ between `old` and `new`, with each
+/// side's URLs formatted for display.
+pub(super) fn changed_external_refs(
+    old: &BTreeMap<String, BTreeSet<String>>,
+    new: &BTreeMap<String, BTreeSet<String>>,
+) -> Vec<(String, String, String)> {
+    let none = BTreeSet::new();
+    let types: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    types
+        .into_iter()
+        .filter_map(|ref_type| {
+            let old_urls = old.get(ref_type).unwrap_or(&none);
+            let new_urls = new.get(ref_type).unwrap_or(&none);
+            (old_urls != new_urls)
+                .then(|| (ref_type.clone(), format_set(old_urls), format_set(new_urls)))
+        })
+        .collect()
+}
+
+/// what the two documents describe: `old -> new`, or the one product when
+/// both describe the same. `None` when neither names a primary component.
+pub(super) fn describes(diff: &Diff) -> Option<String> {
+    let old = &diff.old_document.describes;
+    let new = &diff.new_document.describes;
+    if old.is_none() && new.is_none() {
+        None
+    } else if old == new {
+        old.clone()
+    } else {
+        Some(format!("{} -> {}", format_option(old), format_option(new)))
+    }
+}
+
+/// the release change a distro upgrade names: `old -> new`.
+pub(super) fn distro_label(upgrade: &DistroUpgrade) -> String {
+    format!("{} -> {}", upgrade.old, upgrade.new)
+}
+
+/// the heading for one package's file changes.
+pub(super) fn file_package(files: &FileDiff) -> &str {
+    files.package.as_deref().unwrap_or("<no package>")
+}
+
 /// formats an `Option<String>` for display, returning `"<none>"` for `None`.
 pub fn format_option(opt: &Option<String>) -> &str {
     opt.as_deref().unwrap_or("<none>")
@@ -84,6 +131,40 @@ pub fn format_set(set: &BTreeSet<String>) -> String {
     }
 }
 
+/// formats purl qualifiers as comma-separated `key=value` pairs, or `"<none>"` if empty.
+pub fn format_qualifiers(qualifiers: &BTreeMap<String, String>) -> String {
+    if qualifiers.is_empty() {
+        "<none>".to_string()
+    } else {
+        qualifiers
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// how reports flag a digest that changed under an unchanged version.
+pub(super) const HASH_MISMATCH_NOTE: &str = "same-version mismatch";
+
+/// the match reason a report shows for a change. every pair has one, but
+/// only a user mapping or a dependency-graph guess is worth a line of its
+/// own; the rest is for `--explain-matching`.
+pub(super) fn noted_match_reason(change: &ComponentChange) -> Option<MatchReason> {
+    change
+        .match_reason
+        .filter(|reason| matches!(reason, MatchReason::UserMapping | MatchReason::Graph))
+}
+
+/// `reason` described for a report, with the confidence of a graph match,
+/// e.g. "matched by dependency graph, 80% confidence".
+pub(super) fn describe_match(change: &ComponentChange, reason: MatchReason) -> String {
+    match change.match_confidence {
+        Some(confidence) => format!("{}, {confidence}% confidence", reason.describe()),
+        None => reason.describe().to_string(),
+    }
+}
+
 /// trait for rendering a [`Diff`] to an output stream.
 pub trait Renderer {
     /// writes the formatted diff to the provided writer.
@@ -116,7 +197,9 @@ pub(super) trait FieldChangeFormatter {
         old: &str,
         new: &str,
     ) -> std::io::Result<()>;
-    fn hash_header<W: Write>(&self, w: &mut W, downgrade: bool) -> std::io::Result<()>;
+    /// opens a hash change, with what makes it notable in `note`, e.g.
+    /// "algorithm downgrade".
+    fn hash_header<W: Write>(&self, w: &mut W, note: Option<&str>) -> std::io::Result<()>;
     fn hash_removed<W: Write>(&self, w: &mut W, algo: &str, digest: &str) -> std::io::Result<()>;
     fn hash_changed<W: Write>(
         &self,
@@ -127,6 +210,8 @@ pub(super) trait FieldChangeFormatter {
     ) -> std::io::Result<()>;
     fn hash_added<W: Write>(&self, w: &mut W, algo: &str, digest: &str) -> std::io::Result<()>;
     fn component_header<W: Write>(&self, w: &mut W, id: &str) -> std::io::Result<()>;
+    /// notes how a changed component's sides were paired, under its header.
+    fn match_note<W: Write>(&self, w: &mut W, note: &str) -> std::io::Result<()>;
 }
 
 pub(super) fn write_field_changes<F: FieldChangeFormatter, W: Write>(
@@ -134,9 +219,13 @@ pub(super) fn write_field_changes<F: FieldChangeFormatter, W: Write>(
     writer: &mut W,
     changes: &[FieldChange],
     is_downgrade: bool,
+    is_hash_mismatch: bool,
 ) -> std::io::Result<()> {
     for change in changes {
         match change {
+            FieldChange::Name(old, new) => {
+                fmt.field_change(writer, "Name", old, new)?;
+            }
             FieldChange::Version(old, new) => {
                 let label = if is_downgrade {
                     "Version (downgrade)"
@@ -156,12 +245,47 @@ pub(super) fn write_field_changes<F: FieldChangeFormatter, W: Write>(
                     format_option(new),
                 )?;
             }
+            FieldChange::DeclaredLicense(old, new) => {
+                fmt.field_change(
+                    writer,
+                    "Declared license",
+                    &format_set(old),
+                    &format_set(new),
+                )?;
+            }
+            FieldChange::DeclaredLicenseExpression(old, new) => {
+                fmt.field_change(
+                    writer,
+                    "Declared license expression",
+                    format_option(old),
+                    format_option(new),
+                )?;
+            }
             FieldChange::Supplier(old, new) => {
                 fmt.field_change(writer, "Supplier", format_option(old), format_option(new))?;
             }
+            FieldChange::Copyright(old, new) => {
+                fmt.field_change(writer, "Copyright", format_option(old), format_option(new))?;
+            }
             FieldChange::Purl(old, new) => {
                 fmt.field_change(writer, "Purl", format_option(old), format_option(new))?;
             }
+            FieldChange::Qualifiers(old, new) => {
+                fmt.field_change(
+                    writer,
+                    "Qualifiers",
+                    &format_qualifiers(old),
+                    &format_qualifiers(new),
+                )?;
+            }
+            FieldChange::Cpes(old, new) => {
+                fmt.field_change(writer, "CPEs", &format_set(old), &format_set(new))?;
+            }
+            FieldChange::ExternalRefs(old, new) => {
+                for (ref_type, old, new) in changed_external_refs(old, new) {
+                    fmt.field_change(writer, &format!("External ref ({ref_type})"), &old, &new)?;
+                }
+            }
             FieldChange::Description(old, new) => {
                 fmt.field_change(
                     writer,
@@ -171,7 +295,17 @@ pub(super) fn write_field_changes<F: FieldChangeFormatter, W: Write>(
                 )?;
             }
             FieldChange::Hashes(old, new) => {
-                fmt.hash_header(writer, is_hash_algorithm_downgrade(old, new))?;
+                let mut notes = Vec::new();
+                if is_hash_mismatch {
+                    notes.push(HASH_MISMATCH_NOTE);
+                }
+                if is_hash_algorithm_downgrade(old, new) {
+                    notes.push("algorithm downgrade");
+                }
+                fmt.hash_header(
+                    writer,
+                    (!notes.is_empty()).then(|| notes.join(", ")).as_deref(),
+                )?;
                 for (algo, digest) in old {
                     if !new.contains_key(algo) {
                         fmt.hash_removed(writer, algo, digest)?;
@@ -188,19 +322,33 @@ pub(super) fn write_field_changes<F: FieldChangeFormatter, W: Write>(
             FieldChange::Ecosystem(old, new) => {
                 fmt.field_change(writer, "Ecosystem", format_option(old), format_option(new))?;
             }
+            FieldChange::Kind(old, new) => {
+                fmt.field_change(writer, "Kind", format_option(old), format_option(new))?;
+            }
+            FieldChange::Property(name, old, new) => {
+                fmt.field_change(
+                    writer,
+                    &format!("Property ({name})"),
+                    &format_set(old),
+                    &format_set(new),
+                )?;
+            }
         }
     }
     Ok(())
 }
 
-pub(super) fn write_changed<F: FieldChangeFormatter, W: Write>(
+pub(super) fn write_changed<'a, F: FieldChangeFormatter, W: Write>(
     fmt: &F,
     writer: &mut W,
-    changes: &[ComponentChange],
+    changes: impl IntoIterator<Item = &'a ComponentChange>,
 ) -> std::io::Result<()> {
     for c in changes {
         fmt.component_header(writer, c.new.purl.as_deref().unwrap_or(c.id.as_str()))?;
-        write_field_changes(fmt, writer, &c.changes, c.is_downgrade)?;
+        if let Some(reason) = noted_match_reason(c) {
+            fmt.match_note(writer, &describe_match(c, reason))?;
+        }
+        write_field_changes(fmt, writer, &c.changes, c.is_downgrade, c.is_hash_mismatch)?;
     }
     Ok(())
 }
@@ -280,6 +428,14 @@ pub(super) trait FullFormatter: FieldChangeFormatter {
     ) -> std::io::Result<()>;
     /// closes an added/removed/changed section, emitting the trailing blank line.
     fn section_close<W: Write>(&self, w: &mut W) -> std::io::Result<()>;
+    /// opens the section grouping the packages a distro upgrade changed
+    /// (heading only); closed with [`section_close`](Self::section_close).
+    fn distro_open<W: Write>(
+        &self,
+        w: &mut W,
+        upgrade: &DistroUpgrade,
+        count: usize,
+    ) -> std::io::Result<()>;
     /// renders the component list body of an added or removed section.
     fn component_list<W: Write>(&self, w: &mut W, components: &[Component]) -> std::io::Result<()>;
     /// opens the edge-changes section.
@@ -288,6 +444,12 @@ pub(super) trait FullFormatter: FieldChangeFormatter {
     fn edge_entry<W: Write>(&self, w: &mut W, diff: &Diff, edge: &EdgeDiff) -> std::io::Result<()>;
     /// closes the edge-changes section.
     fn edge_close<W: Write>(&self, w: &mut W) -> std::io::Result<()>;
+    /// opens the file-changes section.
+    fn file_open<W: Write>(&self, w: &mut W, count: usize) -> std::io::Result<()>;
+    /// renders one package's file changes.
+    fn file_entry<W: Write>(&self, w: &mut W, files: &FileDiff) -> std::io::Result<()>;
+    /// closes the file-changes section, emitting the trailing blank line.
+    fn file_close<W: Write>(&self, w: &mut W) -> std::io::Result<()>;
     /// opens the metadata-changes section.
     fn metadata_open<W: Write>(&self, w: &mut W) -> std::io::Result<()>;
     /// closes the metadata-changes section.
@@ -306,22 +468,50 @@ pub(super) fn write_full<F: FullFormatter, W: Write>(
 
     fmt.full_count_header(writer, diff)?;
 
+    // packages that moved with a distro upgrade are listed once, under it,
+    // instead of among the other changes.
+    let (upgraded, changed) = diff.partition_distro_changes();
+    if let Some(upgrade) = diff
+        .distro_upgrade
+        .as_ref()
+        .filter(|_| !upgraded.is_empty())
+    {
+        fmt.distro_open(writer, upgrade, upgraded.len())?;
+        write_changed(fmt, writer, upgraded)?;
+        fmt.section_close(writer)?;
+    }
+
     if opts.group_by_ecosystem {
         let grouped = diff.group_by_ecosystem();
         let breakdown = grouped.ecosystem_breakdown();
         fmt.full_ecosystem_breakdown(writer, &breakdown)?;
+        let upgraded: HashSet<&ComponentId> = diff
+            .distro_upgrade
+            .iter()
+            .flat_map(|u| &u.components)
+            .collect();
         for (ecosystem, eco_diff) in &grouped.by_ecosystem {
+            let changed: Vec<&ComponentChange> = eco_diff
+                .changed
+                .iter()
+                .filter(|c| !upgraded.contains(&c.id))
+                .collect();
+            if eco_diff.added.is_empty() && eco_diff.removed.is_empty() && changed.is_empty() {
+                continue;
+            }
             fmt.full_ecosystem_header(writer, ecosystem)?;
-            write_full_sections(
-                fmt,
-                writer,
-                &eco_diff.added,
-                &eco_diff.removed,
-                &eco_diff.changed,
-            )?;
+            write_full_sections(fmt, writer, &eco_diff.added, &eco_diff.removed, &changed)?;
         }
     } else {
-        write_full_sections(fmt, writer, &diff.added, &diff.removed, &diff.changed)?;
+        write_full_sections(fmt, writer, &diff.added, &diff.removed, &changed)?;
+    }
+
+    if !diff.file_diffs.is_empty() {
+        fmt.file_open(writer, diff.file_diffs.len())?;
+        for files in &diff.file_diffs {
+            fmt.file_entry(writer, files)?;
+        }
+        fmt.file_close(writer)?;
     }
 
     if !diff.edge_diffs.is_empty() {
@@ -354,6 +544,14 @@ pub(super) fn write_full<F: FullFormatter, W: Write>(
                 &format_vec_or_none(new),
             )?;
         }
+        if let Some((old, new)) = &mc.primary_component {
+            fmt.field_change(
+                writer,
+                "Primary component",
+                format_option(old),
+                format_option(new),
+            )?;
+        }
         fmt.metadata_close(writer)?;
     }
 
@@ -365,7 +563,7 @@ fn write_full_sections<F: FullFormatter, W: Write>(
     writer: &mut W,
     added: &[Component],
     removed: &[Component],
-    changed: &[ComponentChange],
+    changed: &[&ComponentChange],
 ) -> std::io::Result<()> {
     if !added.is_empty() {
         fmt.section_open(writer, SectionKind::Added, added.len())?;
@@ -379,7 +577,7 @@ fn write_full_sections<F: FullFormatter, W: Write>(
     }
     if !changed.is_empty() {
         fmt.section_open(writer, SectionKind::Changed, changed.len())?;
-        write_changed(fmt, writer, changed)?;
+        write_changed(fmt, writer, changed.iter().copied())?;
         fmt.section_close(writer)?;
     }
     Ok(())
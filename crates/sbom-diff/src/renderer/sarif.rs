@@ -1,8 +1,9 @@
 use super::{
-    format_option, format_set, format_vec_or_none, kind_suffix, RenderOptions, Renderer,
+    changed_external_refs, describe_match, distro_label, format_option, format_qualifiers,
+    format_set, format_vec_or_none, kind_suffix, noted_match_reason, RenderOptions, Renderer,
     SummaryRenderer,
 };
-use crate::{Diff, FieldChange};
+use crate::{ComponentChange, Diff, FieldChange};
 use sbom_model::{is_hash_algorithm_downgrade, Component};
 use serde::Serialize;
 use std::io::Write;
@@ -17,6 +18,8 @@ const RULE_COMPONENT_CHANGED: usize = 2;
 const RULE_DEPENDENCY_CHANGED: usize = 3;
 const RULE_METADATA_CHANGED: usize = 4;
 const RULE_PARSER_WARNING: usize = 5;
+const RULE_FILE_CHANGED: usize = 6;
+const RULE_DISTRO_UPGRADED: usize = 7;
 
 #[derive(Clone, Copy)]
 struct RuleInfo {
@@ -54,7 +57,7 @@ const SARIF_RULES: &[RuleInfo] = &[
     RuleInfo {
         id: "metadata-changed",
         short_desc: "Metadata changed",
-        full_desc: "Document metadata (timestamp, tools, or authors) changed between SBOMs",
+        full_desc: "Document metadata (timestamp, tools, authors, or primary component) changed between SBOMs",
         level: "note",
     },
     RuleInfo {
@@ -63,6 +66,18 @@ const SARIF_RULES: &[RuleInfo] = &[
         full_desc: "The SBOM parser emitted a warning about the input document",
         level: "note",
     },
+    RuleInfo {
+        id: "file-changed",
+        short_desc: "File changed",
+        full_desc: "A file or snippet was added, removed, or changed its hashes or licenses",
+        level: "note",
+    },
+    RuleInfo {
+        id: "distro-upgraded",
+        short_desc: "Distro upgraded",
+        full_desc: "The OS packages moved to a different distribution release",
+        level: "note",
+    },
 ];
 
 #[derive(Serialize)]
@@ -143,8 +158,8 @@ struct SarifLogicalLocation {
 /// SARIF 2.1.0 renderer for GitHub Code Scanning integration.
 ///
 /// produces a SARIF log with one run containing rules for each change type
-/// (component added/removed/changed, dependency changed, metadata changed)
-/// and a result entry per finding.
+/// (component added/removed/changed, dependency changed, metadata changed,
+/// file changed) and a result entry per finding.
 pub struct SarifRenderer;
 
 impl SarifRenderer {
@@ -173,8 +188,9 @@ impl SarifRenderer {
         }]
     }
 
-    fn format_field_change(fc: &FieldChange, is_downgrade: bool) -> String {
+    fn format_field_change(fc: &FieldChange, is_downgrade: bool, is_hash_mismatch: bool) -> String {
         match fc {
+            FieldChange::Name(old, new) => format!("name: {} -> {}", old, new),
             FieldChange::Version(old, new) => {
                 if is_downgrade {
                     format!(
@@ -196,12 +212,50 @@ impl SarifRenderer {
                     format_option(new)
                 )
             }
+            FieldChange::DeclaredLicense(old, new) => {
+                format!(
+                    "declared license: {} -> {}",
+                    format_set(old),
+                    format_set(new)
+                )
+            }
+            FieldChange::DeclaredLicenseExpression(old, new) => {
+                format!(
+                    "declared license expression: {} -> {}",
+                    format_option(old),
+                    format_option(new)
+                )
+            }
             FieldChange::Supplier(old, new) => {
                 format!("supplier: {} -> {}", format_option(old), format_option(new))
             }
+            FieldChange::Copyright(old, new) => {
+                format!(
+                    "copyright: {} -> {}",
+                    format_option(old),
+                    format_option(new)
+                )
+            }
             FieldChange::Purl(old, new) => {
                 format!("purl: {} -> {}", format_option(old), format_option(new))
             }
+            FieldChange::Qualifiers(old, new) => {
+                format!(
+                    "qualifiers: {} -> {}",
+                    format_qualifiers(old),
+                    format_qualifiers(new)
+                )
+            }
+            FieldChange::Cpes(old, new) => {
+                format!("cpes: {} -> {}", format_set(old), format_set(new))
+            }
+            FieldChange::ExternalRefs(old, new) => {
+                let parts: Vec<_> = changed_external_refs(old, new)
+                    .into_iter()
+                    .map(|(ref_type, old, new)| format!("{}: {} -> {}", ref_type, old, new))
+                    .collect();
+                format!("external refs: {}", parts.join("; "))
+            }
             FieldChange::Description(old, new) => {
                 format!(
                     "description: {} -> {}",
@@ -223,7 +277,9 @@ impl SarifRenderer {
                         parts.push(format!("added {}={}", algo, digest));
                     }
                 }
-                let label = if is_hash_algorithm_downgrade(old, new) {
+                let label = if is_hash_mismatch {
+                    "hashes (same-version mismatch)"
+                } else if is_hash_algorithm_downgrade(old, new) {
                     "hashes (algorithm downgrade)"
                 } else {
                     "hashes"
@@ -237,6 +293,17 @@ impl SarifRenderer {
                     format_option(new)
                 )
             }
+            FieldChange::Kind(old, new) => {
+                format!("kind: {} -> {}", format_option(old), format_option(new))
+            }
+            FieldChange::Property(name, old, new) => {
+                format!(
+                    "property {}: {} -> {}",
+                    name,
+                    format_set(old),
+                    format_set(new)
+                )
+            }
         }
     }
 
@@ -302,21 +369,52 @@ impl SarifRenderer {
             });
         }
 
-        for change in &diff.changed {
+        // the packages a distro upgrade changed follow its result, each
+        // naming the upgrade.
+        let (upgraded, changed) = diff.partition_distro_changes();
+        let mut grouped: Vec<(&ComponentChange, Option<String>)> = Vec::new();
+        if let Some(upgrade) = &diff.distro_upgrade {
+            let label = distro_label(upgrade);
+            results.push(SarifResultEntry {
+                rule_id: SARIF_RULES[RULE_DISTRO_UPGRADED].id,
+                rule_index: RULE_DISTRO_UPGRADED,
+                level: SARIF_RULES[RULE_DISTRO_UPGRADED].level,
+                message: SarifTextMessage {
+                    text: format!(
+                        "Distro upgraded: {} ({} packages changed with it)",
+                        label,
+                        upgraded.len()
+                    ),
+                },
+                locations: vec![SarifLocation {
+                    logical_locations: vec![SarifLogicalLocation {
+                        fully_qualified_name: upgrade.new.clone(),
+                        kind: "module",
+                    }],
+                }],
+            });
+            grouped.extend(upgraded.into_iter().map(|c| (c, Some(label.clone()))));
+        }
+        grouped.extend(changed.into_iter().map(|c| (c, None)));
+
+        for (change, upgrade) in grouped {
             let display = Self::component_display(&change.new);
             let is_downgrade = change.is_downgrade;
-            let field_changes: Vec<String> = change
+            let mut field_changes: Vec<String> = change
                 .changes
                 .iter()
-                .map(|fc| Self::format_field_change(fc, is_downgrade))
+                .map(|fc| Self::format_field_change(fc, is_downgrade, change.is_hash_mismatch))
                 .collect();
+            if let Some(reason) = noted_match_reason(change) {
+                field_changes.push(describe_match(change, reason));
+            }
 
             let hash_downgrade = change.changes.iter().any(|fc| match fc {
                 FieldChange::Hashes(old, new) => is_hash_algorithm_downgrade(old, new),
                 _ => false,
             });
 
-            let level = if is_downgrade || hash_downgrade {
+            let level = if is_downgrade || hash_downgrade || change.is_hash_mismatch {
                 "error"
             } else {
                 SARIF_RULES[RULE_COMPONENT_CHANGED].level
@@ -326,11 +424,19 @@ impl SarifRenderer {
                 rule_index: RULE_COMPONENT_CHANGED,
                 level,
                 message: SarifTextMessage {
-                    text: format!(
-                        "Component changed: {} ({})",
-                        display,
-                        field_changes.join("; "),
-                    ),
+                    text: match upgrade {
+                        Some(upgrade) => format!(
+                            "Component changed with distro upgrade {}: {} ({})",
+                            upgrade,
+                            display,
+                            field_changes.join("; "),
+                        ),
+                        None => format!(
+                            "Component changed: {} ({})",
+                            display,
+                            field_changes.join("; "),
+                        ),
+                    },
                 },
                 locations: Self::component_location(&change.new),
             });
@@ -384,6 +490,60 @@ impl SarifRenderer {
             }
         }
 
+        for files in &diff.file_diffs {
+            let package = files.package.as_deref().unwrap_or("<no package>");
+            let file_result = |path: &str, level, text: String| SarifResultEntry {
+                rule_id: SARIF_RULES[RULE_FILE_CHANGED].id,
+                rule_index: RULE_FILE_CHANGED,
+                level,
+                message: SarifTextMessage { text },
+                locations: vec![SarifLocation {
+                    logical_locations: vec![SarifLogicalLocation {
+                        fully_qualified_name: path.to_string(),
+                        kind: "resource",
+                    }],
+                }],
+            };
+            let level = SARIF_RULES[RULE_FILE_CHANGED].level;
+            for file in &files.added {
+                results.push(file_result(
+                    &file.path,
+                    level,
+                    format!("File added to {}: {}", package, file.path),
+                ));
+            }
+            for file in &files.removed {
+                results.push(file_result(
+                    &file.path,
+                    level,
+                    format!("File removed from {}: {}", package, file.path),
+                ));
+            }
+            for file in &files.changed {
+                let field_changes: Vec<String> = file
+                    .changes
+                    .iter()
+                    .map(|fc| Self::format_field_change(fc, false, false))
+                    .collect();
+                // a file whose content changed under an unchanged package is
+                // what a tampered image looks like.
+                let content_changed = file
+                    .changes
+                    .iter()
+                    .any(|fc| matches!(fc, FieldChange::Hashes(..)));
+                results.push(file_result(
+                    &file.path,
+                    if content_changed { "warning" } else { level },
+                    format!(
+                        "File changed in {}: {} ({})",
+                        package,
+                        file.path,
+                        field_changes.join("; ")
+                    ),
+                ));
+            }
+        }
+
         if let Some(mc) = &diff.metadata_changed {
             let mut parts = Vec::new();
             if let Some((ref old, ref new)) = mc.timestamp {
@@ -407,6 +567,13 @@ impl SarifRenderer {
                     format_vec_or_none(new)
                 ));
             }
+            if let Some((ref old, ref new)) = mc.primary_component {
+                parts.push(format!(
+                    "primary component: {} -> {}",
+                    old.as_deref().unwrap_or("<none>"),
+                    new.as_deref().unwrap_or("<none>")
+                ));
+            }
 
             if !parts.is_empty() {
                 results.push(SarifResultEntry {
@@ -17,6 +17,9 @@ fn mock_diff() -> Diff {
             new: c2,
             changes: vec![FieldChange::Version(Some("1.0".into()), Some("1.1".into()))],
             is_downgrade: false,
+            match_reason: None,
+            is_hash_mismatch: false,
+            match_confidence: None,
         }],
         edge_diffs: vec![],
         ..Diff::default()
@@ -38,16 +41,43 @@ fn mock_diff_all_field_changes() -> Diff {
             old: c1,
             new: c2,
             changes: vec![
+                FieldChange::Name("Pkg_A".into(), "pkg-a".into()),
                 FieldChange::Version(Some("1.0".into()), Some("1.1".into())),
                 FieldChange::License(
                     BTreeSet::from(["MIT".into()]),
                     BTreeSet::from(["Apache-2.0".into()]),
                 ),
+                FieldChange::DeclaredLicense(
+                    BTreeSet::from(["MIT".into()]),
+                    BTreeSet::from(["BSD-3-Clause".into()]),
+                ),
                 FieldChange::Supplier(Some("Old Corp".into()), Some("New Corp".into())),
+                FieldChange::Copyright(
+                    Some("Copyright 2020 Old Corp".into()),
+                    Some("Copyright 2024 New Corp".into()),
+                ),
                 FieldChange::Purl(
                     Some("pkg:npm/pkg-a@1.0".into()),
                     Some("pkg:npm/pkg-a@1.1".into()),
                 ),
+                FieldChange::Qualifiers(
+                    BTreeMap::from([("distro".into(), "debian-12".into())]),
+                    BTreeMap::from([("distro".into(), "debian-13".into())]),
+                ),
+                FieldChange::Cpes(
+                    BTreeSet::from(["cpe:2.3:a:acme:pkg-a:1.0:*:*:*:*:*:*:*".into()]),
+                    BTreeSet::from(["cpe:2.3:a:acme:pkg-a:1.1:*:*:*:*:*:*:*".into()]),
+                ),
+                FieldChange::ExternalRefs(
+                    BTreeMap::from([(
+                        "vcs".into(),
+                        BTreeSet::from(["https://github.com/acme/pkg-a".into()]),
+                    )]),
+                    BTreeMap::from([(
+                        "vcs".into(),
+                        BTreeSet::from(["https://github.com/fork/pkg-a".into()]),
+                    )]),
+                ),
                 FieldChange::Description(
                     Some("Old description".into()),
                     Some("New description".into()),
@@ -57,8 +87,17 @@ fn mock_diff_all_field_changes() -> Diff {
                     BTreeMap::from([("sha256".into(), "bbb".into())]),
                 ),
                 FieldChange::Ecosystem(Some("npm".into()), Some("cargo".into())),
+                FieldChange::Kind(Some("library".into()), Some("framework".into())),
+                FieldChange::Property(
+                    "cdx:npm:package:development".into(),
+                    BTreeSet::new(),
+                    BTreeSet::from(["true".into()]),
+                ),
             ],
             is_downgrade: false,
+            match_reason: None,
+            is_hash_mismatch: false,
+            match_confidence: None,
         }],
         edge_diffs: vec![crate::EdgeDiff {
             parent: ComponentId::new(None, &[("name", "parent")]),
@@ -109,6 +148,7 @@ fn test_text_renderer_all_field_changes() {
         .unwrap();
     let out = String::from_utf8(buf).unwrap();
 
+    assert!(out.contains("Name: Pkg_A -> pkg-a"));
     assert!(out.contains("Version: 1.0 -> 1.1"));
     assert!(out.contains("License:"));
     assert!(out.contains("MIT"));
@@ -117,12 +157,23 @@ fn test_text_renderer_all_field_changes() {
     assert!(out.contains("Old Corp"));
     assert!(out.contains("New Corp"));
     assert!(out.contains("Purl:"));
+    assert!(out.contains("Qualifiers: distro=debian-12 -> distro=debian-13"));
     assert!(out.contains("Description:"));
     assert!(out.contains("Old description"));
     assert!(out.contains("New description"));
     assert!(out.contains("Hashes:"));
     assert!(out.contains("~ sha256: aaa -> bbb"));
     assert!(out.contains("Ecosystem: npm -> cargo"));
+    assert!(out.contains("Kind: library -> framework"));
+    assert!(out.contains("Declared license: MIT -> BSD-3-Clause"));
+    assert!(out.contains("Copyright: Copyright 2020 Old Corp -> Copyright 2024 New Corp"));
+    assert!(out.contains(
+        "External ref (vcs): https://github.com/acme/pkg-a -> https://github.com/fork/pkg-a"
+    ));
+    assert!(out.contains(
+        "CPEs: cpe:2.3:a:acme:pkg-a:1.0:*:*:*:*:*:*:* -> cpe:2.3:a:acme:pkg-a:1.1:*:*:*:*:*:*:*"
+    ));
+    assert!(out.contains("Property (cdx:npm:package:development): <none> -> true"));
     assert!(out.contains("[~] Edge Changes"));
 }
 
@@ -169,14 +220,22 @@ fn test_markdown_renderer_all_field_changes() {
         .unwrap();
     let out = String::from_utf8(buf).unwrap();
 
+    assert!(out.contains("**Name**"));
     assert!(out.contains("**Version**"));
     assert!(out.contains("**License**"));
     assert!(out.contains("**Supplier**"));
     assert!(out.contains("**Purl**"));
+    assert!(out.contains("**Qualifiers**"));
     assert!(out.contains("**Description**"));
     assert!(out.contains("**Hashes**:"));
     assert!(out.contains("`sha256`: `aaa` &rarr; `bbb`"));
     assert!(out.contains("**Ecosystem**"));
+    assert!(out.contains("**Kind**"));
+    assert!(out.contains("**Declared license**"));
+    assert!(out.contains("**Copyright**"));
+    assert!(out.contains("**External ref (vcs)**"));
+    assert!(out.contains("**CPEs**"));
+    assert!(out.contains("**Property (cdx:npm:package:development)**"));
     assert!(out.contains("Edge Changes"));
     assert!(out.contains("**Removed dependencies:**"));
     assert!(out.contains("**Added dependencies:**"));
@@ -215,7 +274,7 @@ fn test_json_renderer_all_field_changes() {
     let val: serde_json::Value = serde_json::from_slice(&buf).unwrap();
 
     assert_eq!(val["changed"].as_array().unwrap().len(), 1);
-    assert_eq!(val["changed"][0]["changes"].as_array().unwrap().len(), 7);
+    assert_eq!(val["changed"][0]["changes"].as_array().unwrap().len(), 15);
     assert_eq!(val["edge_diffs"].as_array().unwrap().len(), 1);
 }
 
@@ -259,6 +318,9 @@ fn mock_diff_with_ecosystems() -> Diff {
                 Some("18.0.0".into()),
             )],
             is_downgrade: false,
+            match_reason: None,
+            is_hash_mismatch: false,
+            match_confidence: None,
         }],
         edge_diffs: vec![],
         ..Diff::default()
@@ -590,6 +652,7 @@ fn mock_diff_with_metadata_change() -> Diff {
             timestamp: Some((Some("2024-01-01".into()), Some("2024-01-02".into()))),
             tools: Some((vec!["syft".into()], vec!["trivy".into()])),
             authors: None,
+            primary_component: None,
         }),
         ..Diff::default()
     }
@@ -674,6 +737,348 @@ fn test_json_renderer_metadata_change() {
     assert!(mc.get("authors").is_none());
 }
 
+fn mock_diff_with_primary_component_change() -> Diff {
+    let document = |describes: &str| crate::DocumentIdentity {
+        format: Some("cyclonedx".into()),
+        describes: Some(describes.into()),
+        ..Default::default()
+    };
+    Diff {
+        metadata_changed: Some(crate::MetadataChange {
+            timestamp: None,
+            tools: None,
+            authors: None,
+            primary_component: Some((Some("acme-app@1.0.0".into()), Some("acme-app@1.1.0".into()))),
+        }),
+        old_document: document("acme-app@1.0.0"),
+        new_document: document("acme-app@1.1.0"),
+        ..Diff::default()
+    }
+}
+
+#[test]
+fn test_text_and_markdown_state_what_each_side_describes() {
+    let diff = mock_diff_with_primary_component_change();
+
+    let mut buf = Vec::new();
+    TextRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("Describes:        acme-app@1.0.0 -> acme-app@1.1.0"));
+    assert!(out.contains("Primary component: acme-app@1.0.0 -> acme-app@1.1.0"));
+
+    let mut buf = Vec::new();
+    MarkdownRenderer
+        .render_summary(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("| Describes | acme-app@1.0.0 -> acme-app@1.1.0 |"));
+
+    // the same product on both sides is named once; none at all, not at all
+    let mut same = diff.clone();
+    same.new_document = same.old_document.clone();
+    let mut buf = Vec::new();
+    TextRenderer
+        .render_summary(&same, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    assert!(String::from_utf8(buf)
+        .unwrap()
+        .contains("Describes:        acme-app@1.0.0\n"));
+
+    let mut buf = Vec::new();
+    TextRenderer
+        .render_summary(&mock_diff_empty(), &RenderOptions::default(), &mut buf)
+        .unwrap();
+    assert!(!String::from_utf8(buf).unwrap().contains("Describes:"));
+}
+
+#[test]
+fn test_primary_component_change_in_machine_formats() {
+    let diff = mock_diff_with_primary_component_change();
+
+    let mut buf = Vec::new();
+    SarifRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let val = sarif_parse(&buf);
+    let results = val["runs"][0]["results"].as_array().unwrap();
+    let meta = results
+        .iter()
+        .find(|r| r["ruleId"] == "metadata-changed")
+        .unwrap();
+    assert_eq!(
+        meta["message"]["text"],
+        "Metadata changed: primary component: acme-app@1.0.0 -> acme-app@1.1.0"
+    );
+
+    let mut buf = Vec::new();
+    CsvRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("metadata,,,primary-component,acme-app@1.0.0,acme-app@1.1.0"));
+
+    let mut buf = Vec::new();
+    JsonRenderer
+        .render_summary(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let val: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(
+        val["metadata_changes"]["primary_component"],
+        serde_json::json!(["acme-app@1.0.0", "acme-app@1.1.0"])
+    );
+    assert_eq!(val["old_document"]["describes"], "acme-app@1.0.0");
+    assert_eq!(val["new_document"]["format"], "cyclonedx");
+}
+
+fn mock_diff_with_distro_upgrade() -> Diff {
+    let change = |purl_old: &str, purl_new: &str, ecosystem: &str| {
+        let mut old = Component::new("pkg".into(), None);
+        old.purl = Some(purl_old.into());
+        old.ecosystem = Some(ecosystem.into());
+        let mut new = old.clone();
+        new.purl = Some(purl_new.into());
+        new.id = sbom_model::ComponentId::new(Some(purl_new), &[]);
+        ComponentChange {
+            id: new.id.clone(),
+            old,
+            new,
+            changes: vec![FieldChange::Purl(
+                Some(purl_old.into()),
+                Some(purl_new.into()),
+            )],
+            is_downgrade: false,
+            match_reason: None,
+            is_hash_mismatch: false,
+            match_confidence: None,
+        }
+    };
+    let zlib = change(
+        "pkg:deb/debian/zlib1g@1.2.13?distro=debian-11",
+        "pkg:deb/debian/zlib1g@1.2.13?distro=debian-12",
+        "deb",
+    );
+    let lodash = change("pkg:npm/lodash@4.17.20", "pkg:npm/lodash@4.17.21", "npm");
+    Diff {
+        distro_upgrade: Some(crate::DistroUpgrade {
+            old: "debian-11".into(),
+            new: "debian-12".into(),
+            components: vec![zlib.id.clone()],
+        }),
+        changed: vec![lodash, zlib],
+        ..Diff::default()
+    }
+}
+
+#[test]
+fn test_text_and_markdown_group_changes_under_distro_upgrade() {
+    let diff = mock_diff_with_distro_upgrade();
+
+    let mut buf = Vec::new();
+    TextRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("Distro upgrade:   debian-11 -> debian-12 (1 packages)"));
+    assert!(out.contains(
+        "[~] Distro Upgrade: debian-11 -> debian-12\n------------------------------------------\npkg:deb/debian/zlib1g@1.2.13?distro=debian-12\n"
+    ));
+    let changed = out.split("[~] Changed").nth(1).unwrap();
+    assert!(changed.contains("pkg:npm/lodash@4.17.21"));
+    assert!(!changed.contains("zlib1g"));
+
+    let mut buf = Vec::new();
+    MarkdownRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("| Distro upgrade | debian-11 &rarr; debian-12 (1 packages) |"));
+    assert!(out.contains(
+        "<details><summary><b>Distro Upgrade: debian-11 &rarr; debian-12 (1)</b></summary>"
+    ));
+    assert!(out.contains("<details><summary><b>Changed (1)</b></summary>"));
+
+    // grouped by ecosystem, the upgraded package stays out of its ecosystem
+    let opts = RenderOptions {
+        group_by_ecosystem: true,
+        ..RenderOptions::default()
+    };
+    let mut buf = Vec::new();
+    TextRenderer.render(&diff, &opts, &mut buf).unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("[npm]"));
+    assert!(!out.contains("[deb]"));
+    assert_eq!(out.matches("[~] Distro Upgrade").count(), 1);
+}
+
+#[test]
+fn test_distro_upgrade_in_machine_formats() {
+    let diff = mock_diff_with_distro_upgrade();
+
+    let mut buf = Vec::new();
+    SarifRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let val = sarif_parse(&buf);
+    let results = val["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["ruleId"], "distro-upgraded");
+    assert_eq!(
+        results[0]["message"]["text"],
+        "Distro upgraded: debian-11 -> debian-12 (1 packages changed with it)"
+    );
+    assert!(results[1]["message"]["text"]
+        .as_str()
+        .unwrap()
+        .starts_with("Component changed with distro upgrade debian-11 -> debian-12: pkg:deb/"));
+    assert!(results[2]["message"]["text"]
+        .as_str()
+        .unwrap()
+        .starts_with("Component changed: pkg:npm/lodash"));
+
+    let mut buf = Vec::new();
+    CsvRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    let rows: Vec<&str> = out.lines().skip(1).collect();
+    assert_eq!(rows[0], "distro-upgrade,,,distro,debian-11,debian-12");
+    assert!(rows[1].starts_with("distro-changed,pkg:deb/debian/zlib1g"));
+    assert!(rows[2].starts_with("changed,pkg:npm/lodash"));
+
+    let mut buf = Vec::new();
+    CsvRenderer
+        .render_summary(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    assert!(String::from_utf8(buf)
+        .unwrap()
+        .contains("distro_upgrade_packages,1\n"));
+
+    let mut buf = Vec::new();
+    JsonRenderer
+        .render_summary(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let val: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(
+        val["distro_upgrade"],
+        serde_json::json!({"old": "debian-11", "new": "debian-12", "packages": 1})
+    );
+
+    let mut buf = Vec::new();
+    JsonRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let val: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(
+        val["distro_upgrade"]["components"][0],
+        "pkg:deb/debian/zlib1g@1.2.13?distro=debian-12"
+    );
+}
+
+fn mock_diff_with_file_changes() -> Diff {
+    let file = |path: &str| sbom_model::FileEntry {
+        path: path.into(),
+        package: None,
+        hashes: BTreeMap::new(),
+        licenses: Default::default(),
+        source_ids: vec![],
+    };
+    let hashes = |digest: &str| BTreeMap::from([("sha-256".to_string(), digest.to_string())]);
+    Diff {
+        file_diffs: vec![crate::FileDiff {
+            package: Some("busybox".into()),
+            added: vec![file("/etc/fstab")],
+            removed: vec![file("/etc/profile")],
+            changed: vec![crate::FileChange {
+                path: "/bin/busybox".into(),
+                changes: vec![FieldChange::Hashes(hashes("aa"), hashes("cc"))],
+            }],
+        }],
+        ..Diff::default()
+    }
+}
+
+#[test]
+fn test_text_and_markdown_list_file_changes_per_package() {
+    let diff = mock_diff_with_file_changes();
+
+    let mut buf = Vec::new();
+    TextRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("File changes:     3"));
+    assert!(out.contains(
+        "[~] File Changes\n----------------\nbusybox\n  - /etc/profile\n  + /etc/fstab\n  ~ /bin/busybox\n  Hashes:\n    ~ sha-256: aa -> cc\n"
+    ));
+
+    let mut buf = Vec::new();
+    MarkdownRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("| File changes | 3 |"));
+    assert!(out.contains("<details><summary><b>File Changes (1)</b></summary>"));
+    assert!(out.contains("**Removed files:**\n- `/etc/profile`"));
+    assert!(out.contains("**Changed file `/bin/busybox`:**\n- **Hashes**:"));
+
+    // documents without files say nothing about them
+    let mut buf = Vec::new();
+    TextRenderer
+        .render(&mock_diff(), &RenderOptions::default(), &mut buf)
+        .unwrap();
+    assert!(!String::from_utf8(buf).unwrap().contains("File"));
+}
+
+#[test]
+fn test_file_changes_in_machine_formats() {
+    let diff = mock_diff_with_file_changes();
+
+    let mut buf = Vec::new();
+    SarifRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let val = sarif_parse(&buf);
+    let results = val["runs"][0]["results"].as_array().unwrap();
+    assert!(results.iter().all(|r| r["ruleId"] == "file-changed"));
+    assert_eq!(results.len(), 3);
+    let changed = &results[2];
+    assert_eq!(
+        changed["message"]["text"],
+        "File changed in busybox: /bin/busybox (hashes: changed sha-256: aa -> cc)"
+    );
+    assert_eq!(changed["level"], "warning");
+    assert_eq!(
+        changed["locations"][0]["logicalLocations"][0]["fullyQualifiedName"],
+        "/bin/busybox"
+    );
+
+    let mut buf = Vec::new();
+    CsvRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let out = String::from_utf8(buf).unwrap();
+    assert!(out.contains("file-added,/etc/fstab,,package,,busybox"));
+    assert!(out.contains("file-removed,/etc/profile,,package,busybox,"));
+    assert!(out.contains("file-changed,/bin/busybox,,hashes,sha-256=aa,sha-256=cc"));
+
+    let mut buf = Vec::new();
+    JsonRenderer
+        .render_summary(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let val: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(val["file_changes"], 3);
+
+    let mut buf = Vec::new();
+    JsonRenderer
+        .render(&diff, &RenderOptions::default(), &mut buf)
+        .unwrap();
+    let val: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(val["file_diffs"][0]["package"], "busybox");
+    assert_eq!(val["file_diffs"][0]["added"][0]["path"], "/etc/fstab");
+}
+
 #[test]
 fn test_json_renderer_no_metadata_when_unchanged() {
     let diff = mock_diff_empty();
@@ -814,7 +1219,7 @@ fn test_sarif_renderer_rules() {
     let rules = val["runs"][0]["tool"]["driver"]["rules"]
         .as_array()
         .unwrap();
-    assert_eq!(rules.len(), 6);
+    assert_eq!(rules.len(), 8);
 
     let rule_ids: Vec<&str> = rules.iter().map(|r| r["id"].as_str().unwrap()).collect();
     assert_eq!(
@@ -826,6 +1231,8 @@ fn test_sarif_renderer_rules() {
             "dependency-changed",
             "metadata-changed",
             "parser-warning",
+            "file-changed",
+            "distro-upgraded",
         ]
     );
 
@@ -912,16 +1319,26 @@ fn test_sarif_renderer_all_field_changes() {
         .find(|r| r["ruleId"] == "component-changed")
         .unwrap();
     let msg = changed["message"]["text"].as_str().unwrap();
+    assert!(msg.contains("name: Pkg_A -> pkg-a"));
     assert!(msg.contains("version:"));
     assert!(msg.contains("license:"));
     assert!(msg.contains("supplier:"));
     assert!(msg.contains("purl:"));
+    assert!(msg.contains("qualifiers: distro=debian-12 -> distro=debian-13"));
     assert!(msg.contains("description:"));
     // hashes render with per-algorithm detail (algorithm name + digests), not a
     // bland "hashes changed"
     assert!(msg.contains("hashes:"));
     assert!(msg.contains("changed sha256: aaa -> bbb"));
     assert!(msg.contains("ecosystem:"));
+    assert!(msg.contains("kind: library -> framework"));
+    assert!(msg.contains("declared license: MIT -> BSD-3-Clause"));
+    assert!(msg.contains("copyright: Copyright 2020 Old Corp -> Copyright 2024 New Corp"));
+    assert!(msg.contains(
+        "external refs: vcs: https://github.com/acme/pkg-a -> https://github.com/fork/pkg-a"
+    ));
+    assert!(msg.contains("cpes: cpe:2.3:a:acme:pkg-a:1.0:*:*:*:*:*:*:* -> "));
+    assert!(msg.contains("property cdx:npm:package:development: <none> -> true"));
 
     let dep = results
         .iter()
@@ -945,6 +1362,9 @@ fn mock_diff_hash_change(old: BTreeMap<String, String>, new: BTreeMap<String, St
             new: c2,
             changes: vec![FieldChange::Hashes(old, new)],
             is_downgrade: false,
+            match_reason: None,
+            is_hash_mismatch: false,
+            match_confidence: None,
         }],
         ..Diff::default()
     }
@@ -1386,7 +1806,7 @@ fn test_sarif_renderer_warnings_rule_present() {
     let rules = val["runs"][0]["tool"]["driver"]["rules"]
         .as_array()
         .unwrap();
-    assert_eq!(rules.len(), 6);
+    assert_eq!(rules.len(), 8);
     assert_eq!(rules[5]["id"], "parser-warning");
     assert_eq!(rules[5]["defaultConfiguration"]["level"], "note");
 }
@@ -1398,6 +1818,7 @@ fn test_sarif_renderer_no_metadata_when_all_none_subfields() {
             timestamp: None,
             tools: None,
             authors: None,
+            primary_component: None,
         }),
         ..Diff::default()
     };
@@ -1487,13 +1908,23 @@ fn test_csv_renderer_all_field_changes() {
         .unwrap();
     let out = String::from_utf8(buf).unwrap();
 
+    assert!(out.contains(",name,Pkg_A,pkg-a"));
     assert!(out.contains(",version,1.0,1.1"));
     assert!(out.contains(",license,"));
     assert!(out.contains(",supplier,Old Corp,New Corp"));
     assert!(out.contains(",purl,"));
+    assert!(out.contains(",qualifiers,distro=debian-12,distro=debian-13"));
     assert!(out.contains(",description,Old description,New description"));
     assert!(out.contains(",hashes,"));
     assert!(out.contains(",ecosystem,npm,cargo"));
+    assert!(out.contains(",kind,library,framework"));
+    assert!(out.contains(",declared-license,MIT,BSD-3-Clause"));
+    assert!(out.contains(",copyright,Copyright 2020 Old Corp,Copyright 2024 New Corp"));
+    assert!(out.contains(
+        ",external-refs,vcs=https://github.com/acme/pkg-a,vcs=https://github.com/fork/pkg-a"
+    ));
+    assert!(out.contains(",cpes,cpe:2.3:a:acme:pkg-a:1.0:*:*:*:*:*:*:*,"));
+    assert!(out.contains(",property,,cdx:npm:package:development=true"));
     // edge diffs
     assert!(out.contains("edge-added,"));
     assert!(out.contains("edge-removed,"));
@@ -1715,6 +2146,9 @@ fn mock_diff_with_downgrade() -> Diff {
                 Some("1.0.0".into()),
             )],
             is_downgrade: true,
+            match_reason: None,
+            is_hash_mismatch: false,
+            match_confidence: None,
         }],
         ..Diff::default()
     }
@@ -1818,3 +2252,106 @@ fn test_sarif_renderer_upgrade_stays_warning() {
         .unwrap();
     assert_eq!(changed["level"], "warning");
 }
+
+#[test]
+fn test_user_mapped_change_carries_its_match_reason() {
+    let mut diff = mock_diff();
+    diff.changed[0].match_reason = Some(crate::MatchReason::UserMapping);
+    diff.changed[0].new.purl = Some("pkg:npm/pkg-a@1.1".into());
+    let render = |renderer: &dyn Fn(&mut Vec<u8>)| {
+        let mut buf = Vec::new();
+        renderer(&mut buf);
+        String::from_utf8(buf).unwrap()
+    };
+    let opts = RenderOptions::default();
+
+    let text = render(&|buf| TextRenderer.render(&diff, &opts, buf).unwrap());
+    assert!(
+        text.contains("pkg:npm/pkg-a@1.1\n  (matched by user mapping)\n  Version: 1.0 -> 1.1\n")
+    );
+    let md = render(&|buf| MarkdownRenderer.render(&diff, &opts, buf).unwrap());
+    assert!(md.contains("_matched by user mapping_"));
+    let csv = render(&|buf| CsvRenderer.render(&diff, &opts, buf).unwrap());
+    assert!(csv.contains("changed,pkg:npm/pkg-a@1.1,,match,,user-mapping\n"));
+    let sarif = render(&|buf| SarifRenderer.render(&diff, &opts, buf).unwrap());
+    assert!(sarif.contains(
+        "Component changed: pkg:npm/pkg-a@1.1 (version: 1.0 -> 1.1; matched by user mapping)"
+    ));
+    let json = render(&|buf| JsonRenderer.render(&diff, &opts, buf).unwrap());
+    let val: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(val["changed"][0]["match_reason"], "user-mapping");
+}
+
+#[test]
+fn test_same_version_hash_mismatch_is_highlighted() {
+    let mut diff = mock_diff();
+    let hashes = |digest: &str| BTreeMap::from([("sha-512".to_string(), digest.to_string())]);
+    let change = &mut diff.changed[0];
+    change.new.version = change.old.version.clone();
+    change.new.purl = Some("pkg:npm/pkg-a@1.0".into());
+    change.changes = vec![FieldChange::Hashes(hashes("aa"), hashes("bb"))];
+    change.is_hash_mismatch = true;
+    let render = |renderer: &dyn Fn(&mut Vec<u8>)| {
+        let mut buf = Vec::new();
+        renderer(&mut buf);
+        String::from_utf8(buf).unwrap()
+    };
+    let opts = RenderOptions::default();
+
+    let text = render(&|buf| TextRenderer.render(&diff, &opts, buf).unwrap());
+    assert!(text.contains("Hash mismatches:  1 (same version, other digest)\n"));
+    assert!(text.contains("  Hashes (same-version mismatch):\n    ~ sha-512: aa -> bb\n"));
+    let md = render(&|buf| MarkdownRenderer.render(&diff, &opts, buf).unwrap());
+    assert!(md.contains("| **Hash mismatches** (same version, other digest) | 1 |"));
+    assert!(md.contains("- **Hashes (same-version mismatch)**:"));
+    let csv = render(&|buf| CsvRenderer.render(&diff, &opts, buf).unwrap());
+    assert!(csv.contains("changed,pkg:npm/pkg-a@1.0,,hashes-mismatch,sha-512=aa,sha-512=bb\n"));
+    let summary = render(&|buf| CsvRenderer.render_summary(&diff, &opts, buf).unwrap());
+    assert!(summary.contains("hash_mismatches,1\n"));
+
+    let sarif = render(&|buf| SarifRenderer.render(&diff, &opts, buf).unwrap());
+    let val = sarif_parse(sarif.as_bytes());
+    let result = val["runs"][0]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["ruleId"] == "component-changed")
+        .unwrap();
+    assert_eq!(result["level"], "error");
+    assert!(result["message"]["text"]
+        .as_str()
+        .unwrap()
+        .contains("hashes (same-version mismatch): changed sha-512: aa -> bb"));
+
+    let json = render(&|buf| JsonRenderer.render(&diff, &opts, buf).unwrap());
+    let val: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(val["changed"][0]["is_hash_mismatch"], true);
+    let summary = render(&|buf| JsonRenderer.render_summary(&diff, &opts, buf).unwrap());
+    let val: serde_json::Value = serde_json::from_str(&summary).unwrap();
+    assert_eq!(val["hash_mismatches"], 1);
+}
+
+#[test]
+fn test_graph_matched_change_shows_its_confidence() {
+    let mut diff = mock_diff();
+    diff.changed[0].match_reason = Some(crate::MatchReason::Graph);
+    diff.changed[0].match_confidence = Some(80);
+    diff.changed[0].new.purl = Some("pkg:npm/pkg-a@1.1".into());
+    let render = |renderer: &dyn Fn(&mut Vec<u8>)| {
+        let mut buf = Vec::new();
+        renderer(&mut buf);
+        String::from_utf8(buf).unwrap()
+    };
+    let opts = RenderOptions::default();
+
+    let text = render(&|buf| TextRenderer.render(&diff, &opts, buf).unwrap());
+    assert!(text.contains("  (matched by dependency graph, 80% confidence)\n"));
+    let md = render(&|buf| MarkdownRenderer.render(&diff, &opts, buf).unwrap());
+    assert!(md.contains("_matched by dependency graph, 80% confidence_"));
+    let csv = render(&|buf| CsvRenderer.render(&diff, &opts, buf).unwrap());
+    assert!(csv.contains("changed,pkg:npm/pkg-a@1.1,,match,,graph (80%)\n"));
+    let json = render(&|buf| JsonRenderer.render(&diff, &opts, buf).unwrap());
+    let val: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(val["changed"][0]["match_reason"], "graph");
+    assert_eq!(val["changed"][0]["match_confidence"], 80);
+}
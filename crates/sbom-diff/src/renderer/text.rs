@@ -1,8 +1,9 @@
 use super::{
-    kind_suffix, write_full, write_summary, FieldChangeFormatter, FullFormatter, RenderOptions,
-    Renderer, SectionKind, SummaryFormatter, SummaryRenderer,
+    describes, distro_label, file_package, kind_suffix, write_field_changes, write_full,
+    write_summary, FieldChangeFormatter, FullFormatter, RenderOptions, Renderer, SectionKind,
+    SummaryFormatter, SummaryRenderer,
 };
-use crate::{Diff, EcosystemCounts, EdgeDiff};
+use crate::{Diff, DistroUpgrade, EcosystemCounts, EdgeDiff, FileDiff};
 use sbom_model::Component;
 use std::collections::BTreeMap;
 use std::io::Write;
@@ -21,11 +22,10 @@ impl FieldChangeFormatter for TextRenderer {
         writeln!(w, "  {}: {} -> {}", name, old, new)
     }
 
-    fn hash_header<W: Write>(&self, w: &mut W, downgrade: bool) -> std::io::Result<()> {
-        if downgrade {
-            writeln!(w, "  Hashes (algorithm downgrade):")
-        } else {
-            writeln!(w, "  Hashes:")
+    fn hash_header<W: Write>(&self, w: &mut W, note: Option<&str>) -> std::io::Result<()> {
+        match note {
+            Some(note) => writeln!(w, "  Hashes ({}):", note),
+            None => writeln!(w, "  Hashes:"),
         }
     }
 
@@ -50,6 +50,10 @@ impl FieldChangeFormatter for TextRenderer {
     fn component_header<W: Write>(&self, w: &mut W, id: &str) -> std::io::Result<()> {
         writeln!(w, "{}", id)
     }
+
+    fn match_note<W: Write>(&self, w: &mut W, note: &str) -> std::io::Result<()> {
+        writeln!(w, "  ({})", note)
+    }
 }
 
 impl FullFormatter for TextRenderer {
@@ -120,6 +124,17 @@ impl FullFormatter for TextRenderer {
         writeln!(w)
     }
 
+    fn distro_open<W: Write>(
+        &self,
+        w: &mut W,
+        upgrade: &DistroUpgrade,
+        _count: usize,
+    ) -> std::io::Result<()> {
+        let heading = format!("[~] Distro Upgrade: {}", distro_label(upgrade));
+        writeln!(w, "{}", heading)?;
+        writeln!(w, "{}", "-".repeat(heading.len()))
+    }
+
     fn component_list<W: Write>(&self, w: &mut W, components: &[Component]) -> std::io::Result<()> {
         for c in components {
             writeln!(w, "{}", c.purl.as_deref().unwrap_or(c.id.as_str()))?;
@@ -156,6 +171,30 @@ impl FullFormatter for TextRenderer {
         Ok(())
     }
 
+    fn file_open<W: Write>(&self, w: &mut W, _count: usize) -> std::io::Result<()> {
+        writeln!(w, "[~] File Changes")?;
+        writeln!(w, "----------------")
+    }
+
+    fn file_entry<W: Write>(&self, w: &mut W, files: &FileDiff) -> std::io::Result<()> {
+        writeln!(w, "{}", file_package(files))?;
+        for file in &files.removed {
+            writeln!(w, "  - {}", file.path)?;
+        }
+        for file in &files.added {
+            writeln!(w, "  + {}", file.path)?;
+        }
+        for file in &files.changed {
+            writeln!(w, "  ~ {}", file.path)?;
+            write_field_changes(self, w, &file.changes, false, false)?;
+        }
+        Ok(())
+    }
+
+    fn file_close<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        writeln!(w)
+    }
+
     fn metadata_open<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
         writeln!(w, "[~] Metadata Changes")?;
         writeln!(w, "--------------------")
@@ -198,6 +237,25 @@ impl SummaryFormatter for TextRenderer {
         writeln!(w, "Removed:          {}", diff.removed.len())?;
         writeln!(w, "Changed:          {}", diff.changed.len())?;
         writeln!(w, "Edge changes:     {}", diff.edge_diffs.len())?;
+        if !diff.file_diffs.is_empty() {
+            writeln!(w, "File changes:     {}", diff.file_change_count())?;
+        }
+        let hash_mismatches = diff.hash_mismatch_count();
+        if hash_mismatches > 0 {
+            writeln!(
+                w,
+                "Hash mismatches:  {} (same version, other digest)",
+                hash_mismatches
+            )?;
+        }
+        if let Some(upgrade) = &diff.distro_upgrade {
+            writeln!(
+                w,
+                "Distro upgrade:   {} ({} packages)",
+                distro_label(upgrade),
+                upgrade.components.len()
+            )?;
+        }
         writeln!(
             w,
             "Metadata changed: {}",
@@ -206,7 +264,11 @@ impl SummaryFormatter for TextRenderer {
             } else {
                 "no"
             }
-        )
+        )?;
+        if let Some(describes) = describes(diff) {
+            writeln!(w, "Describes:        {}", describes)?;
+        }
+        Ok(())
     }
 
     fn write_ecosystem_breakdown<W: Write>(
@@ -0,0 +1,183 @@
+//! benchmark for the streaming JSON readers against the document-model path:
+//! wall time, peak heap above the input buffer, and how much of that peak is
+//! the resulting `Sbom` versus transient parser state, per reader.
+//! run with: cargo test --release -p sbom-diff --test bench_streaming_parse -- --nocapture --ignored
+
+use sbom_model::Sbom;
+use sbom_model_cyclonedx::CycloneDxReader;
+use sbom_model_spdx::SpdxReader;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// the system allocator, tracking live and peak heap bytes.
+struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let now = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(now, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            let now = CURRENT.fetch_add(new_size, Ordering::Relaxed) + new_size;
+            PEAK.fetch_max(now, Ordering::Relaxed);
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+        new
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+/// a CycloneDX 1.5 document with `n` components, each carrying the fields
+/// real generators emit, and a dependency chain through them.
+fn cyclonedx_json(n: usize) -> Vec<u8> {
+    let mut s = String::from(
+        r#"{"bomFormat":"CycloneDX","specVersion":"1.5","version":1,"metadata":{"timestamp":"2024-01-01T00:00:00Z","component":{"type":"application","bom-ref":"root","name":"app"}},"components":["#,
+    );
+    for i in 0..n {
+        if i > 0 {
+            s.push(',');
+        }
+        write!(
+            s,
+            r#"{{"type":"library","bom-ref":"c{i}","name":"pkg-{i}","version":"1.{i}.0","purl":"pkg:npm/pkg-{i}@1.{i}.0","description":"synthetic package number {i} for the streaming benchmark","licenses":[{{"license":{{"id":"MIT"}}}}],"hashes":[{{"alg":"SHA-256","content":"{i:064x}"}}],"externalReferences":[{{"type":"website","url":"https://example.com/pkg-{i}"}}],"properties":[{{"name":"cdx:npm:package:path","value":"node_modules/pkg-{i}"}}]}}"#
+        )
+        .unwrap();
+    }
+    s.push_str(r#"],"dependencies":[{"ref":"root","dependsOn":["c0"]}"#);
+    for i in 0..n {
+        write!(s, r#",{{"ref":"c{i}","dependsOn":["c{}"]}}"#, (i + 1) % n).unwrap();
+    }
+    s.push_str("]}");
+    s.into_bytes()
+}
+
+/// an SPDX 2.3 document with `n` packages and a DEPENDS_ON chain through them.
+fn spdx_json(n: usize) -> Vec<u8> {
+    let mut s = String::from(
+        r#"{"spdxVersion":"SPDX-2.3","dataLicense":"CC0-1.0","SPDXID":"SPDXRef-DOCUMENT","name":"bench","documentNamespace":"https://example.com/bench","creationInfo":{"creators":["Tool: bench"],"created":"2024-01-01T00:00:00Z"},"packages":["#,
+    );
+    for i in 0..n {
+        if i > 0 {
+            s.push(',');
+        }
+        write!(
+            s,
+            r#"{{"name":"pkg-{i}","SPDXID":"SPDXRef-p{i}","versionInfo":"1.{i}.0","downloadLocation":"NOASSERTION","filesAnalyzed":false,"licenseConcluded":"MIT","licenseDeclared":"MIT","copyrightText":"NOASSERTION","description":"synthetic package number {i} for the streaming benchmark","checksums":[{{"algorithm":"SHA256","checksumValue":"{i:064x}"}}],"externalRefs":[{{"referenceCategory":"PACKAGE-MANAGER","referenceType":"purl","referenceLocator":"pkg:npm/pkg-{i}@1.{i}.0"}}]}}"#
+        )
+        .unwrap();
+    }
+    s.push_str(r#"],"relationships":["#);
+    for i in 0..n {
+        if i > 0 {
+            s.push(',');
+        }
+        write!(
+            s,
+            r#"{{"spdxElementId":"SPDXRef-p{i}","relatedSpdxElement":"SPDXRef-p{}","relationshipType":"DEPENDS_ON"}}"#,
+            (i + 1) % n
+        )
+        .unwrap();
+    }
+    s.push_str("]}");
+    s.into_bytes()
+}
+
+struct Measurement {
+    elapsed: Duration,
+    /// peak heap above what was live beforehand (the input buffer).
+    peak: usize,
+    /// heap still held once parsing returned: the `Sbom` itself.
+    retained: usize,
+}
+
+/// runs `parse` once, returning its result and what it cost.
+fn measure(input: &[u8], parse: fn(&[u8]) -> Sbom) -> (Sbom, Measurement) {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let start = Instant::now();
+    let sbom = parse(input);
+    let elapsed = start.elapsed();
+    let measurement = Measurement {
+        elapsed,
+        peak: PEAK.load(Ordering::Relaxed) - baseline,
+        retained: CURRENT.load(Ordering::Relaxed) - baseline,
+    };
+    (sbom, measurement)
+}
+
+fn mib(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+fn bench_pair(
+    label: &str,
+    n: usize,
+    input: &[u8],
+    model: fn(&[u8]) -> Sbom,
+    streaming: fn(&[u8]) -> Sbom,
+) {
+    let (expected, model_cost) = measure(input, model);
+    drop(expected);
+    let (streamed, stream_cost) = measure(input, streaming);
+    assert_eq!(
+        streamed.components.len(),
+        n + usize::from(label == "cyclonedx")
+    );
+
+    println!("{label} (n={n}, input={:.1}MiB):", mib(input.len()));
+    for (path, cost) in [("document model", model_cost), ("streaming", stream_cost)] {
+        println!(
+            "  {path:<14} {:>7.0}ms  peak={:>7.1}MiB  sbom={:>7.1}MiB  transient={:>7.1}MiB",
+            cost.elapsed.as_secs_f64() * 1000.0,
+            mib(cost.peak),
+            mib(cost.retained),
+            mib(cost.peak - cost.retained),
+        );
+    }
+}
+
+// a single test, so that no other test allocates while one is measured.
+#[test]
+#[ignore]
+fn benchmark_streaming_parse() {
+    println!();
+    for &n in &[10_000, 50_000, 200_000] {
+        let input = cyclonedx_json(n);
+        bench_pair(
+            "cyclonedx",
+            n,
+            &input,
+            |c| CycloneDxReader::read_json(c).unwrap(),
+            |c| CycloneDxReader::read_json_streaming(c).unwrap(),
+        );
+        drop(input);
+
+        let input = spdx_json(n);
+        bench_pair(
+            "spdx",
+            n,
+            &input,
+            |c| SpdxReader::read_json(c).unwrap(),
+            |c| SpdxReader::read_json_streaming(c).unwrap(),
+        );
+    }
+}
@@ -1321,6 +1321,63 @@ fn include_ecosystem_with_fail_on_respects_filter() {
     );
 }
 
+#[test]
+fn include_kind_filters_to_operating_system() {
+    let out = sbom_diff()
+        .arg(fixture("component-kind-old.json"))
+        .arg(fixture("component-kind-new.json"))
+        .arg("--summary")
+        .arg("--include-kind")
+        .arg("operating-system")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    // only the debian component itself; the deb packages are libraries
+    assert!(stdout.contains("Old total:        1 components"));
+    assert!(stdout.contains("Added:            0"));
+    assert!(stdout.contains("Changed:          1"));
+    assert!(stdout.contains("Edge changes:     0"));
+}
+
+#[test]
+fn exclude_kind_removes_libraries() {
+    let out = sbom_diff()
+        .arg(fixture("component-kind-old.json"))
+        .arg(fixture("component-kind-new.json"))
+        .arg("--exclude-kind")
+        .arg("LIBRARY")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Added:            0"));
+    // debian and web; busybox is a library on the new side
+    assert!(stdout.contains("Changed:          2"));
+    assert!(stdout.contains("Version: 12.4 -> 12.5"));
+    assert!(!stdout.contains("openssl"));
+    // the container image's new edge is kept: its parent is not a library
+    assert!(stdout.contains("Edge changes:     1"));
+}
+
+#[test]
+fn only_kind_reports_kind_change() {
+    let out = sbom_diff()
+        .arg(fixture("component-kind-old.json"))
+        .arg(fixture("component-kind-new.json"))
+        .arg("--only")
+        .arg("kind")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Changed:          1"));
+    assert!(stdout.contains("Kind: application -> library"));
+}
+
 #[test]
 fn fail_on_hash_algorithm_downgrade_exits_3() {
     let out = sbom_diff()
@@ -2365,3 +2422,968 @@ fn reordering_expression_operands_is_not_a_license_change() {
         String::from_utf8_lossy(&out.stderr)
     );
 }
+
+#[test]
+fn attested_sboms_diff_like_their_predicates() {
+    let attested = sbom_diff()
+        .arg(fixture("attested-golden-old.dsse.json"))
+        .arg(fixture("attested-golden-new.intoto.jsonl"))
+        .output()
+        .unwrap();
+    let plain = sbom_diff()
+        .arg(fixture("golden-old.json"))
+        .arg(fixture("golden-new.spdx.json"))
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        attested.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&attested.stderr)
+    );
+    assert_eq!(attested.stdout, plain.stdout);
+}
+
+#[test]
+fn verify_key_accepts_signed_inputs() {
+    let out = sbom_diff()
+        .arg(fixture("signed-ed25519-golden-old.dsse.json"))
+        .arg(fixture("signed-ed25519-golden-new.dsse.json"))
+        .arg("--verify-key")
+        .arg(fixture("attestation-ed25519.pub.pem"))
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("Changed"),
+        "expected a rendered diff: {stdout}"
+    );
+}
+
+#[test]
+fn verify_key_accepts_ecdsa_p256_signature() {
+    let out = sbom_diff()
+        .arg(fixture("signed-p256-golden-old.dsse.json"))
+        .arg(fixture("signed-p256-golden-old.dsse.json"))
+        .arg("--verify-key")
+        .arg(fixture("attestation-p256.pub.pem"))
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn verify_key_wrong_key_exits_4() {
+    let out = sbom_diff()
+        .arg(fixture("signed-p256-golden-old.dsse.json"))
+        .arg(fixture("signed-ed25519-golden-new.dsse.json"))
+        .arg("--verify-key")
+        .arg(fixture("attestation-p256.pub.pem"))
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("failed to load new sbom") && !stderr.contains("old sbom"),
+        "only the new input should be rejected: {stderr}"
+    );
+    assert!(stderr.contains("--verify-key"), "{stderr}");
+    assert!(out.stdout.is_empty(), "no diff should be rendered");
+}
+
+#[test]
+fn verify_key_tampered_payload_exits_4() {
+    let out = sbom_diff()
+        .arg(fixture("signed-ed25519-golden-old.dsse.json"))
+        .arg(fixture("signed-ed25519-tampered-new.dsse.json"))
+        .arg("--verify-key")
+        .arg(fixture("attestation-ed25519.pub.pem"))
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("no signature on the DSSE envelope verifies"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn verify_key_missing_signatures_exit_4_for_both_inputs() {
+    let out = sbom_diff()
+        .arg(fixture("golden-old.json"))
+        .arg(fixture("attested-golden-old.dsse.json"))
+        .arg("--verify-key")
+        .arg(fixture("attestation-ed25519.pub.pem"))
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("failed to load old sbom: input is not a signed DSSE envelope"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("failed to load new sbom: DSSE envelope carries no signatures"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn verify_key_unreadable_key_exits_1() {
+    let out = sbom_diff()
+        .arg(fixture("signed-ed25519-golden-old.dsse.json"))
+        .arg(fixture("signed-ed25519-golden-new.dsse.json"))
+        .arg("--verify-key")
+        .arg(fixture("golden-old.json"))
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("invalid verification key"), "{stderr}");
+}
+
+#[test]
+fn oci_layout_input_reports_entry_used() {
+    let out = sbom_diff()
+        .arg(fixture("oci-layout"))
+        .arg(fixture("golden-new.spdx.json"))
+        .arg("--fail-on")
+        .arg("changed-components")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert_eq!(out.status.code(), Some(0), "{stderr}");
+    assert!(
+        stderr.contains(
+            "using entry blobs/sha256/142d7824a86e9b7eeeb77a198bb318d0e390eadbd4f54c2aa1baeef73e47daea"
+        ),
+        "{stderr}"
+    );
+    assert!(stderr.contains("https://spdx.dev/Document"), "{stderr}");
+}
+
+#[test]
+fn entry_names_an_oci_blob_by_digest() {
+    let out = sbom_diff()
+        .arg(fixture("golden-old.spdx.json"))
+        .arg(fixture("oci-layout"))
+        .arg("--new-entry")
+        .arg("sha256:142d7824a86e9b7eeeb77a198bb318d0e390eadbd4f54c2aa1baeef73e47daea")
+        .arg("-o")
+        .arg("json")
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let plain = sbom_diff()
+        .arg(fixture("golden-old.spdx.json"))
+        .arg(fixture("golden-new.spdx.json"))
+        .arg("-o")
+        .arg("json")
+        .output()
+        .unwrap();
+    assert_eq!(out.stdout, plain.stdout);
+}
+
+#[test]
+fn entry_missing_from_archive_exits_1() {
+    let out = sbom_diff()
+        .arg(fixture("oci-layout"))
+        .arg(fixture("golden-new.spdx.json"))
+        .arg("--entry")
+        .arg("sbom.json")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("has no entry 'sbom.json'"), "{stderr}");
+}
+
+#[test]
+fn old_and_new_entry_select_each_side_separately() {
+    let out = sbom_diff()
+        .arg(fixture("oci-layout"))
+        .arg(fixture("oci-layout"))
+        .arg("--old-entry")
+        .arg("sha256:142d7824a86e9b7eeeb77a198bb318d0e390eadbd4f54c2aa1baeef73e47daea")
+        .arg("--new-entry")
+        .arg("blobs/sha256/142d7824a86e9b7eeeb77a198bb318d0e390eadbd4f54c2aa1baeef73e47daea")
+        .arg("--fail-on")
+        .arg("changed-components")
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn entry_for_a_plain_sbom_input_exits_1() {
+    let out = sbom_diff()
+        .arg(fixture("golden-old.spdx.json"))
+        .arg(fixture("oci-layout"))
+        .arg("--entry")
+        .arg("sha256:142d7824a86e9b7eeeb77a198bb318d0e390eadbd4f54c2aa1baeef73e47daea")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("failed to load old sbom"), "{stderr}");
+    assert!(stderr.contains("is not an archive"), "{stderr}");
+}
+
+#[test]
+fn auto_detects_syft_json() {
+    let out = sbom_diff()
+        .arg(fixture("golden-old.syft.json"))
+        .arg(fixture("golden-new.syft.json"))
+        .arg("--summary")
+        .arg("--output")
+        .arg("json")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let v: serde_json::Value = serde_json::from_str(&stdout).expect("output should be valid JSON");
+    assert_eq!(v["added"], 1);
+    assert_eq!(v["removed"], 1);
+    assert_eq!(v["changed"], 2);
+    assert_eq!(v["edge_changes"], 1);
+}
+
+#[test]
+fn syft_json_diffs_against_spdx_json() {
+    let out = sbom_diff()
+        .arg(fixture("golden-old.syft.json"))
+        .arg(fixture("golden-new.spdx.json"))
+        .arg("--output")
+        .arg("json")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let v: serde_json::Value = serde_json::from_str(&stdout).expect("output should be valid JSON");
+    assert_eq!(v["added"].as_array().unwrap().len(), 1);
+    assert_eq!(v["removed"].as_array().unwrap().len(), 1);
+    assert_eq!(v["changed"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn explicit_syft_format_matches_auto() {
+    let run = |format: Option<&str>| {
+        let mut cmd = sbom_diff();
+        cmd.arg(fixture("golden-old.syft.json"))
+            .arg(fixture("golden-new.syft.json"));
+        if let Some(f) = format {
+            cmd.arg("--format").arg(f);
+        }
+        let out = cmd.arg("--output").arg("json").output().unwrap();
+        assert_eq!(out.status.code(), Some(0));
+        String::from_utf8_lossy(&out.stdout).into_owned()
+    };
+
+    assert_eq!(run(Some("syft")), run(None));
+}
+
+#[test]
+fn nested_library_moving_between_applications_reports_containment() {
+    let out = sbom_diff()
+        .arg(fixture("containment-old.json"))
+        .arg(fixture("containment-new.json"))
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Edge changes:     2"));
+    assert!(stdout.contains("pkg:generic/backend@1.0.0\n  + pkg:npm/left-pad@1.3.0 (contained)"));
+    assert!(stdout.contains("pkg:generic/frontend@1.0.0\n  - pkg:npm/left-pad@1.3.0 (contained)"));
+}
+
+#[test]
+fn declared_license_drift_is_reported() {
+    let out = sbom_diff()
+        .arg(fixture("declared-license-old.spdx.json"))
+        .arg(fixture("declared-license-new.spdx.json"))
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Declared license: MIT -> GPL-3.0-only"));
+    // the concluded license did not change
+    assert!(!stdout.contains("  License:"));
+}
+
+#[test]
+fn deny_license_checks_declared_license_when_asked() {
+    let run = |source: &str| {
+        sbom_diff()
+            .arg(fixture("declared-license-old.spdx.json"))
+            .arg(fixture("declared-license-new.spdx.json"))
+            .arg("--deny-license")
+            .arg("GPL-3.0-only")
+            .arg("--license-source")
+            .arg(source)
+            .output()
+            .unwrap()
+    };
+
+    assert_eq!(run("concluded").status.code(), Some(0));
+
+    let out = run("declared");
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("declared license GPL-3.0-only is denied"),
+        "stderr: {}",
+        stderr
+    );
+
+    assert_eq!(run("both").status.code(), Some(2));
+}
+
+#[test]
+fn only_copyright_reports_copyright_holder_change() {
+    let out = sbom_diff()
+        .arg(fixture("copyright-old.json"))
+        .arg(fixture("copyright-new.json"))
+        .arg("--only")
+        .arg("copyright")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Changed:          1"));
+    assert!(stdout.contains(
+        "Copyright: Copyright JS Foundation and other contributors -> Copyright OpenJS Foundation and other contributors"
+    ));
+    assert!(!stdout.contains("Version:"));
+}
+
+#[test]
+fn vcs_moving_to_a_fork_is_reported_and_gated() {
+    let out = sbom_diff()
+        .arg(fixture("vcs-old.json"))
+        .arg(fixture("vcs-new.json"))
+        .arg("--fail-on")
+        .arg("vcs-changed")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(3));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Changed:          2"));
+    assert!(stdout.contains(
+        "External ref (vcs): https://github.com/lodash/lodash.git -> https://github.com/lodash-mirror-dev/lodash.git"
+    ));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("vcs changed on component pkg:npm/lodash@4.17.21"),
+        "stderr should name the forked component, got: {}",
+        stderr
+    );
+    // left-pad only moved its pinned tag; that is not a different repository
+    assert!(!stderr.contains("left-pad"), "got: {}", stderr);
+}
+
+#[test]
+fn purl_less_components_renamed_across_generators_match_by_cpe() {
+    let out = sbom_diff()
+        .arg(fixture("cpe-old.spdx.json"))
+        .arg(fixture("cpe-new.json"))
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Added:            0"), "got: {}", stdout);
+    assert!(stdout.contains("Removed:          0"), "got: {}", stdout);
+    assert!(stdout.contains("Changed:          2"), "got: {}", stdout);
+    assert!(stdout.contains("Version: 3.0.2 -> 3.0.7"));
+    assert!(stdout.contains("Version: 1.36.0 -> 1.36.1"));
+    // the version moved inside the CPE too; that is not reported twice
+    assert!(!stdout.contains("CPEs:"));
+}
+
+#[test]
+fn property_changes_are_reported_per_name() {
+    let out = sbom_diff()
+        .arg(fixture("properties-old.json"))
+        .arg(fixture("properties-new.json"))
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Changed:          2"));
+    assert!(stdout.contains("Property (cdx:npm:package:development): true -> false"));
+    assert!(stdout.contains(
+        "Property (syft:location:0:path): /app/package-lock.json -> /srv/app/package-lock.json"
+    ));
+}
+
+#[test]
+fn include_property_limits_diff_to_matching_names() {
+    let out = sbom_diff()
+        .arg(fixture("properties-old.json"))
+        .arg(fixture("properties-new.json"))
+        .arg("--include-property")
+        .arg("cdx:npm:*")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    // left-pad only moved its scan location, which is no longer compared
+    assert!(stdout.contains("Changed:          1"), "got: {}", stdout);
+    assert!(stdout.contains("Unchanged:        1"));
+    assert!(stdout.contains("Property (cdx:npm:package:development): true -> false"));
+    assert!(!stdout.contains("syft:location"));
+}
+
+#[test]
+fn report_states_the_product_each_side_describes() {
+    let out = sbom_diff()
+        .arg(fixture("product-old.spdx.json"))
+        .arg(fixture("product-new.json"))
+        .arg("--summary")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("Describes:        acme-app@1.0.0 -> acme-app@1.1.0"),
+        "got: {}",
+        stdout
+    );
+
+    let out = sbom_diff()
+        .arg(fixture("product-old.spdx.json"))
+        .arg(fixture("product-new.json"))
+        .args(["--output", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["old_document"]["format"], "spdx");
+    assert_eq!(json["old_document"]["name"], "acme-app-1.0.0");
+    assert_eq!(json["new_document"]["format"], "cyclonedx");
+    assert_eq!(json["new_document"]["spec_version"], "1.5");
+    assert_eq!(
+        json["new_document"]["serial_number"],
+        "urn:uuid:6b1f3a52-0a4e-4c57-9a8e-7d2f0c1b9e44"
+    );
+    assert_eq!(json["new_document"]["bom_version"], 2);
+}
+
+#[test]
+fn primary_component_change_fails_metadata_gate() {
+    let out = sbom_diff()
+        .arg(fixture("product-old.spdx.json"))
+        .arg(fixture("product-new.json"))
+        .args(["--fail-on", "metadata-changed"])
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains(
+        "document primary component changed: acme-app@1.0.0 -> acme-app@1.1.0 (--fail-on metadata-changed)"
+    ));
+}
+
+#[test]
+fn cyclonedx_dev_and_excluded_dependencies_carry_their_kind() {
+    let out = sbom_diff()
+        .arg(fixture("dev-scope-old.json"))
+        .arg(fixture("dev-scope-new.json"))
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("  + pkg:npm/jest@29.7.0 (dev)"),
+        "got: {}",
+        stdout
+    );
+    assert!(stdout.contains("  ~ pkg:npm/mocha@10.2.0 (runtime -> dev)"));
+}
+
+#[test]
+fn include_files_reports_file_changes_per_package() {
+    let out = sbom_diff()
+        .arg(fixture("firmware-old.spdx.json"))
+        .arg(fixture("firmware-new.spdx.json"))
+        .arg("--include-files")
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("File changes:     5"), "got: {}", stdout);
+    assert!(stdout.contains("base-files\n  - /etc/profile\n  + /etc/fstab\n  ~ /etc/inittab\n"));
+    assert!(stdout.contains(&format!(
+        "busybox\n  ~ /bin/busybox\n  Hashes:\n    ~ sha-256: {} -> {}\n",
+        "a".repeat(64),
+        "c".repeat(64)
+    )));
+    assert!(stdout
+        .contains("  ~ /bin/busybox#bytes=4096-8191\n  License: GPL-2.0-only -> GPL-2.0-or-later"));
+}
+
+#[test]
+fn files_are_not_diffed_without_include_files() {
+    let out = sbom_diff()
+        .arg(fixture("firmware-old.spdx.json"))
+        .arg(fixture("firmware-new.spdx.json"))
+        .args(["--output", "json"])
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(v.get("file_diffs").is_none());
+
+    // an ecosystem filter keeps the files of the packages it keeps
+    let out = sbom_diff()
+        .arg(fixture("firmware-old.spdx.json"))
+        .arg(fixture("firmware-new.spdx.json"))
+        .args(["--include-files", "--output", "json"])
+        .args(["--include-ecosystem", "npm"])
+        .output()
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert!(v.get("file_diffs").is_none(), "got: {}", v);
+}
+
+#[test]
+fn purl_spellings_from_different_tools_match() {
+    // %40Angular vs @angular, maven type=jar vs the default repository_url,
+    // pypi Django_Rest_Framework vs django-rest-framework
+    let out = sbom_diff()
+        .arg(fixture("purl-spelling-old.json"))
+        .arg(fixture("purl-spelling-new.spdx.json"))
+        .args(["--output", "json", "--fail-on", "purl-changed"])
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(0));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["unchanged"], 3, "got: {}", v);
+    assert_eq!(v["added"].as_array().unwrap().len(), 0);
+    assert_eq!(v["changed"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn ignore_qualifier_pairs_per_arch_packages_across_a_mirror_move() {
+    let run = |extra: &[&str]| {
+        sbom_diff()
+            .arg(fixture("multiarch-mirror-old.json"))
+            .arg(fixture("multiarch-mirror-new.json"))
+            .args(["--output", "json", "--fail-on", "purl-changed"])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    // every package's purl moved to another repository_url
+    assert_eq!(run(&[]).status.code(), Some(3));
+
+    let out = run(&[
+        "--ignore-qualifier",
+        "repository_url",
+        "--ignore-qualifier",
+        "arch",
+    ]);
+    assert_eq!(out.status.code(), Some(0));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["added"].as_array().unwrap().len(), 0);
+    assert_eq!(v["removed"].as_array().unwrap().len(), 0);
+    let changed = v["changed"].as_array().unwrap();
+    assert_eq!(changed.len(), 4);
+    for change in changed {
+        // amd64 stays paired with amd64, arm64 with arm64
+        let arch = |side: &str| {
+            let purl = change[side]["purl"].as_str().unwrap();
+            purl.split("arch=").nth(1).unwrap()[..5].to_string()
+        };
+        assert_eq!(arch("old"), arch("new"), "got: {}", change);
+        assert_eq!(
+            change["changes"],
+            serde_json::json!([{
+                "Qualifiers": [
+                    {"repository_url": "https://deb.debian.org/debian"},
+                    {"repository_url": "https://mirror.example/debian"}
+                ]
+            }])
+        );
+    }
+}
+
+#[test]
+fn distro_upgrade_groups_os_package_changes() {
+    let out = sbom_diff()
+        .arg(fixture("base-image-old.json"))
+        .arg(fixture("base-image-new.json"))
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("Distro upgrade:   debian-11 -> debian-12 (4 packages)"));
+    let grouped = stdout
+        .split("[~] Distro Upgrade: debian-11 -> debian-12")
+        .nth(1)
+        .unwrap();
+    assert!(grouped.contains("pkg:deb/debian/zlib1g@1:1.2.13.dfsg-1"));
+    // the release is named once, not as a qualifier change per package
+    assert!(!stdout.contains("Qualifiers:"));
+    assert!(!stdout.contains("[~] Changed"));
+    assert!(stdout.contains("[+] Added\n---------\npkg:deb/debian/libssl3@"));
+
+    let out = sbom_diff()
+        .arg(fixture("base-image-old.json"))
+        .arg(fixture("base-image-new.json"))
+        .args(["--output", "json"])
+        .output()
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["distro_upgrade"]["old"], "debian-11");
+    assert_eq!(v["distro_upgrade"]["new"], "debian-12");
+    assert_eq!(
+        v["distro_upgrade"]["components"].as_array().unwrap().len(),
+        4
+    );
+}
+
+#[test]
+fn name_spellings_reconcile_across_version_bumps() {
+    // PyYAML vs pyyaml, typing_extensions vs typing-extensions, and a bare
+    // maven artifactId vs groupId:artifactId, each with a new version
+    let out = sbom_diff()
+        .arg(fixture("name-spelling-old.json"))
+        .arg(fixture("name-spelling-new.spdx.json"))
+        .args(["--output", "json"])
+        .output()
+        .unwrap();
+
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["added"].as_array().unwrap().len(), 0, "got: {}", v);
+    assert_eq!(v["removed"].as_array().unwrap().len(), 0);
+    let changed = v["changed"].as_array().unwrap();
+    assert_eq!(changed.len(), 3);
+    let yaml = changed
+        .iter()
+        .find(|c| c["new"]["name"] == "pyyaml")
+        .unwrap();
+    assert_eq!(
+        yaml["changes"][0],
+        serde_json::json!({"Name": ["PyYAML", "pyyaml"]})
+    );
+}
+
+#[test]
+fn match_map_pairs_packages_across_renames() {
+    let run = |extra: &[&str]| {
+        sbom_diff()
+            .arg(fixture("fork-old.json"))
+            .arg(fixture("fork-new.json"))
+            .args(["--output", "json", "--fail-on", "added-components"])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    // unmapped, the fork and the moved group read as new packages
+    assert_eq!(run(&[]).status.code(), Some(3));
+
+    let map = fixture("match-map.txt");
+    let out = run(&["--match-map", map.to_str().unwrap()]);
+    assert_eq!(out.status.code(), Some(0));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["added"].as_array().unwrap().len(), 0);
+    assert_eq!(v["removed"].as_array().unwrap().len(), 0);
+    let changed = v["changed"].as_array().unwrap();
+    assert_eq!(changed.len(), 2);
+    for change in changed {
+        assert_eq!(change["match_reason"], "user-mapping", "got: {}", change);
+        assert!(change["changes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|c| c.get("Purl").is_some()));
+    }
+
+    let out = sbom_diff()
+        .arg(fixture("fork-old.json"))
+        .arg(fixture("fork-new.json"))
+        .args(["--match-map", map.to_str().unwrap()])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("pkg:npm/%40cypress/request@3.0.1\n  (matched by user mapping)\n"));
+}
+
+#[test]
+fn invalid_match_map_is_an_error() {
+    let out = sbom_diff()
+        .arg(fixture("fork-old.json"))
+        .arg(fixture("fork-new.json"))
+        .arg("--match-map")
+        .arg(fixture("fork-old.json"))
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("invalid match map"), "stderr: {stderr}");
+    assert!(stderr.contains("line 1"), "stderr: {stderr}");
+}
+
+#[test]
+fn explain_matching_prints_pairing_decisions_to_stderr() {
+    let map = fixture("match-map.txt");
+    let out = sbom_diff()
+        .arg(fixture("fork-old.json"))
+        .arg(fixture("fork-new.json"))
+        .args(["--output", "json", "--match-map", map.to_str().unwrap()])
+        .arg("--explain-matching")
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(0));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        stderr.contains("match: 1 components paired by id\n"),
+        "stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains(
+            "match: pkg:npm/request@2.88.2 -> pkg:npm/%40cypress/request@3.0.1 (matched by user mapping)\n"
+        ),
+        "stderr: {stderr}"
+    );
+
+    // json carries the decisions too, and stdout stays valid json
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["match_decisions"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn identical_content_hash_pairs_a_renamed_purl_less_component() {
+    let out = sbom_diff()
+        .arg(fixture("vendored-old.json"))
+        .arg(fixture("vendored-new.json"))
+        .args(["--output", "json"])
+        .output()
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let changed = v["changed"].as_array().unwrap();
+    assert_eq!(changed.len(), 1, "got: {}", v["changed"]);
+    assert_eq!(changed[0]["match_reason"], "content-hash");
+    assert_eq!(
+        changed[0]["changes"],
+        serde_json::json!([{"Name": ["third_party/miniz", "miniz"]}])
+    );
+    // a different digest is a different artifact, whatever the name
+    assert_eq!(v["added"][0]["name"], "u-boot");
+    assert_eq!(v["removed"][0]["name"], "bootloader");
+}
+
+#[test]
+fn renamed_vendored_component_pairs_by_its_place_in_the_graph() {
+    let run = |extra: &[&str]| {
+        let out = sbom_diff()
+            .arg(fixture("graph-old.json"))
+            .arg(fixture("graph-new.json"))
+            .args(["--output", "json"])
+            .args(extra)
+            .output()
+            .unwrap();
+        serde_json::from_slice::<serde_json::Value>(&out.stdout).unwrap()
+    };
+
+    let v = run(&[]);
+    let changed = v["changed"].as_array().unwrap();
+    assert_eq!(changed.len(), 1, "got: {}", v["changed"]);
+    assert_eq!(changed[0]["match_reason"], "graph");
+    assert_eq!(changed[0]["match_confidence"], 100);
+    assert_eq!(changed[0]["new"]["name"], "vendor/zlib-ng");
+    assert!(v["edge_diffs"].as_array().unwrap().is_empty());
+
+    let out = sbom_diff()
+        .arg(fixture("graph-old.json"))
+        .arg(fixture("graph-new.json"))
+        .args(["--graph-threshold", "0"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+}
+
+#[test]
+fn fail_on_hash_mismatch_flags_a_republished_version() {
+    let run = |fail_on: &str| {
+        sbom_diff()
+            .arg(fixture("republished-old.json"))
+            .arg(fixture("republished-new.json"))
+            .args(["--fail-on", fail_on])
+            .output()
+            .unwrap()
+    };
+
+    let out = run("hash-mismatch");
+    assert_eq!(out.status.code(), Some(3));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        stderr.contains(
+            "hash mismatch on component pkg:npm/event-stream@3.3.6 at unchanged version 3.3.6: SHA-512 differs"
+        ),
+        "stderr: {stderr}"
+    );
+    // through was bumped: its new digest is expected
+    assert!(!stderr.contains("through"), "stderr: {stderr}");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("Hash mismatches:  1 (same version, other digest)"));
+
+    // a digest change at the same strength is no algorithm downgrade
+    assert_eq!(run("hash-algorithm-downgrade").status.code(), Some(0));
+}
+
+#[test]
+fn ignore_file_drops_noisy_components_before_rendering_and_gating() {
+    let run = |dir: Option<&std::path::Path>, extra: &[&str]| {
+        let mut cmd = sbom_diff();
+        cmd.arg(fixture("ignore-old.json"))
+            .arg(fixture("ignore-new.json"))
+            .args(["--output", "json", "--fail-on", "added-components"])
+            .args(extra);
+        if let Some(dir) = dir {
+            cmd.current_dir(dir);
+        }
+        cmd.output().unwrap()
+    };
+    let json = |out: &std::process::Output| -> serde_json::Value {
+        serde_json::from_slice(&out.stdout).unwrap()
+    };
+
+    let out = run(None, &[]);
+    assert_eq!(out.status.code(), Some(3));
+    assert_eq!(json(&out)["changed"].as_array().unwrap().len(), 3);
+
+    let ignore = fixture("ignore.txt");
+    let out = run(None, &["--ignore-file", ignore.to_str().unwrap()]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v = json(&out);
+    let changed = v["changed"].as_array().unwrap();
+    assert_eq!(changed.len(), 1, "got: {}", v["changed"]);
+    assert_eq!(changed[0]["new"]["name"], "lodash");
+    assert!(v["added"].as_array().unwrap().is_empty());
+    // the firmware's only changes are ignored: it counts as unchanged
+    assert_eq!(
+        (v["old_total"].as_u64(), v["new_total"].as_u64()),
+        (Some(2), Some(2))
+    );
+    assert_eq!(v["unchanged"], 1);
+
+    // a `.sbom-diff-ignore` in the working directory is picked up
+    let dir = std::env::temp_dir().join("sbom-diff-cli-test-ignore-file");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(&ignore, dir.join(".sbom-diff-ignore")).unwrap();
+    let out = run(Some(&dir), &[]);
+    assert!(out.status.success());
+    assert_eq!(json(&out)["changed"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn ignored_components_leave_the_cycle_gate() {
+    let dir = std::env::temp_dir().join("sbom-diff-cli-test-ignored-cycle");
+    std::fs::create_dir_all(&dir).unwrap();
+    let run = |ignore: &str| {
+        let path = dir.join(format!("{}.txt", ignore.len()));
+        std::fs::write(&path, ignore).unwrap();
+        sbom_diff()
+            .arg(fixture("cyclic-dep-old.json"))
+            .arg(fixture("cyclic-dep-new.json"))
+            .args(["--fail-on", "cyclic-dependency", "--ignore-file"])
+            .arg(&path)
+            .output()
+            .unwrap()
+    };
+
+    // lib-c closes the cycle: with it ignored there is none
+    assert_eq!(run("pkg:npm/lib-c\n").status.code(), Some(0));
+    // a field-scoped rule keeps the component, and its edges
+    assert_eq!(run("pkg:npm/lib-c description\n").status.code(), Some(3));
+
+    // the ecosystem filters narrow the report, not the cycle gate
+    let out = sbom_diff()
+        .arg(fixture("cyclic-dep-old.json"))
+        .arg(fixture("cyclic-dep-new.json"))
+        .args([
+            "--fail-on",
+            "cyclic-dependency",
+            "--exclude-ecosystem",
+            "npm",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(3));
+}
+
+#[test]
+fn invalid_ignore_file_is_an_error() {
+    let dir = std::env::temp_dir().join("sbom-diff-cli-test-invalid-ignore-file");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("ignore.txt");
+    std::fs::write(&path, "pkg:generic/* description,checksum\n").unwrap();
+    let out = sbom_diff()
+        .arg(fixture("ignore-old.json"))
+        .arg(fixture("ignore-new.json"))
+        .arg("--ignore-file")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("invalid ignore file"), "{stderr}");
+    assert!(
+        stderr.contains("line 1: unknown field `checksum`"),
+        "{stderr}"
+    );
+}
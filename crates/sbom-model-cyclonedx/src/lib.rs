@@ -4,10 +4,13 @@ use sbom_model::{
     canonical_algorithm_name, parse_license_expression, Component, ComponentId, DependencyKind,
     Sbom,
 };
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet};
 use std::io::Read;
 use thiserror::Error;
 
+mod stream;
+
 /// errors that can occur when parsing CycloneDX documents.
 #[derive(Error, Debug)]
 pub enum Error {
@@ -37,6 +40,26 @@ pub enum Error {
 /// the library gains support for newer spec revisions.
 const SUPPORTED_SPEC_VERSIONS: &[&str] = &["1.3", "1.4", "1.5"];
 
+/// component properties that state a dependency's kind, as
+/// (name, value, kind), from the CycloneDX property taxonomy and cdxgen.
+const KIND_PROPERTIES: &[(&str, &str, DependencyKind)] = &[
+    ("cdx:npm:package:development", "true", DependencyKind::Dev),
+    ("cdx:maven:component_scope", "test", DependencyKind::Test),
+    (
+        "cdx:maven:component_scope",
+        "provided",
+        DependencyKind::Provided,
+    ),
+    (
+        "cdx:maven:component_scope",
+        "system",
+        DependencyKind::Provided,
+    ),
+];
+
+/// the license entry field that separates declared from concluded licenses.
+const ACKNOWLEDGEMENT: &[u8] = b"\"acknowledgement\"";
+
 /// maximum nesting depth for recursive sub-component collection.
 ///
 /// real-world SBOMs rarely nest more than a handful of levels. A limit
@@ -80,7 +103,46 @@ impl CycloneDxReader {
         Self::check_cyclonedx_version(buf)?;
 
         let bom = cyclonedx_bom::prelude::Bom::parse_from_json(buf)?;
-        Self::bom_to_sbom(bom)
+        let mut sbom = Self::bom_to_sbom(bom)?;
+        // cyclonedx-bom drops license `acknowledgement`; recover it only
+        // when the document uses it.
+        if buf
+            .windows(ACKNOWLEDGEMENT.len())
+            .any(|w| w == ACKNOWLEDGEMENT)
+        {
+            stream::apply_license_acknowledgements(buf, &mut sbom)?;
+        }
+        Ok(sbom)
+    }
+
+    /// parses a CycloneDX JSON document from a reader without building the
+    /// intermediate document model.
+    ///
+    /// produces the same [`Sbom`] as [`read_json`](Self::read_json), but maps
+    /// each component and dependency entry as it is read, so peak memory is
+    /// the resulting `Sbom` plus one top-level component rather than several
+    /// copies of the whole document. Use it for very large SBOMs.
+    ///
+    /// differences from `read_json`:
+    ///
+    /// - fields the adapter does not map are skipped without being checked
+    ///   against the schema, so some malformed documents parse here and
+    ///   fail there;
+    /// - components are inserted in document order, so in the rare document
+    ///   that lists `components` before `metadata`, the root component comes
+    ///   after them (and wins over a duplicate id instead of losing).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sbom_model_cyclonedx::CycloneDxReader;
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("sbom.cdx.json").unwrap();
+    /// let sbom = CycloneDxReader::read_json_streaming(file).unwrap();
+    /// ```
+    pub fn read_json_streaming<R: Read>(reader: R) -> Result<Sbom, Error> {
+        stream::read(reader)
     }
 
     /// parses a CycloneDX XML document from a byte slice.
@@ -187,23 +249,41 @@ impl CycloneDxReader {
         }
     }
 
-    /// maps a CycloneDX component scope to a [`DependencyKind`].
+    /// maps a CycloneDX component's scope and properties to the
+    /// [`DependencyKind`] of the edges pointing at it.
+    ///
+    /// a well-known property from [`KIND_PROPERTIES`] wins, since it says
+    /// more than the scope does (cdxgen, for one, marks npm dev dependencies
+    /// `optional` as well as `cdx:npm:package:development`). otherwise:
     ///
-    /// - `Required` (or absent) → `Runtime`
-    /// - `Optional` → `Optional`
-    /// - `Excluded` / unknown → `Runtime`
-    fn scope_to_dep_kind(
-        scope: Option<&cyclonedx_bom::models::component::Scope>,
-    ) -> DependencyKind {
-        use cyclonedx_bom::models::component::Scope;
+    /// - `required` (or absent) → `Runtime`
+    /// - `optional` → `Optional`
+    /// - `excluded` → `Dev`: not part of the runtime environment, the
+    ///   closest kind to which is a development dependency
+    /// - unknown → `Runtime`
+    fn dependency_kind(scope: Option<&str>, properties: &[(&str, &str)]) -> DependencyKind {
+        let stated = KIND_PROPERTIES.iter().find_map(|(name, value, kind)| {
+            properties
+                .iter()
+                .any(|(n, v)| n == name && v.trim().eq_ignore_ascii_case(value))
+                .then_some(*kind)
+        });
+        if let Some(kind) = stated {
+            return kind;
+        }
         match scope {
-            Some(Scope::Optional) => DependencyKind::Optional,
+            Some("optional") => DependencyKind::Optional,
+            Some("excluded") => DependencyKind::Dev,
             _ => DependencyKind::Runtime,
         }
     }
 
     fn bom_to_sbom(bom: cyclonedx_bom::prelude::Bom) -> Result<Sbom, Error> {
         let mut sbom = Sbom::default();
+        sbom.metadata.format = Some("cyclonedx".to_string());
+        sbom.metadata.spec_version = Some(bom.spec_version.to_string());
+        sbom.metadata.serial_number = bom.serial_number.map(|s| s.to_string());
+        sbom.metadata.bom_version = Some(bom.version);
 
         // collect bom-ref → DependencyKind derived from each component's scope.
         let mut scope_map = BTreeMap::new();
@@ -216,112 +296,163 @@ impl CycloneDxReader {
                 match tools {
                     cyclonedx_bom::models::tool::Tools::List(list) => {
                         for tool in list {
-                            let mut s = String::new();
-                            if let Some(v) = &tool.vendor {
-                                s.push_str(v.as_ref());
-                                s.push(' ');
-                            }
-                            if let Some(n) = &tool.name {
-                                s.push_str(n.as_ref());
-                            }
-                            if let Some(v) = &tool.version {
-                                s.push(' ');
-                                s.push_str(v.as_ref());
-                            }
-                            sbom.metadata.tools.push(s.trim().to_string());
+                            sbom.metadata.tools.push(tool_label(
+                                tool.vendor.as_deref(),
+                                tool.name.as_deref(),
+                                tool.version.as_deref(),
+                            ));
                         }
                     }
                     cyclonedx_bom::models::tool::Tools::Object { components, .. } => {
                         for component in components.into_iter().flat_map(|c| c.0) {
-                            let mut s = component.name.to_string();
-                            if let Some(v) = &component.version {
-                                s.push(' ');
-                                s.push_str(v.as_ref());
-                            }
-                            sbom.metadata.tools.push(s);
+                            sbom.metadata.tools.push(tool_label(
+                                None,
+                                Some(&component.name),
+                                component.version.as_deref(),
+                            ));
                         }
                     }
                 }
             }
             if let Some(authors) = meta.authors {
                 for author in authors {
-                    let mut s = String::new();
-                    if let Some(n) = &author.name {
-                        s.push_str(n.as_ref());
-                    }
-                    if let Some(e) = &author.email {
-                        s.push_str(" <");
-                        s.push_str(e.as_ref());
-                        s.push('>');
-                    }
-                    sbom.metadata.authors.push(s.trim().to_string());
+                    sbom.metadata.authors.push(author_label(
+                        author.name.as_deref(),
+                        author.email.as_deref(),
+                    ));
                 }
             }
             // the BOM's primary/root component (its subject: app, image, or container).
             if let Some(root) = meta.component {
-                Self::collect_components(std::slice::from_ref(&root), &mut sbom, &mut scope_map, 0);
+                sbom.metadata.primary_component = Some(Self::component_id(&root));
+                Self::collect_components(
+                    std::slice::from_ref(&root),
+                    &mut sbom,
+                    &mut scope_map,
+                    None,
+                    0,
+                );
             }
         }
 
         if let Some(components) = bom.components {
-            Self::collect_components(&components.0, &mut sbom, &mut scope_map, 0);
+            Self::collect_components(&components.0, &mut sbom, &mut scope_map, None, 0);
+        }
+
+        let ref_map = Self::ref_map(&sbom);
+
+        if let Some(dependencies) = bom.dependencies {
+            for dep in dependencies.0 {
+                Self::add_dependency(
+                    &mut sbom,
+                    &ref_map,
+                    &scope_map,
+                    &dep.dependency_ref,
+                    &dep.dependencies,
+                );
+            }
         }
 
-        // CDX uses bom-refs for the dependency graph; map them to our ComponentIds.
+        sbom.rebuild_reverse_deps();
+        Ok(sbom)
+    }
+
+    /// CDX uses bom-refs for the dependency graph; maps them to our ComponentIds.
+    fn ref_map(sbom: &Sbom) -> BTreeMap<String, ComponentId> {
         let mut ref_map = BTreeMap::new();
         for (id, comp) in &sbom.components {
             for src_id in &comp.source_ids {
                 ref_map.insert(src_id.clone(), id.clone());
             }
         }
+        ref_map
+    }
 
-        if let Some(dependencies) = bom.dependencies {
-            for dep in dependencies.0 {
-                let parent_ref = dep.dependency_ref;
-                if let Some(parent_id) = ref_map.get(&parent_ref.to_string()) {
-                    let mut children = BTreeMap::new();
-                    for child_ref in dep.dependencies {
-                        if let Some(child_id) = ref_map.get(&child_ref.to_string()) {
-                            let kind = scope_map
-                                .get(&child_ref.to_string())
-                                .copied()
-                                .unwrap_or(DependencyKind::Runtime);
-                            children.insert(child_id.clone(), kind);
-                        } else {
-                            sbom.warnings.push(format!(
-                                "CycloneDX: dependency bom-ref '{}' (child of '{}') does not match any component",
-                                child_ref, parent_ref
-                            ));
-                        }
-                    }
-                    if !children.is_empty() {
-                        sbom.dependencies.insert(parent_id.clone(), children);
-                    }
+    /// maps one `dependencies` entry onto `sbom.dependencies`, warning about
+    /// bom-refs that name no component.
+    fn add_dependency(
+        sbom: &mut Sbom,
+        ref_map: &BTreeMap<String, ComponentId>,
+        scope_map: &BTreeMap<String, DependencyKind>,
+        parent_ref: &str,
+        child_refs: &[String],
+    ) {
+        if let Some(parent_id) = ref_map.get(parent_ref) {
+            let mut children = BTreeMap::new();
+            for child_ref in child_refs {
+                if let Some(child_id) = ref_map.get(child_ref) {
+                    let kind = scope_map
+                        .get(child_ref)
+                        .copied()
+                        .unwrap_or(DependencyKind::Runtime);
+                    children.insert(child_id.clone(), kind);
                 } else {
                     sbom.warnings.push(format!(
-                        "CycloneDX: dependency bom-ref '{}' does not match any component",
-                        parent_ref
+                        "CycloneDX: dependency bom-ref '{}' (child of '{}') does not match any component",
+                        child_ref, parent_ref
                     ));
                 }
             }
+            if !children.is_empty() {
+                // merged, so edges recorded from component nesting survive;
+                // a restated nesting edge takes the dependency's kind.
+                sbom.dependencies
+                    .entry(parent_id.clone())
+                    .or_default()
+                    .extend(children);
+            }
+        } else {
+            sbom.warnings.push(format!(
+                "CycloneDX: dependency bom-ref '{}' does not match any component",
+                parent_ref
+            ));
         }
+    }
 
-        sbom.rebuild_reverse_deps();
-        Ok(sbom)
+    fn supplier<C: ComponentSource>(cdx_comp: &C) -> Option<&str> {
+        cdx_comp.supplier_name().filter(|s| !s.is_empty())
+    }
+
+    /// the id a component maps to: its purl, or a hash of name, version
+    /// and supplier.
+    fn component_id<C: ComponentSource>(cdx_comp: &C) -> ComponentId {
+        let mut props = vec![("name", cdx_comp.name())];
+        if let Some(version) = cdx_comp.version() {
+            props.push(("version", version));
+        }
+        if let Some(supplier) = Self::supplier(cdx_comp) {
+            props.push(("supplier", supplier));
+        }
+        ComponentId::new(cdx_comp.purl(), &props)
+    }
+
+    /// sets the license fields of `comp`. entries acknowledged as declared
+    /// go to the declared fields; the rest are the concluded licenses, which
+    /// fall back to the declared ones when there are none.
+    fn map_licenses<C: ComponentSource>(cdx_comp: &C, comp: &mut Component) {
+        let (declared, declared_expression) = license_fields(cdx_comp.declared_licenses());
+        let concluded = cdx_comp.licenses();
+        (comp.licenses, comp.license_expression) = if concluded.is_empty() {
+            (declared.clone(), declared_expression.clone())
+        } else {
+            license_fields(concluded)
+        };
+        comp.declared_licenses = declared;
+        comp.declared_license_expression = declared_expression;
     }
 
-    fn collect_components(
-        cdx_components: &[cyclonedx_bom::models::component::Component],
+    /// adds `cdx_components` and their nested sub-components to `sbom`,
+    /// recording each nesting level as a [`DependencyKind::Contains`] edge
+    /// from `parent`.
+    fn collect_components<C: ComponentSource>(
+        cdx_components: &[C],
         sbom: &mut Sbom,
         scope_map: &mut BTreeMap<String, DependencyKind>,
+        parent: Option<&ComponentId>,
         depth: usize,
     ) {
         if depth >= MAX_COMPONENT_DEPTH {
-            let names: Vec<_> = cdx_components
-                .iter()
-                .take(3)
-                .map(|c| c.name.to_string())
-                .collect();
+            let names: Vec<_> = cdx_components.iter().take(3).map(|c| c.name()).collect();
             let suffix = if cdx_components.len() > 3 {
                 format!(" and {} more", cdx_components.len() - 3)
             } else {
@@ -340,88 +471,62 @@ impl CycloneDxReader {
         }
 
         for cdx_comp in cdx_components {
-            let name = cdx_comp.name.to_string();
-            let version = cdx_comp.version.as_ref().map(|v| v.to_string());
-
-            let mut props = vec![("name", name.as_str())];
-            let v_str = version.clone().unwrap_or_default();
-            if version.is_some() {
-                props.push(("version", v_str.as_str()));
-            }
-
-            let supplier = cdx_comp
-                .supplier
-                .as_ref()
-                .and_then(|s| s.name.as_ref().map(|n| n.to_string()))
-                .filter(|s| !s.is_empty());
-            let s_str = supplier.clone().unwrap_or_default();
-            if supplier.is_some() {
-                props.push(("supplier", s_str.as_str()));
-            }
-
-            let purl = cdx_comp.purl.as_ref().map(|p| p.to_string());
-            let purl_str = purl.as_deref();
-
-            let ecosystem = purl_str.and_then(sbom_model::ecosystem_from_purl);
-
-            let id = ComponentId::new(purl_str, &props);
+            let id = Self::component_id(cdx_comp);
+            let purl = cdx_comp.purl().map(str::to_string);
 
             let mut comp = Component {
                 id: id.clone(),
-                name,
-                version,
-                ecosystem,
-                supplier,
-                description: cdx_comp.description.as_ref().map(|d| d.to_string()),
+                name: cdx_comp.name().to_string(),
+                version: cdx_comp.version().map(str::to_string),
+                ecosystem: purl.as_deref().and_then(sbom_model::ecosystem_from_purl),
+                kind: cdx_comp.kind().map(Cow::into_owned),
+                supplier: Self::supplier(cdx_comp).map(str::to_string),
+                copyright: copyright_text(cdx_comp.copyright()),
+                description: cdx_comp.description().map(str::to_string),
                 purl,
+                cpes: cdx_comp
+                    .cpe()
+                    .map(str::trim)
+                    .filter(|cpe| !cpe.is_empty())
+                    .map(str::to_string)
+                    .into_iter()
+                    .collect(),
+                external_refs: BTreeMap::new(),
                 licenses: BTreeSet::new(),
                 license_expression: None,
+                declared_licenses: BTreeSet::new(),
+                declared_license_expression: None,
                 hashes: BTreeMap::new(),
+                properties: BTreeMap::new(),
                 source_ids: Vec::new(),
             };
 
-            if let Some(bom_ref) = &cdx_comp.bom_ref {
+            if let Some(bom_ref) = cdx_comp.bom_ref() {
                 comp.source_ids.push(bom_ref.to_string());
-                scope_map.insert(
-                    bom_ref.to_string(),
-                    Self::scope_to_dep_kind(cdx_comp.scope.as_ref()),
-                );
+                scope_map.insert(bom_ref.to_string(), cdx_comp.dependency_kind());
             }
 
-            if let Some(licenses) = &cdx_comp.licenses {
-                if let [cyclonedx_bom::models::license::LicenseChoice::Expression(e)] =
-                    licenses.0.as_slice()
-                {
-                    comp.license_expression = Some(e.to_string());
-                }
-                for license_choice in &licenses.0 {
-                    match license_choice {
-                        cyclonedx_bom::models::license::LicenseChoice::License(l) => {
-                            let s = match &l.license_identifier {
-                                cyclonedx_bom::models::license::LicenseIdentifier::Name(n) => {
-                                    n.to_string()
-                                }
-                                cyclonedx_bom::models::license::LicenseIdentifier::SpdxId(id) => {
-                                    id.to_string()
-                                }
-                            };
-                            comp.licenses.insert(s);
-                        }
-                        cyclonedx_bom::models::license::LicenseChoice::Expression(e) => {
-                            comp.licenses
-                                .extend(parse_license_expression(&e.to_string()));
-                        }
-                    }
+            Self::map_licenses(cdx_comp, &mut comp);
+
+            for (ref_type, url) in cdx_comp.external_refs() {
+                if !url.trim().is_empty() {
+                    comp.external_refs
+                        .entry(ref_type.into_owned())
+                        .or_default()
+                        .insert(url.trim().to_string());
                 }
             }
 
-            if let Some(hashes) = &cdx_comp.hashes {
-                for h in &hashes.0 {
-                    comp.hashes.insert(
-                        canonical_algorithm_name(&h.alg.to_string()),
-                        h.content.0.clone(),
-                    );
-                }
+            for (name, value) in cdx_comp.properties() {
+                comp.properties
+                    .entry(name.to_string())
+                    .or_default()
+                    .insert(value.to_string());
+            }
+
+            for (alg, content) in cdx_comp.hashes() {
+                comp.hashes
+                    .insert(canonical_algorithm_name(&alg), content.to_string());
             }
 
             if let Some(existing) = sbom.components.get(&id) {
@@ -431,13 +536,234 @@ impl CycloneDxReader {
                     id, comp.name, existing.name,
                 ));
             }
-            sbom.components.insert(id, comp);
+            sbom.components.insert(id.clone(), comp);
+
+            if let Some(parent) = parent.filter(|p| **p != id) {
+                sbom.dependencies
+                    .entry(parent.clone())
+                    .or_default()
+                    .insert(id.clone(), DependencyKind::Contains);
+            }
+
+            if let Some(sub) = cdx_comp.children() {
+                Self::collect_components(sub, sbom, scope_map, Some(&id), depth + 1);
+            }
+        }
+    }
+}
+
+/// formats a `metadata.tools` entry as `vendor name version`.
+fn tool_label(vendor: Option<&str>, name: Option<&str>, version: Option<&str>) -> String {
+    let mut s = String::new();
+    if let Some(v) = vendor {
+        s.push_str(v);
+        s.push(' ');
+    }
+    if let Some(n) = name {
+        s.push_str(n);
+    }
+    if let Some(v) = version {
+        s.push(' ');
+        s.push_str(v);
+    }
+    s.trim().to_string()
+}
+
+/// formats a `metadata.authors` entry as `name <email>`.
+fn author_label(name: Option<&str>, email: Option<&str>) -> String {
+    let mut s = String::new();
+    if let Some(n) = name {
+        s.push_str(n);
+    }
+    if let Some(e) = email {
+        s.push_str(" <");
+        s.push_str(e);
+        s.push('>');
+    }
+    s.trim().to_string()
+}
 
-            if let Some(sub) = &cdx_comp.components {
-                Self::collect_components(&sub.0, sbom, scope_map, depth + 1);
+/// a copyright notice with surrounding whitespace trimmed; blank counts as absent.
+fn copyright_text(text: Option<&str>) -> Option<String> {
+    text.map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+}
+
+/// flattens license entries into an identifier set, plus the expression when
+/// the entries are a single one.
+fn license_fields(entries: Vec<LicenseEntry<'_>>) -> (BTreeSet<String>, Option<String>) {
+    let expression = match entries.as_slice() {
+        [LicenseEntry::Expression(e)] => Some(e.to_string()),
+        _ => None,
+    };
+    let mut ids = BTreeSet::new();
+    for license in entries {
+        match license {
+            LicenseEntry::Identifier(l) => {
+                ids.insert(l.into_owned());
+            }
+            LicenseEntry::Expression(e) => {
+                ids.extend(parse_license_expression(&e));
             }
         }
     }
+    (ids, expression)
+}
+
+/// a license entry of a component, with the id/name distinction dropped.
+enum LicenseEntry<'a> {
+    /// a `license` entry's SPDX id or free-text name.
+    Identifier(Cow<'a, str>),
+    /// an SPDX license expression.
+    Expression(Cow<'a, str>),
+}
+
+/// the component fields the adapter maps, read either from the
+/// `cyclonedx-bom` model or from the streaming reader's own component type,
+/// so both paths share one mapping.
+trait ComponentSource: Sized {
+    fn name(&self) -> &str;
+    fn version(&self) -> Option<&str>;
+    fn kind(&self) -> Option<Cow<'_, str>>;
+    fn supplier_name(&self) -> Option<&str>;
+    fn purl(&self) -> Option<&str>;
+    fn cpe(&self) -> Option<&str>;
+    fn description(&self) -> Option<&str>;
+    fn copyright(&self) -> Option<&str>;
+    /// `externalReferences` as (type, url).
+    fn external_refs(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)>;
+    fn bom_ref(&self) -> Option<&str>;
+    /// `scope`, lowercase as spelled in the schema.
+    fn scope(&self) -> Option<Cow<'_, str>>;
+    /// license entries not acknowledged as declared.
+    fn licenses(&self) -> Vec<LicenseEntry<'_>>;
+    /// license entries with `acknowledgement: declared`.
+    fn declared_licenses(&self) -> Vec<LicenseEntry<'_>>;
+    fn hashes(&self) -> Vec<(Cow<'_, str>, &str)>;
+    /// `properties` as (name, value).
+    fn properties(&self) -> Vec<(&str, &str)>;
+    fn children(&self) -> Option<&[Self]>;
+
+    fn dependency_kind(&self) -> DependencyKind {
+        CycloneDxReader::dependency_kind(self.scope().as_deref(), &self.properties())
+    }
+}
+
+impl ComponentSource for cyclonedx_bom::models::component::Component {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    fn kind(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Owned(self.component_type.to_string()))
+    }
+
+    fn supplier_name(&self) -> Option<&str> {
+        self.supplier.as_ref().and_then(|s| s.name.as_deref())
+    }
+
+    fn purl(&self) -> Option<&str> {
+        self.purl.as_ref().map(AsRef::as_ref)
+    }
+
+    fn cpe(&self) -> Option<&str> {
+        self.cpe.as_ref().map(AsRef::as_ref)
+    }
+
+    fn copyright(&self) -> Option<&str> {
+        self.copyright.as_ref().map(AsRef::as_ref)
+    }
+
+    fn external_refs(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)> {
+        use cyclonedx_bom::models::external_reference::ExternalReferenceType;
+        let Some(refs) = &self.external_references else {
+            return Vec::new();
+        };
+        refs.0
+            .iter()
+            .map(|r| {
+                let ref_type = match &r.external_reference_type {
+                    // cyclonedx-bom misspells the variant, and with it the name.
+                    ExternalReferenceType::CondifiedInfrastructure => {
+                        Cow::Borrowed("codified-infrastructure")
+                    }
+                    other => Cow::Owned(other.to_string()),
+                };
+                (ref_type, Cow::Owned(r.url.to_string()))
+            })
+            .collect()
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn bom_ref(&self) -> Option<&str> {
+        self.bom_ref.as_deref()
+    }
+
+    fn scope(&self) -> Option<Cow<'_, str>> {
+        self.scope
+            .as_ref()
+            .map(|scope| Cow::Owned(scope.to_string()))
+    }
+
+    fn licenses(&self) -> Vec<LicenseEntry<'_>> {
+        use cyclonedx_bom::models::license::{LicenseChoice, LicenseIdentifier};
+        let Some(licenses) = &self.licenses else {
+            return Vec::new();
+        };
+        licenses
+            .0
+            .iter()
+            .map(|choice| match choice {
+                LicenseChoice::License(l) => match &l.license_identifier {
+                    LicenseIdentifier::Name(n) => LicenseEntry::Identifier(Cow::Borrowed(n)),
+                    LicenseIdentifier::SpdxId(id) => {
+                        LicenseEntry::Identifier(Cow::Owned(id.to_string()))
+                    }
+                },
+                LicenseChoice::Expression(e) => LicenseEntry::Expression(Cow::Owned(e.to_string())),
+            })
+            .collect()
+    }
+
+    fn declared_licenses(&self) -> Vec<LicenseEntry<'_>> {
+        // the model has no `acknowledgement`; `read_json` recovers it from
+        // the document afterwards.
+        Vec::new()
+    }
+
+    fn hashes(&self) -> Vec<(Cow<'_, str>, &str)> {
+        let Some(hashes) = &self.hashes else {
+            return Vec::new();
+        };
+        hashes
+            .0
+            .iter()
+            .map(|h| (Cow::Owned(h.alg.to_string()), h.content.0.as_str()))
+            .collect()
+    }
+
+    fn properties(&self) -> Vec<(&str, &str)> {
+        let Some(properties) = &self.properties else {
+            return Vec::new();
+        };
+        properties
+            .0
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_ref()))
+            .collect()
+    }
+
+    fn children(&self) -> Option<&[Self]> {
+        self.components.as_ref().map(|c| c.0.as_slice())
+    }
 }
 
 #[cfg(test)]
@@ -778,7 +1104,7 @@ mod tests {
     }
 
     #[test]
-    fn test_excluded_scope_defaults_to_runtime() {
+    fn test_excluded_scope_is_dev() {
         let json = r#"{
             "bomFormat": "CycloneDX",
             "specVersion": "1.4",
@@ -822,11 +1148,77 @@ mod tests {
             .id
             .clone();
 
-        // excluded maps to Runtime, see `scope_to_dep_kind`
-        assert_eq!(
-            sbom.dependencies[&app_id][&excl_id],
-            DependencyKind::Runtime
-        );
+        // excluded components are not in the runtime environment
+        assert_eq!(sbom.dependencies[&app_id][&excl_id], DependencyKind::Dev);
+    }
+
+    #[test]
+    fn test_kind_properties_set_dependency_kind() {
+        let json = r#"{
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "components": [
+                { "type": "application", "name": "app", "bom-ref": "app" },
+                {
+                    "type": "library",
+                    "name": "mocha",
+                    "bom-ref": "mocha",
+                    "scope": "optional",
+                    "properties": [
+                        { "name": "cdx:npm:package:development", "value": "true" }
+                    ]
+                },
+                {
+                    "type": "library",
+                    "name": "express",
+                    "bom-ref": "express",
+                    "properties": [
+                        { "name": "cdx:npm:package:development", "value": "false" }
+                    ]
+                },
+                {
+                    "type": "library",
+                    "name": "junit",
+                    "bom-ref": "junit",
+                    "properties": [
+                        { "name": "cdx:maven:component_scope", "value": "test" }
+                    ]
+                },
+                {
+                    "type": "library",
+                    "name": "servlet-api",
+                    "bom-ref": "servlet-api",
+                    "properties": [
+                        { "name": "cdx:maven:component_scope", "value": "provided" }
+                    ]
+                }
+            ],
+            "dependencies": [
+                {
+                    "ref": "app",
+                    "dependsOn": ["mocha", "express", "junit", "servlet-api"]
+                }
+            ]
+        }"#;
+        let sbom = CycloneDxReader::read_json(json.as_bytes()).unwrap();
+        let id = |name: &str| {
+            sbom.components
+                .values()
+                .find(|c| c.name == name)
+                .unwrap()
+                .id
+                .clone()
+        };
+        let deps = &sbom.dependencies[&id("app")];
+        // the property wins over the `optional` scope
+        assert_eq!(deps[&id("mocha")], DependencyKind::Dev);
+        assert_eq!(deps[&id("express")], DependencyKind::Runtime);
+        assert_eq!(deps[&id("junit")], DependencyKind::Test);
+        assert_eq!(deps[&id("servlet-api")], DependencyKind::Provided);
+
+        let streamed = CycloneDxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed, sbom);
     }
 
     #[test]
@@ -955,6 +1347,61 @@ mod tests {
         assert!(leaf.licenses.contains("MIT"));
     }
 
+    #[test]
+    fn test_nested_subcomponents_become_containment_edges() {
+        let json = r#"{
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "components": [
+                {
+                    "type": "container",
+                    "name": "root",
+                    "version": "1.0.0",
+                    "components": [
+                        {
+                            "type": "application",
+                            "name": "mid",
+                            "version": "2.0.0",
+                            "components": [
+                                {
+                                    "type": "library",
+                                    "name": "leaf",
+                                    "version": "3.0.0",
+                                    "purl": "pkg:npm/leaf@3.0.0"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }"#;
+        let sbom = CycloneDxReader::read_json(json.as_bytes()).unwrap();
+        let id = |name: &str| {
+            sbom.components
+                .values()
+                .find(|c| c.name == name)
+                .unwrap()
+                .id
+                .clone()
+        };
+
+        assert_eq!(sbom.dependencies.len(), 2);
+        assert_eq!(
+            sbom.dependencies[&id("root")],
+            BTreeMap::from([(id("mid"), DependencyKind::Contains)])
+        );
+        assert_eq!(
+            sbom.dependencies[&id("mid")],
+            BTreeMap::from([(id("leaf"), DependencyKind::Contains)])
+        );
+        assert_eq!(sbom.reverse_deps[&id("leaf")], BTreeSet::from([id("mid")]));
+        assert_eq!(sbom.roots(), vec![id("root")]);
+
+        let streamed = CycloneDxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed.dependencies, sbom.dependencies);
+    }
+
     #[test]
     fn test_nested_subcomponents_bom_refs_in_dependency_graph() {
         let json = r#"{
@@ -1019,8 +1466,16 @@ mod tests {
             .id
             .clone();
 
-        // image -> inner-a dependency
-        assert!(sbom.dependencies[&image_id].contains_key(&inner_a_id));
+        // image -> inner-a dependency, restating the nesting: the dependency wins
+        assert_eq!(
+            sbom.dependencies[&image_id][&inner_a_id],
+            DependencyKind::Runtime
+        );
+        // image -> inner-b only by nesting
+        assert_eq!(
+            sbom.dependencies[&image_id][&inner_b_id],
+            DependencyKind::Contains
+        );
         // inner-a -> inner-b dependency
         assert!(sbom.dependencies[&inner_a_id].contains_key(&inner_b_id));
     }
@@ -1472,6 +1927,41 @@ mod tests {
         assert_eq!(sbom.components[0].name, "my-app");
     }
 
+    #[test]
+    fn test_document_identity_parsed() {
+        let json = r#"{
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "serialNumber": "urn:uuid:3e671687-395b-41f5-a30f-a58921a69b79",
+            "version": 7,
+            "metadata": {
+                "component": {
+                    "type": "application",
+                    "name": "my-app",
+                    "version": "3.2.1",
+                    "purl": "pkg:cargo/my-app@3.2.1"
+                }
+            },
+            "components": []
+        }"#;
+        let sbom = CycloneDxReader::read_json(json.as_bytes()).unwrap();
+        let meta = &sbom.metadata;
+        assert_eq!(meta.format.as_deref(), Some("cyclonedx"));
+        assert_eq!(meta.spec_version.as_deref(), Some("1.5"));
+        assert_eq!(
+            meta.serial_number.as_deref(),
+            Some("urn:uuid:3e671687-395b-41f5-a30f-a58921a69b79")
+        );
+        assert_eq!(meta.bom_version, Some(7));
+        assert_eq!(
+            meta.primary_component.as_ref().map(ComponentId::as_str),
+            Some("pkg:cargo/my-app@3.2.1")
+        );
+
+        let streamed = CycloneDxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed, sbom);
+    }
+
     #[test]
     fn test_metadata_component_dependency_resolves() {
         let json = r#"{
@@ -1663,4 +2153,214 @@ mod tests {
         // per-license entries carry no expression, so the flat set stands alone
         assert_eq!(find("listed").license_expression, None);
     }
+
+    #[test]
+    fn test_license_acknowledgement_separates_declared() {
+        let json = r#"{
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "components": [
+                {
+                    "type": "library",
+                    "name": "both",
+                    "version": "1.0.0",
+                    "purl": "pkg:npm/both@1.0.0",
+                    "licenses": [
+                        {"expression": "MIT OR Apache-2.0", "acknowledgement": "declared"},
+                        {"license": {"id": "MIT", "acknowledgement": "concluded"}}
+                    ],
+                    "components": [
+                        {
+                            "type": "library",
+                            "name": "declared-only",
+                            "version": "2.0.0",
+                            "licenses": [
+                                {"license": {"id": "BSD-3-Clause", "acknowledgement": "declared"}}
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "library",
+                    "name": "unacknowledged",
+                    "version": "3.0.0",
+                    "licenses": [{"license": {"id": "ISC"}}]
+                }
+            ]
+        }"#;
+        let sbom = CycloneDxReader::read_json(json.as_bytes()).unwrap();
+        let find = |name: &str| sbom.components.values().find(|c| c.name == name).unwrap();
+
+        let both = find("both");
+        assert_eq!(both.licenses, BTreeSet::from(["MIT".into()]));
+        assert_eq!(
+            both.declared_licenses,
+            BTreeSet::from(["Apache-2.0".into(), "MIT".into()])
+        );
+        assert_eq!(
+            both.declared_license_expression.as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+
+        // nothing concluded: the declared license is used for both
+        let declared_only = find("declared-only");
+        assert_eq!(
+            declared_only.licenses,
+            BTreeSet::from(["BSD-3-Clause".into()])
+        );
+        assert_eq!(declared_only.declared_licenses, declared_only.licenses);
+
+        // without acknowledgement an entry counts as concluded
+        let plain = find("unacknowledged");
+        assert_eq!(plain.licenses, BTreeSet::from(["ISC".into()]));
+        assert!(plain.declared_licenses.is_empty());
+
+        let streamed = CycloneDxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed.components, sbom.components);
+    }
+
+    #[test]
+    fn test_copyright_parsed() {
+        let json = r#"{
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "components": [
+                {
+                    "type": "library",
+                    "name": "pkg-a",
+                    "version": "1.0.0",
+                    "copyright": "  Copyright 2016 Acme Corp  "
+                },
+                {
+                    "type": "library",
+                    "name": "pkg-b",
+                    "version": "1.0.0",
+                    "copyright": ""
+                }
+            ]
+        }"#;
+        let sbom = CycloneDxReader::read_json(json.as_bytes()).unwrap();
+        let find = |name: &str| sbom.components.values().find(|c| c.name == name).unwrap();
+        assert_eq!(
+            find("pkg-a").copyright.as_deref(),
+            Some("Copyright 2016 Acme Corp")
+        );
+        assert_eq!(find("pkg-b").copyright, None);
+
+        let streamed = CycloneDxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed.components, sbom.components);
+    }
+
+    #[test]
+    fn test_external_references_and_cpe_parsed() {
+        let json = r#"{
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "components": [
+                {
+                    "type": "library",
+                    "name": "pkg-a",
+                    "version": "1.0.0",
+                    "cpe": "cpe:2.3:a:acme:pkg-a:1.0.0:*:*:*:*:*:*:*",
+                    "externalReferences": [
+                        {"type": "vcs", "url": "https://github.com/acme/pkg-a.git"},
+                        {"type": "website", "url": "https://pkg-a.example"},
+                        {"type": "website", "url": "https://docs.pkg-a.example"},
+                        {"type": "distribution", "url": "  "}
+                    ]
+                }
+            ]
+        }"#;
+        let sbom = CycloneDxReader::read_json(json.as_bytes()).unwrap();
+        let comp = sbom.components.values().next().unwrap();
+        assert_eq!(
+            comp.external_refs["vcs"],
+            BTreeSet::from(["https://github.com/acme/pkg-a.git".to_string()])
+        );
+        assert_eq!(comp.external_refs["website"].len(), 2);
+        assert!(!comp.external_refs.contains_key("distribution"));
+        assert_eq!(
+            comp.cpes,
+            BTreeSet::from(["cpe:2.3:a:acme:pkg-a:1.0.0:*:*:*:*:*:*:*".to_string()])
+        );
+
+        let streamed = CycloneDxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed.components, sbom.components);
+    }
+
+    #[test]
+    fn test_properties_parsed() {
+        let json = r#"{
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "components": [
+                {
+                    "type": "library",
+                    "name": "pkg-a",
+                    "version": "1.0.0",
+                    "properties": [
+                        {"name": "syft:package:foundBy", "value": "javascript-lock-cataloger"},
+                        {"name": "syft:location:0:path", "value": "/app/package-lock.json"},
+                        {"name": "syft:location:0:path", "value": "/srv/package-lock.json"},
+                        {"name": "cdx:npm:package:development", "value": "true"}
+                    ]
+                }
+            ]
+        }"#;
+        let sbom = CycloneDxReader::read_json(json.as_bytes()).unwrap();
+        let comp = sbom.components.values().next().unwrap();
+        assert_eq!(comp.properties.len(), 3);
+        assert_eq!(comp.properties["syft:location:0:path"].len(), 2);
+        assert_eq!(
+            comp.properties["cdx:npm:package:development"],
+            BTreeSet::from(["true".to_string()])
+        );
+
+        let streamed = CycloneDxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed.components, sbom.components);
+    }
+
+    #[test]
+    fn test_component_type_becomes_kind() {
+        let json = br#"{
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "metadata": {
+                "component": {"type": "container", "bom-ref": "img", "name": "image"}
+            },
+            "components": [
+                {"type": "operating-system", "name": "debian", "version": "12.5"},
+                {
+                    "type": "application",
+                    "name": "app",
+                    "components": [{"type": "framework", "name": "spring"}]
+                },
+                {"type": "machine-learning-model", "name": "model"}
+            ]
+        }"#;
+
+        let sbom = CycloneDxReader::read_json(&json[..]).unwrap();
+        let kind = |name: &str| {
+            sbom.components
+                .values()
+                .find(|c| c.name == name)
+                .unwrap_or_else(|| panic!("{name} missing"))
+                .kind
+                .as_deref()
+        };
+        assert_eq!(kind("image"), Some("container"));
+        assert_eq!(kind("debian"), Some("operating-system"));
+        assert_eq!(kind("app"), Some("application"));
+        assert_eq!(kind("spring"), Some("framework"));
+        assert_eq!(kind("model"), Some("machine-learning-model"));
+        assert_eq!(
+            CycloneDxReader::read_json_streaming(&json[..]).unwrap(),
+            sbom
+        );
+    }
 }
@@ -0,0 +1,622 @@
+//! streaming JSON reader.
+//!
+//! [`read`] walks the document with a `serde_json` deserializer and maps each
+//! component and dependency entry into the [`Sbom`] as soon as it has been
+//! read, instead of materializing the `serde_json::Value` tree and the
+//! `cyclonedx-bom` model that [`CycloneDxReader::read_json`] goes through.
+//! only one top-level component (with its sub-components) is held at a time.
+
+use crate::{
+    author_label, tool_label, ComponentSource, CycloneDxReader, Error, LicenseEntry,
+    MAX_COMPONENT_DEPTH, SUPPORTED_SPEC_VERSIONS,
+};
+use cyclonedx_bom::errors::{BomError, JsonReadError};
+use sbom_model::{ComponentId, DependencyKind, Sbom};
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+
+/// parses a CycloneDX JSON document from `reader` without building the
+/// intermediate document model.
+pub(crate) fn read<R: Read>(reader: R) -> Result<Sbom, Error> {
+    let mut reader = BufReader::new(reader);
+    // strip a leading UTF-8 BOM; serde_json does not skip it.
+    if reader.fill_buf()?.starts_with(b"\xef\xbb\xbf") {
+        reader.consume(3);
+    }
+
+    let mut state = State::default();
+    let mut de = serde_json::Deserializer::from_reader(reader);
+    let parsed = DocumentSeed(&mut state)
+        .deserialize(&mut de)
+        .and_then(|()| de.end());
+    if let Some(version) = state.unsupported {
+        return Err(Error::UnsupportedVersion { version });
+    }
+    parsed.map_err(JsonReadError::from)?;
+    state.finish()
+}
+
+/// what has been mapped so far, plus the bookkeeping needed to resolve
+/// dependency bom-refs.
+#[derive(Default)]
+struct State {
+    sbom: Sbom,
+    scope_map: BTreeMap<String, DependencyKind>,
+    spec_version: Option<String>,
+    unsupported: Option<String>,
+    seen_metadata: bool,
+    seen_components: bool,
+    ref_map: Option<BTreeMap<String, ComponentId>>,
+    /// dependency entries read before every component they can name was.
+    pending: Vec<Dependency>,
+}
+
+impl State {
+    fn add_metadata(&mut self, meta: Metadata) {
+        self.seen_metadata = true;
+        let sbom = &mut self.sbom;
+        if let Some(timestamp) = meta.timestamp {
+            sbom.metadata.timestamp = Some(timestamp);
+        }
+        match meta.tools {
+            Some(Tools::List(list)) => {
+                for tool in list {
+                    sbom.metadata.tools.push(tool_label(
+                        tool.vendor.as_deref(),
+                        tool.name.as_deref(),
+                        tool.version.as_deref(),
+                    ));
+                }
+            }
+            Some(Tools::Object { components }) => {
+                for component in components {
+                    sbom.metadata.tools.push(tool_label(
+                        None,
+                        Some(&component.name),
+                        component.version.as_deref(),
+                    ));
+                }
+            }
+            None => {}
+        }
+        for author in meta.authors {
+            sbom.metadata.authors.push(author_label(
+                author.name.as_deref(),
+                author.email.as_deref(),
+            ));
+        }
+        if let Some(root) = meta.component {
+            sbom.metadata.primary_component = Some(CycloneDxReader::component_id(&root));
+            CycloneDxReader::collect_components(
+                std::slice::from_ref(&root),
+                sbom,
+                &mut self.scope_map,
+                None,
+                0,
+            );
+        }
+    }
+
+    fn add_dependency(&mut self, dep: Dependency) {
+        // the document's root component lives in `metadata`, so both have to
+        // be read before a bom-ref can be reported as dangling.
+        if !(self.seen_metadata && self.seen_components) {
+            self.pending.push(dep);
+            return;
+        }
+        let ref_map = self
+            .ref_map
+            .get_or_insert_with(|| CycloneDxReader::ref_map(&self.sbom));
+        CycloneDxReader::add_dependency(
+            &mut self.sbom,
+            ref_map,
+            &self.scope_map,
+            &dep.dependency_ref,
+            &dep.depends_on,
+        );
+    }
+
+    fn finish(mut self) -> Result<Sbom, Error> {
+        if self.spec_version.is_none() {
+            return Err(JsonReadError::from(BomError::UnsupportedSpecVersion(
+                "No field 'specVersion' found".to_string(),
+            ))
+            .into());
+        }
+        self.sbom.metadata.format = Some("cyclonedx".to_string());
+        self.seen_metadata = true;
+        self.seen_components = true;
+        for dep in std::mem::take(&mut self.pending) {
+            self.add_dependency(dep);
+        }
+        self.sbom.rebuild_reverse_deps();
+        Ok(self.sbom)
+    }
+}
+
+struct DocumentSeed<'a>(&'a mut State);
+
+impl<'de> DeserializeSeed<'de> for DocumentSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DocumentSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a CycloneDX JSON document")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "specVersion" => {
+                    let version: String = map.next_value()?;
+                    if !SUPPORTED_SPEC_VERSIONS.contains(&version.as_str()) {
+                        let err = de::Error::custom(format!("unsupported specVersion '{version}'"));
+                        self.0.unsupported = Some(version);
+                        return Err(err);
+                    }
+                    self.0.sbom.metadata.spec_version = Some(version.clone());
+                    self.0.spec_version = Some(version);
+                }
+                "serialNumber" => {
+                    self.0.sbom.metadata.serial_number = map.next_value()?;
+                }
+                "version" => {
+                    self.0.sbom.metadata.bom_version = map.next_value()?;
+                }
+                "metadata" => {
+                    let meta: Metadata = map.next_value()?;
+                    self.0.add_metadata(meta);
+                }
+                "components" => {
+                    map.next_value_seed(ComponentsSeed(self.0))?;
+                    self.0.seen_components = true;
+                }
+                "dependencies" => map.next_value_seed(DependenciesSeed(self.0))?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// maps each top-level component as soon as it has been read.
+struct ComponentsSeed<'a>(&'a mut State);
+
+impl<'de> DeserializeSeed<'de> for ComponentsSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ComponentsSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of components")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(component) = seq.next_element::<Component>()? {
+            CycloneDxReader::collect_components(
+                std::slice::from_ref(&component),
+                &mut self.0.sbom,
+                &mut self.0.scope_map,
+                None,
+                0,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// maps each dependency entry as soon as it has been read.
+struct DependenciesSeed<'a>(&'a mut State);
+
+impl<'de> DeserializeSeed<'de> for DependenciesSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DependenciesSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of dependencies")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(dep) = seq.next_element::<Dependency>()? {
+            self.0.add_dependency(dep);
+        }
+        Ok(())
+    }
+}
+
+/// the subset of `metadata` the adapter maps.
+#[derive(Deserialize)]
+struct Metadata {
+    timestamp: Option<String>,
+    tools: Option<Tools>,
+    #[serde(default)]
+    authors: Vec<Author>,
+    component: Option<Component>,
+}
+
+/// `metadata.tools`: a list of tools (1.3, 1.4) or an object of components
+/// and services (1.5).
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Tools {
+    List(Vec<Tool>),
+    Object {
+        #[serde(default)]
+        components: Vec<ToolComponent>,
+    },
+}
+
+#[derive(Deserialize)]
+struct Tool {
+    vendor: Option<String>,
+    name: Option<String>,
+    version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ToolComponent {
+    name: String,
+    version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    name: Option<String>,
+    email: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Dependency {
+    #[serde(rename = "ref")]
+    dependency_ref: String,
+    #[serde(default, rename = "dependsOn")]
+    depends_on: Vec<String>,
+}
+
+/// the parts of a document that carry components, for recovering what the
+/// `cyclonedx-bom` model cannot hold.
+#[derive(Deserialize)]
+struct ComponentsOnly {
+    metadata: Option<MetadataComponent>,
+    #[serde(default)]
+    components: Vec<Component>,
+}
+
+#[derive(Deserialize)]
+struct MetadataComponent {
+    component: Option<Component>,
+}
+
+/// sets the license fields of the components in `sbom` from the license
+/// `acknowledgement`s in the JSON document `data`, which `cyclonedx-bom`
+/// does not model.
+pub(crate) fn apply_license_acknowledgements(data: &[u8], sbom: &mut Sbom) -> Result<(), Error> {
+    let doc: ComponentsOnly = serde_json::from_slice(data).map_err(JsonReadError::from)?;
+    let root = doc.metadata.and_then(|m| m.component);
+    apply_acknowledged(root.as_slice(), sbom, 0);
+    apply_acknowledged(&doc.components, sbom, 0);
+    Ok(())
+}
+
+/// walks components in the order `collect_components` does, so a duplicate id
+/// ends up with the licenses of the entry that won there.
+fn apply_acknowledged(components: &[Component], sbom: &mut Sbom, depth: usize) {
+    if depth >= MAX_COMPONENT_DEPTH {
+        return;
+    }
+    for component in components {
+        let id = CycloneDxReader::component_id(component);
+        if let Some(comp) = sbom.components.get_mut(&id) {
+            CycloneDxReader::map_licenses(component, comp);
+        }
+        if let Some(children) = &component.components {
+            apply_acknowledged(children, sbom, depth + 1);
+        }
+    }
+}
+
+/// the subset of a component the adapter maps.
+#[derive(Deserialize)]
+struct Component {
+    #[serde(rename = "type")]
+    component_type: Option<String>,
+    name: String,
+    version: Option<String>,
+    description: Option<String>,
+    supplier: Option<Supplier>,
+    copyright: Option<String>,
+    purl: Option<String>,
+    cpe: Option<String>,
+    #[serde(default, rename = "externalReferences")]
+    external_references: Vec<ExternalReference>,
+    #[serde(rename = "bom-ref")]
+    bom_ref: Option<String>,
+    scope: Option<String>,
+    #[serde(default)]
+    licenses: Vec<LicenseChoice>,
+    #[serde(default)]
+    hashes: Vec<Hash>,
+    #[serde(default)]
+    properties: Vec<Property>,
+    components: Option<Vec<Component>>,
+}
+
+#[derive(Deserialize)]
+struct Property {
+    name: String,
+    value: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExternalReference {
+    #[serde(rename = "type")]
+    ref_type: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct Supplier {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LicenseChoice {
+    License {
+        license: License,
+    },
+    Expression {
+        expression: String,
+        acknowledgement: Option<String>,
+    },
+}
+
+impl LicenseChoice {
+    /// the entry, and whether it is acknowledged as declared.
+    fn entry(&self) -> Option<(LicenseEntry<'_>, bool)> {
+        match self {
+            LicenseChoice::License { license } => {
+                license.id.as_deref().or(license.name.as_deref()).map(|l| {
+                    (
+                        LicenseEntry::Identifier(Cow::Borrowed(l)),
+                        is_declared(&license.acknowledgement),
+                    )
+                })
+            }
+            LicenseChoice::Expression {
+                expression,
+                acknowledgement,
+            } => Some((
+                LicenseEntry::Expression(Cow::Borrowed(expression)),
+                is_declared(acknowledgement),
+            )),
+        }
+    }
+}
+
+fn is_declared(acknowledgement: &Option<String>) -> bool {
+    acknowledgement.as_deref() == Some("declared")
+}
+
+#[derive(Deserialize)]
+struct License {
+    id: Option<String>,
+    name: Option<String>,
+    acknowledgement: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Hash {
+    alg: String,
+    content: String,
+}
+
+impl ComponentSource for Component {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    fn kind(&self) -> Option<Cow<'_, str>> {
+        self.component_type.as_deref().map(Cow::Borrowed)
+    }
+
+    fn supplier_name(&self) -> Option<&str> {
+        self.supplier.as_ref().and_then(|s| s.name.as_deref())
+    }
+
+    fn purl(&self) -> Option<&str> {
+        self.purl.as_deref()
+    }
+
+    fn cpe(&self) -> Option<&str> {
+        self.cpe.as_deref()
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn copyright(&self) -> Option<&str> {
+        self.copyright.as_deref()
+    }
+
+    fn external_refs(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)> {
+        self.external_references
+            .iter()
+            .map(|r| {
+                (
+                    Cow::Borrowed(r.ref_type.as_str()),
+                    Cow::Borrowed(r.url.as_str()),
+                )
+            })
+            .collect()
+    }
+
+    fn bom_ref(&self) -> Option<&str> {
+        self.bom_ref.as_deref()
+    }
+
+    fn scope(&self) -> Option<Cow<'_, str>> {
+        self.scope.as_deref().map(Cow::Borrowed)
+    }
+
+    fn licenses(&self) -> Vec<LicenseEntry<'_>> {
+        self.licenses
+            .iter()
+            .filter_map(LicenseChoice::entry)
+            .filter(|(_, declared)| !declared)
+            .map(|(entry, _)| entry)
+            .collect()
+    }
+
+    fn declared_licenses(&self) -> Vec<LicenseEntry<'_>> {
+        self.licenses
+            .iter()
+            .filter_map(LicenseChoice::entry)
+            .filter(|(_, declared)| *declared)
+            .map(|(entry, _)| entry)
+            .collect()
+    }
+
+    fn hashes(&self) -> Vec<(Cow<'_, str>, &str)> {
+        self.hashes
+            .iter()
+            .map(|h| (Cow::Borrowed(h.alg.as_str()), h.content.as_str()))
+            .collect()
+    }
+
+    fn properties(&self) -> Vec<(&str, &str)> {
+        self.properties
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_deref().unwrap_or_default()))
+            .collect()
+    }
+
+    fn children(&self) -> Option<&[Self]> {
+        self.components.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_read_json_on_fixtures() {
+        let mut compared = 0;
+        for entry in std::fs::read_dir("../../tests/fixtures").unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let data = std::fs::read(&path).unwrap();
+            let Ok(expected) = CycloneDxReader::read_json(data.as_slice()) else {
+                continue;
+            };
+            let streamed =
+                read(data.as_slice()).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+            assert_eq!(streamed, expected, "{}", path.display());
+            let order = |s: &Sbom| s.components.keys().cloned().collect::<Vec<_>>();
+            assert_eq!(order(&streamed), order(&expected), "{}", path.display());
+            compared += 1;
+        }
+        assert!(compared > 20, "only {compared} fixtures compared");
+    }
+
+    #[test]
+    fn test_dependencies_before_components() {
+        let json = r#"{
+            "dependencies": [
+                {"ref": "app", "dependsOn": ["lib", "missing"]}
+            ],
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "components": [
+                {"type": "library", "bom-ref": "lib", "name": "lib", "scope": "optional"}
+            ],
+            "metadata": {
+                "component": {"type": "application", "bom-ref": "app", "name": "app"}
+            }
+        }"#;
+        let sbom = read(json.as_bytes()).unwrap();
+        let app = &sbom
+            .components
+            .values()
+            .find(|c| c.name == "app")
+            .unwrap()
+            .id;
+        let lib = &sbom
+            .components
+            .values()
+            .find(|c| c.name == "lib")
+            .unwrap()
+            .id;
+        assert_eq!(sbom.dependencies[app][lib], DependencyKind::Optional);
+        assert_eq!(sbom.warnings.len(), 1);
+        assert!(sbom.warnings[0].contains("'missing'"));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let json = r#"{"bomFormat": "CycloneDX", "specVersion": "1.6", "components": []}"#;
+        let err = read(json.as_bytes()).unwrap_err();
+        assert!(
+            matches!(err, Error::UnsupportedVersion { ref version } if version == "1.6"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_missing_spec_version() {
+        let err = read(&br#"{"bomFormat": "CycloneDX", "components": []}"#[..]).unwrap_err();
+        assert!(err.to_string().contains("specVersion"), "{err}");
+    }
+
+    #[test]
+    fn test_malformed_input_is_parse_error() {
+        let err =
+            read(&br#"{"specVersion": "1.4", "components": [{"version": "1"}]}"#[..]).unwrap_err();
+        assert!(matches!(err, Error::Parse(_)), "{err}");
+        assert!(err.to_string().contains("name"), "{err}");
+    }
+
+    #[test]
+    fn test_utf8_bom_and_trailing_garbage() {
+        let mut json = b"\xef\xbb\xbf".to_vec();
+        json.extend_from_slice(br#"{"specVersion": "1.4", "components": []}"#);
+        assert!(read(json.as_slice()).unwrap().components.is_empty());
+
+        json.extend_from_slice(b" {}");
+        assert!(matches!(read(json.as_slice()), Err(Error::Parse(_))));
+    }
+}
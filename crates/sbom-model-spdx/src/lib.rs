@@ -2,14 +2,18 @@
 
 use sbom_model::{
     canonical_algorithm_name, parse_license_expression, Component, ComponentId, DependencyKind,
-    Sbom,
+    FileEntry, Sbom,
+};
+use spdx_rs::models::{
+    ExternalPackageReferenceCategory, FileInformation, PackageInformation, Pointer,
+    PrimaryPackagePurpose, Relationship, RelationshipType, Snippet, SpdxExpression,
 };
-use spdx_rs::models::RelationshipType;
 use spdx_rs::parsers::spdx_from_tag_value;
 use std::collections::{BTreeMap, BTreeSet};
 use std::io::Read;
 use thiserror::Error;
 
+mod stream;
 mod xml;
 
 /// errors that can occur when parsing SPDX documents.
@@ -82,6 +86,241 @@ fn dependency_direction(rel_type: &RelationshipType) -> Option<(Direction, Depen
     }
 }
 
+/// the SPDX ID of the element `rel` says the document describes, if it is
+/// a `DESCRIBES` (or inverse `DESCRIBED_BY`) relationship of the document.
+fn described_element<'a>(rel: &'a Relationship, doc_spdx_id: &str) -> Option<&'a str> {
+    match rel.relationship_type {
+        RelationshipType::Describes if rel.spdx_element_id == doc_spdx_id => {
+            Some(&rel.related_spdx_element)
+        }
+        RelationshipType::DescribedBy if rel.related_spdx_element == doc_spdx_id => {
+            Some(&rel.spdx_element_id)
+        }
+        _ => None,
+    }
+}
+
+/// files and snippets read so far, and what places them in a package.
+/// packages, files and relationships can come in any order, so ownership
+/// and snippets are resolved once the whole document has been read.
+#[derive(Default)]
+struct FileLayer {
+    /// file SPDX ID -> index into `Sbom::files`.
+    index: BTreeMap<String, usize>,
+    /// file SPDX ID -> SPDX ID of the package containing it.
+    owners: BTreeMap<String, String>,
+    snippets: Vec<Snippet>,
+}
+
+impl FileLayer {
+    /// records the files a package lists in `hasFiles`.
+    fn add_package_files(&mut self, pkg: &PackageInformation) {
+        for file in &pkg.files {
+            self.owners
+                .entry(file.clone())
+                .or_insert_with(|| pkg.package_spdx_identifier.clone());
+        }
+    }
+}
+
+/// the license identifiers of a concluded expression, falling back to the
+/// ones found in the file or snippet when nothing was concluded.
+fn file_licenses<'a>(
+    concluded: Option<&SpdxExpression>,
+    found: impl IntoIterator<Item = &'a str>,
+) -> BTreeSet<String> {
+    let stated = |l: &str| l != "NOASSERTION" && l != "NONE";
+    match concluded
+        .map(SpdxExpression::to_string)
+        .filter(|l| stated(l))
+    {
+        Some(l) => parse_license_expression(&l),
+        None => found
+            .into_iter()
+            .filter(|l| stated(l))
+            .flat_map(parse_license_expression)
+            .collect(),
+    }
+}
+
+/// `#bytes=START-END` or `#lines=START-END` for a snippet's first range.
+fn snippet_range(snippet: &Snippet) -> String {
+    let Some(range) = snippet.ranges.first() else {
+        return format!("#{}", snippet.snippet_spdx_identifier);
+    };
+    match (&range.start_pointer, &range.end_pointer) {
+        (Pointer::Byte { offset: start, .. }, Pointer::Byte { offset: end, .. }) => {
+            format!("#bytes={start}-{end}")
+        }
+        (
+            Pointer::Line {
+                line_number: start, ..
+            },
+            Pointer::Line {
+                line_number: end, ..
+            },
+        ) => format!("#lines={start}-{end}"),
+        _ => format!("#{}", snippet.snippet_spdx_identifier),
+    }
+}
+
+/// maps a package's download location, home page and non-purl `externalRefs`
+/// onto CycloneDX external reference types.
+///
+/// a download location in SPDX's VCS form (`git+https://...`) is `vcs`, any
+/// other one `distribution`; `SECURITY` `advisory` and `url` refs are
+/// `advisories`. other refs keep their SPDX reference type; CPEs are left to
+/// `Component::cpes`.
+fn package_external_refs(pkg: &PackageInformation) -> BTreeMap<String, BTreeSet<String>> {
+    const VCS_PREFIXES: &[&str] = &["git+", "hg+", "svn+", "bzr+"];
+    let stated = |s: &&str| !s.is_empty() && *s != "NOASSERTION" && *s != "NONE";
+
+    let mut refs: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut add = |ref_type: &str, locator: &str| {
+        refs.entry(ref_type.to_string())
+            .or_default()
+            .insert(locator.to_string());
+    };
+
+    if let Some(location) = Some(pkg.package_download_location.trim()).filter(stated) {
+        if VCS_PREFIXES.iter().any(|p| location.starts_with(p)) {
+            add("vcs", location);
+        } else {
+            add("distribution", location);
+        }
+    }
+    if let Some(home) = pkg
+        .package_home_page
+        .as_deref()
+        .map(str::trim)
+        .filter(stated)
+    {
+        add("website", home);
+    }
+    for r in &pkg.external_reference {
+        let ref_type = r.reference_type.to_ascii_lowercase();
+        let locator = r.reference_locator.trim();
+        match (&r.reference_category, ref_type.as_str()) {
+            (_, "purl" | "cpe22type" | "cpe23type") => {}
+            _ if locator.is_empty() => {}
+            (ExternalPackageReferenceCategory::Security, "advisory" | "url") => {
+                add("advisories", locator)
+            }
+            (_, other) => add(other, locator),
+        }
+    }
+    refs
+}
+
+/// records an annotation comment as a component property. a comment of the
+/// `Name: value` form tools use for key/value annotations (e.g.
+/// "PkgType: npm") becomes that property; any other comment is kept under
+/// `spdx:annotation`.
+fn add_annotation(comp: &mut Component, comment: &str) {
+    let comment = comment.trim();
+    if comment.is_empty() {
+        return;
+    }
+    let (name, value) = match comment.split_once(':') {
+        Some((name, value))
+            if !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+                && !value.starts_with("//")
+                && !value.trim().is_empty() =>
+        {
+            (name, value.trim())
+        }
+        _ => ("spdx:annotation", comment),
+    };
+    comp.properties
+        .entry(name.to_string())
+        .or_default()
+        .insert(value.to_string());
+}
+
+/// maps an SPDX `primaryPackagePurpose` onto the model's component kind,
+/// which uses CycloneDX component-type names.
+fn package_kind(purpose: PrimaryPackagePurpose) -> &'static str {
+    match purpose {
+        PrimaryPackagePurpose::Application => "application",
+        PrimaryPackagePurpose::Framework => "framework",
+        PrimaryPackagePurpose::Library => "library",
+        PrimaryPackagePurpose::Container => "container",
+        PrimaryPackagePurpose::OperatingSystem => "operating-system",
+        PrimaryPackagePurpose::Device => "device",
+        PrimaryPackagePurpose::Firmware => "firmware",
+        PrimaryPackagePurpose::Source => "source",
+        PrimaryPackagePurpose::Archive => "archive",
+        PrimaryPackagePurpose::File => "file",
+        PrimaryPackagePurpose::Install => "install",
+        PrimaryPackagePurpose::Other => "other",
+    }
+}
+
+/// respells a JSON package's `OPERATING-SYSTEM` purpose as the
+/// `OPERATING_SYSTEM` spdx-rs 0.5 expects; it rejects the spec's spelling,
+/// and with it the whole document.
+fn respell_package_purpose(package: &mut serde_json::Value) {
+    if let Some(serde_json::Value::String(purpose)) = package.get_mut("primaryPackagePurpose") {
+        if purpose.contains('-') {
+            *purpose = purpose.replace('-', "_");
+        }
+    }
+}
+
+/// applies [`respell_package_purpose`] to every package of a JSON document.
+fn respell_package_purposes(doc: &mut serde_json::Value) {
+    if let Some(serde_json::Value::Array(packages)) = doc.get_mut("packages") {
+        packages.iter_mut().for_each(respell_package_purpose);
+    }
+}
+
+/// parses a tag-value `PrimaryPackagePurpose` value, in either spelling.
+fn parse_package_purpose(value: &str) -> Option<PrimaryPackagePurpose> {
+    serde_json::from_value(serde_json::Value::String(value.trim().replace('-', "_"))).ok()
+}
+
+/// collects each package's `PrimaryPackagePurpose` by package SPDX ID from
+/// a tag-value document; spdx-rs 0.5 parses the tag but never stores it.
+fn tag_value_package_purposes(input: &str) -> BTreeMap<String, PrimaryPackagePurpose> {
+    let mut purposes = BTreeMap::new();
+    // (SPDX ID, purpose) of the package whose fields are being read.
+    let mut current: Option<(Option<String>, Option<PrimaryPackagePurpose>)> = None;
+    let mut flush = |current: &mut Option<(Option<String>, Option<PrimaryPackagePurpose>)>| {
+        if let Some((Some(id), Some(purpose))) = current.take() {
+            purposes.insert(id, purpose);
+        }
+    };
+    for line in tag_lines(input) {
+        let Some((tag, value)) = line.split_once(':') else {
+            continue;
+        };
+        match tag {
+            "PackageName" => {
+                flush(&mut current);
+                current = Some((None, None));
+            }
+            // a file, snippet, or license section ends the package.
+            "FileName" | "SnippetSPDXID" | "LicenseID" => flush(&mut current),
+            "SPDXID" => {
+                if let Some((id @ None, _)) = &mut current {
+                    *id = Some(value.trim().to_string());
+                }
+            }
+            "PrimaryPackagePurpose" => {
+                if let Some((_, purpose)) = &mut current {
+                    *purpose = parse_package_purpose(value);
+                }
+            }
+            _ => {}
+        }
+    }
+    flush(&mut current);
+    purposes
+}
+
 /// yields the trimmed lines of a tag-value document that begin outside a
 /// `<text>` ... `</text>` block, i.e. the ones that can carry a tag.
 fn tag_lines(input: &str) -> impl Iterator<Item = &str> {
@@ -152,11 +391,45 @@ impl SpdxReader {
 
         Self::check_spdx_version(buf)?;
 
-        let spdx_doc: spdx_rs::models::SPDX = serde_json::from_slice(buf)?;
+        // only documents that need respelling pay for the detour through a
+        // `Value`.
+        let needle = b"OPERATING-SYSTEM";
+        let spdx_doc: spdx_rs::models::SPDX = if buf.windows(needle.len()).any(|w| w == needle) {
+            let mut value: serde_json::Value = serde_json::from_slice(buf)?;
+            respell_package_purposes(&mut value);
+            serde_json::from_value(value)?
+        } else {
+            serde_json::from_slice(buf)?
+        };
 
         Ok(Self::spdx_to_sbom(spdx_doc))
     }
 
+    /// parses an SPDX JSON document from a reader without building the
+    /// intermediate document model.
+    ///
+    /// produces the same [`Sbom`] as [`read_json`](Self::read_json), but maps
+    /// each package and relationship as it is read, so peak memory is the
+    /// resulting `Sbom` plus one package rather than several copies of the
+    /// whole document. Use it for very large SBOMs.
+    ///
+    /// only the fields the adapter maps are read: unknown top-level keys and
+    /// document-level annotations are skipped without being checked against
+    /// the schema, so some malformed documents parse here and fail there.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sbom_model_spdx::SpdxReader;
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("sbom.spdx.json").unwrap();
+    /// let sbom = SpdxReader::read_json_streaming(file).unwrap();
+    /// ```
+    pub fn read_json_streaming<R: Read>(reader: R) -> Result<Sbom, Error> {
+        stream::read(reader)
+    }
+
     /// parses an SPDX XML document from a reader.
     ///
     /// accepts both `<Document>` and `<SpdxDocument>` as the root element.
@@ -176,7 +449,8 @@ impl SpdxReader {
 
         let buf = buf.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&buf);
 
-        let value = xml::xml_to_json(buf)?;
+        let mut value = xml::xml_to_json(buf)?;
+        respell_package_purposes(&mut value);
         Self::check_version(value.get("spdxVersion").and_then(|v| v.as_str()))?;
 
         let spdx_doc: spdx_rs::models::SPDX = serde_json::from_value(value)?;
@@ -207,7 +481,7 @@ impl SpdxReader {
 
         Self::check_spdx_version_tag_value(input)?;
 
-        // spdx-rs 0.5 has two tag-value parsing quirks we work around:
+        // spdx-rs 0.5 has three tag-value parsing quirks we work around:
         //
         // 1. CreationInfo default contamination: the parser starts with
         //    CreationInfo::default() which includes phantom creators
@@ -219,6 +493,13 @@ impl SpdxReader {
         //    PackageName is seen. The very last package's last ExternalRef
         //    is never flushed. We append a sentinel package to trigger
         //    the flush, then strip it from the result.
+        //
+        // 3. PrimaryPackagePurpose dropped: the tag is parsed but never
+        //    stored on the package. We re-parse it from the raw input.
+        //
+        // annotations are also read at document level, with SPDXREF naming
+        // the element, rather than on the package as in JSON; they are
+        // attached to their package after conversion.
         let patched = format!(
             "{}\n\nPackageName: __spdx_rs_flush_sentinel__\nSPDXID: SPDXRef-FLUSH-SENTINEL\nPackageDownloadLocation: NOASSERTION\nFilesAnalyzed: false\n",
             input.trim_end()
@@ -244,6 +525,14 @@ impl SpdxReader {
             .package_information
             .retain(|p| p.package_name != "__spdx_rs_flush_sentinel__");
 
+        // quirk 3: dropped package purposes.
+        let purposes = tag_value_package_purposes(input);
+        for pkg in &mut spdx_doc.package_information {
+            if let Some(purpose) = purposes.get(&pkg.package_spdx_identifier) {
+                pkg.primary_package_purpose = Some(*purpose);
+            }
+        }
+
         // quirk 1: creator contamination.
         let parsed_creators = spdx_doc
             .document_creation_information
@@ -258,7 +547,19 @@ impl SpdxReader {
             .creation_info
             .creators = actual_creators.clone();
 
+        let annotations = std::mem::take(&mut spdx_doc.annotations);
         let mut sbom = Self::spdx_to_sbom(spdx_doc);
+        let ref_map = Self::ref_map(&sbom);
+        for annotation in annotations {
+            let target = annotation
+                .spdx_identifier_reference
+                .as_ref()
+                .and_then(|r| ref_map.get(r))
+                .and_then(|id| sbom.components.get_mut(id));
+            if let Some(comp) = target {
+                add_annotation(comp, &annotation.annotation_comment);
+            }
+        }
 
         // emit diagnostics for workarounds that fired.
         if last_pkg_has_ext_ref {
@@ -291,8 +592,71 @@ impl SpdxReader {
     /// format-agnostic [`Sbom`] type. Shared by JSON and tag-value readers.
     fn spdx_to_sbom(spdx_doc: spdx_rs::models::SPDX) -> Sbom {
         let mut sbom = Sbom::default();
+        let doc_info = spdx_doc.document_creation_information;
+
+        Self::add_document_identity(
+            &mut sbom,
+            doc_info.spdx_version,
+            doc_info.document_name,
+            doc_info.spdx_document_namespace,
+        );
+        Self::add_creation_info(&mut sbom, doc_info.creation_info);
+
+        let mut files = FileLayer::default();
+        for pkg in spdx_doc.package_information {
+            files.add_package_files(&pkg);
+            Self::add_package(&mut sbom, pkg);
+        }
+        for file in spdx_doc.file_information {
+            Self::add_file(&mut sbom, &mut files, file);
+        }
+        files.snippets = spdx_doc.snippet_information;
+
+        let ref_map = Self::ref_map(&sbom);
 
-        let ci = spdx_doc.document_creation_information.creation_info;
+        let doc_spdx_id = doc_info.spdx_identifier;
+        let mut described: BTreeSet<String> = doc_info.document_describes.into_iter().collect();
+
+        for rel in spdx_doc.relationships {
+            described.extend(described_element(&rel, &doc_spdx_id).map(str::to_string));
+            Self::add_relationship(&mut sbom, &ref_map, &mut files, &doc_spdx_id, rel);
+        }
+
+        Self::resolve_files(&mut sbom, &ref_map, files);
+        Self::set_primary_component(&mut sbom, &ref_map, &described);
+        sbom.rebuild_reverse_deps();
+        sbom
+    }
+
+    /// records the format, SPDX version, document name and namespace.
+    /// `NOASSERTION` (what spdx-rs fills in for a tag-value document that
+    /// omits them) counts as absent.
+    fn add_document_identity(sbom: &mut Sbom, version: String, name: String, namespace: String) {
+        let stated = |value: String| Some(value).filter(|v| !v.is_empty() && v != "NOASSERTION");
+        sbom.metadata.format = Some("spdx".to_string());
+        sbom.metadata.spec_version = stated(version);
+        sbom.metadata.name = stated(name);
+        sbom.metadata.namespace = stated(namespace);
+    }
+
+    /// makes the package the document describes its primary component. a
+    /// document that describes several packages (or none) has no single
+    /// primary component.
+    fn set_primary_component(
+        sbom: &mut Sbom,
+        ref_map: &BTreeMap<String, ComponentId>,
+        described: &BTreeSet<String>,
+    ) {
+        let ids: BTreeSet<&ComponentId> = described
+            .iter()
+            .filter_map(|spdx_id| ref_map.get(spdx_id))
+            .collect();
+        if ids.len() == 1 {
+            sbom.metadata.primary_component = ids.into_iter().next().cloned();
+        }
+    }
+
+    fn add_creation_info(sbom: &mut Sbom, ci: spdx_rs::models::CreationInfo) {
         sbom.metadata.timestamp = Some(ci.created.to_string());
         for creator in ci.creators {
             if let Some(stripped) = creator.strip_prefix("Tool: ") {
@@ -301,159 +665,273 @@ impl SpdxReader {
                 sbom.metadata.authors.push(creator);
             }
         }
+    }
 
-        for pkg in spdx_doc.package_information {
-            let name = pkg.package_name;
-            let version = pkg.package_version;
+    fn add_package(sbom: &mut Sbom, pkg: PackageInformation) {
+        let external_refs = package_external_refs(&pkg);
+        let cpes = pkg
+            .external_reference
+            .iter()
+            .filter(|r| {
+                r.reference_type.eq_ignore_ascii_case("cpe22Type")
+                    || r.reference_type.eq_ignore_ascii_case("cpe23Type")
+            })
+            .map(|r| r.reference_locator.trim())
+            .filter(|cpe| !cpe.is_empty())
+            .map(str::to_string)
+            .collect();
+        let name = pkg.package_name;
+        let version = pkg.package_version;
 
-            let mut props = vec![("name", name.as_str())];
-            if let Some(ref v) = version {
-                props.push(("version", v.as_str()));
-            }
+        let mut props = vec![("name", name.as_str())];
+        if let Some(ref v) = version {
+            props.push(("version", v.as_str()));
+        }
 
-            // NOASSERTION/NONE are "unknown supplier" sentinels, not supplier names.
-            let supplier = pkg
-                .package_supplier
-                .clone()
-                .filter(|s| s != "NOASSERTION" && s != "NONE")
-                .map(|s| {
-                    s.strip_prefix("Organization: ")
-                        .or_else(|| s.strip_prefix("Person: "))
-                        .map(|stripped| stripped.to_string())
-                        .unwrap_or(s)
-                });
-            if let Some(ref s) = supplier {
-                props.push(("supplier", s.as_str()));
-            }
+        // NOASSERTION/NONE are "unknown supplier" sentinels, not supplier names.
+        let supplier = pkg
+            .package_supplier
+            .clone()
+            .filter(|s| s != "NOASSERTION" && s != "NONE")
+            .map(|s| {
+                s.strip_prefix("Organization: ")
+                    .or_else(|| s.strip_prefix("Person: "))
+                    .map(|stripped| stripped.to_string())
+                    .unwrap_or(s)
+            });
+        if let Some(ref s) = supplier {
+            props.push(("supplier", s.as_str()));
+        }
 
-            let mut purl = None;
-            for r in &pkg.external_reference {
-                if r.reference_type == "purl" {
-                    purl = Some(r.reference_locator.clone());
-                    break;
-                }
+        let mut purl = None;
+        for r in &pkg.external_reference {
+            if r.reference_type == "purl" {
+                purl = Some(r.reference_locator.clone());
+                break;
             }
-            let purl_str = purl.as_deref();
-
-            let ecosystem = purl_str.and_then(sbom_model::ecosystem_from_purl);
-
-            let id = ComponentId::new(purl_str, &props);
-
-            let mut comp = Component {
-                id: id.clone(),
-                name,
-                version,
-                ecosystem,
-                supplier,
-                description: pkg
-                    .package_detailed_description
-                    .clone()
-                    .or_else(|| pkg.package_summary_description.clone()),
-                purl,
-                licenses: BTreeSet::new(),
-                license_expression: None,
-                hashes: BTreeMap::new(),
-                source_ids: vec![pkg.package_spdx_identifier.clone()],
-            };
+        }
+        let purl_str = purl.as_deref();
+
+        let ecosystem = purl_str.and_then(sbom_model::ecosystem_from_purl);
+
+        let id = ComponentId::new(purl_str, &props);
+
+        let mut comp = Component {
+            id: id.clone(),
+            name,
+            version,
+            ecosystem,
+            kind: pkg
+                .primary_package_purpose
+                .map(|p| package_kind(p).to_string()),
+            supplier,
+            // NOASSERTION/NONE say nothing about who holds the copyright.
+            copyright: pkg
+                .copyright_text
+                .as_deref()
+                .map(str::trim)
+                .filter(|t| !t.is_empty() && *t != "NOASSERTION" && *t != "NONE")
+                .map(str::to_string),
+            description: pkg
+                .package_detailed_description
+                .clone()
+                .or_else(|| pkg.package_summary_description.clone()),
+            external_refs,
+            purl,
+            cpes,
+            licenses: BTreeSet::new(),
+            license_expression: None,
+            declared_licenses: BTreeSet::new(),
+            declared_license_expression: None,
+            hashes: BTreeMap::new(),
+            properties: BTreeMap::new(),
+            source_ids: vec![pkg.package_spdx_identifier.clone()],
+        };
 
-            // licenses: prefer concludedLicense, fall back to declaredLicense
-            // when concluded is absent or NOASSERTION/NONE (common in
-            // automated tooling output from syft, trivy, etc.).
-            let license_expr = pkg
-                .concluded_license
-                .as_ref()
-                .filter(|l| {
-                    let s = l.to_string();
-                    s != "NOASSERTION" && s != "NONE"
+        for annotation in &pkg.annotations {
+            add_annotation(&mut comp, &annotation.annotation_comment);
+        }
+
+        // licenses: prefer concludedLicense, fall back to declaredLicense
+        // when concluded is absent or NOASSERTION/NONE (common in
+        // automated tooling output from syft, trivy, etc.). the declared
+        // license is kept on its own either way.
+        let stated = |l: &&SpdxExpression| {
+            let s = l.to_string();
+            s != "NOASSERTION" && s != "NONE"
+        };
+        let declared = pkg.declared_license.as_ref().filter(stated);
+        if let Some(l) = declared {
+            let l = l.to_string();
+            comp.declared_licenses = parse_license_expression(&l);
+            comp.declared_license_expression = Some(l);
+        }
+        if let Some(l) = pkg.concluded_license.as_ref().filter(stated).or(declared) {
+            let l = l.to_string();
+            comp.licenses.extend(parse_license_expression(&l));
+            comp.license_expression = Some(l);
+        }
+
+        for checksum in pkg.package_checksum {
+            comp.hashes.insert(
+                canonical_algorithm_name(&format!("{:?}", checksum.algorithm)),
+                checksum.value,
+            );
+        }
+
+        if let Some(existing) = sbom.components.get(&id) {
+            sbom.warnings.push(format!(
+                "SPDX: duplicate component id '{}' (name '{}'); \
+                 earlier entry '{}' will be overwritten",
+                id, comp.name, existing.name,
+            ));
+        }
+        sbom.components.insert(id, comp);
+    }
+
+    /// maps a file onto `sbom.files`; the package containing it is filled
+    /// in by [`resolve_files`](Self::resolve_files).
+    fn add_file(sbom: &mut Sbom, files: &mut FileLayer, file: FileInformation) {
+        let entry = FileEntry {
+            path: file.file_name,
+            package: None,
+            hashes: file
+                .file_checksum
+                .into_iter()
+                .map(|c| {
+                    (
+                        canonical_algorithm_name(&format!("{:?}", c.algorithm)),
+                        c.value,
+                    )
                 })
-                .or(pkg.declared_license.as_ref().filter(|l| {
-                    let s = l.to_string();
-                    s != "NOASSERTION" && s != "NONE"
-                }));
-            if let Some(l) = license_expr {
-                let l = l.to_string();
-                comp.licenses.extend(parse_license_expression(&l));
-                comp.license_expression = Some(l);
-            }
+                .collect(),
+            licenses: file_licenses(
+                file.concluded_license.as_ref(),
+                file.license_information_in_file
+                    .iter()
+                    .map(SpdxExpression::to_string)
+                    .collect::<Vec<_>>()
+                    .iter()
+                    .map(String::as_str),
+            ),
+            source_ids: vec![file.file_spdx_identifier.clone()],
+        };
+        files
+            .index
+            .insert(file.file_spdx_identifier, sbom.files.len());
+        sbom.files.push(entry);
+    }
 
-            for checksum in pkg.package_checksum {
-                comp.hashes.insert(
-                    canonical_algorithm_name(&format!("{:?}", checksum.algorithm)),
-                    checksum.value,
-                );
+    /// places each file in the package that contains it, and adds each
+    /// snippet as an entry of its own under its file's path.
+    fn resolve_files(sbom: &mut Sbom, ref_map: &BTreeMap<String, ComponentId>, files: FileLayer) {
+        for (file_id, package_id) in &files.owners {
+            if let (Some(&idx), Some(package)) = (files.index.get(file_id), ref_map.get(package_id))
+            {
+                sbom.files[idx].package = Some(package.clone());
             }
-
-            if let Some(existing) = sbom.components.get(&id) {
+        }
+        for snippet in files.snippets {
+            let Some(&idx) = files.index.get(&snippet.snippet_from_file_spdx_identifier) else {
                 sbom.warnings.push(format!(
-                    "SPDX: duplicate component id '{}' (name '{}'); \
-                     earlier entry '{}' will be overwritten",
-                    id, comp.name, existing.name,
+                    "SPDX: snippet '{}' is from file '{}', which the document does not list",
+                    snippet.snippet_spdx_identifier, snippet.snippet_from_file_spdx_identifier
                 ));
-            }
-            sbom.components.insert(id, comp);
+                continue;
+            };
+            let file = &sbom.files[idx];
+            let entry = FileEntry {
+                path: format!("{}{}", file.path, snippet_range(&snippet)),
+                package: file.package.clone(),
+                hashes: BTreeMap::new(),
+                licenses: file_licenses(
+                    snippet.snippet_concluded_license.as_ref(),
+                    snippet
+                        .license_information_in_snippet
+                        .iter()
+                        .map(String::as_str),
+                ),
+                source_ids: vec![snippet.snippet_spdx_identifier],
+            };
+            sbom.files.push(entry);
         }
+    }
 
-        // map SPDX IDs -> ComponentId
+    /// maps SPDX IDs -> ComponentId
+    fn ref_map(sbom: &Sbom) -> BTreeMap<String, ComponentId> {
         let mut ref_map = BTreeMap::new();
         for (id, comp) in &sbom.components {
             for src_id in &comp.source_ids {
                 ref_map.insert(src_id.clone(), id.clone());
             }
         }
+        ref_map
+    }
 
-        let doc_spdx_id = spdx_doc
-            .document_creation_information
-            .spdx_identifier
-            .clone();
-
-        for rel in spdx_doc.relationships {
-            let left_spdx = rel.spdx_element_id;
-            let right_spdx = rel.related_spdx_element;
-            let rel_type = rel.relationship_type;
-
-            // determine the edge direction and semantic kind for this
-            // relationship type.
-            let (parent_spdx, child_spdx, kind) = match dependency_direction(&rel_type) {
-                Some((Direction::Forward, kind)) => (&left_spdx, &right_spdx, kind),
-                Some((Direction::Inverse, kind)) => (&right_spdx, &left_spdx, kind),
-                None => continue,
+    fn add_relationship(
+        sbom: &mut Sbom,
+        ref_map: &BTreeMap<String, ComponentId>,
+        files: &mut FileLayer,
+        doc_spdx_id: &str,
+        rel: spdx_rs::models::Relationship,
+    ) {
+        let left_spdx = rel.spdx_element_id;
+        let right_spdx = rel.related_spdx_element;
+        let rel_type = rel.relationship_type;
+
+        // relationships of files are not dependency edges; all that is
+        // kept of them is which package contains which file.
+        if files.index.contains_key(&left_spdx) || files.index.contains_key(&right_spdx) {
+            let (package, file) = match rel_type {
+                RelationshipType::Contains => (left_spdx, right_spdx),
+                RelationshipType::ContainedBy => (right_spdx, left_spdx),
+                _ => return,
             };
-
-            // skip relationships involving the document element itself
-            // (e.g. SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package). The
-            // document element is not a package so it will never appear
-            // in ref_map, and warning about it is a false positive.
-            if *parent_spdx == doc_spdx_id || *child_spdx == doc_spdx_id {
-                continue;
+            if ref_map.contains_key(&package) {
+                files.owners.entry(file).or_insert(package);
             }
+            return;
+        }
 
-            let parent_id = ref_map.get(parent_spdx);
-            let child_id = ref_map.get(child_spdx);
+        // determine the edge direction and semantic kind for this
+        // relationship type.
+        let (parent_spdx, child_spdx, kind) = match dependency_direction(&rel_type) {
+            Some((Direction::Forward, kind)) => (&left_spdx, &right_spdx, kind),
+            Some((Direction::Inverse, kind)) => (&right_spdx, &left_spdx, kind),
+            None => return,
+        };
 
-            match (parent_id, child_id) {
-                (Some(pid), Some(cid)) => {
-                    sbom.dependencies
-                        .entry(pid.clone())
-                        .or_default()
-                        .insert(cid.clone(), kind);
-                }
-                (None, _) => {
-                    sbom.warnings.push(format!(
-                        "SPDX: relationship source '{}' does not match any package",
-                        parent_spdx
-                    ));
-                }
-                (_, None) => {
-                    sbom.warnings.push(format!(
-                        "SPDX: relationship target '{}' (from '{}') does not match any package",
-                        child_spdx, parent_spdx
-                    ));
-                }
-            }
+        // skip relationships involving the document element itself
+        // (e.g. SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package). The
+        // document element is not a package so it will never appear
+        // in ref_map, and warning about it is a false positive.
+        if *parent_spdx == doc_spdx_id || *child_spdx == doc_spdx_id {
+            return;
         }
 
-        sbom.rebuild_reverse_deps();
-        sbom
+        let parent_id = ref_map.get(parent_spdx);
+        let child_id = ref_map.get(child_spdx);
+
+        match (parent_id, child_id) {
+            (Some(pid), Some(cid)) => {
+                sbom.dependencies
+                    .entry(pid.clone())
+                    .or_default()
+                    .insert(cid.clone(), kind);
+            }
+            (None, _) => {
+                sbom.warnings.push(format!(
+                    "SPDX: relationship source '{}' does not match any package",
+                    parent_spdx
+                ));
+            }
+            (_, None) => {
+                sbom.warnings.push(format!(
+                    "SPDX: relationship target '{}' (from '{}') does not match any package",
+                    child_spdx, parent_spdx
+                ));
+            }
+        }
     }
 
     /// pre-check the `spdxVersion` field before full parsing.
@@ -618,7 +1096,152 @@ mod tests {
     }
 
     #[test]
-    fn test_supplier_parsed() {
+    fn test_supplier_parsed() {
+        let json = r#"{
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "test",
+            "documentNamespace": "http://spdx.org/spdxdocs/test",
+            "creationInfo": {
+                "creators": ["Tool: manual"],
+                "created": "2023-01-01T00:00:00Z"
+            },
+            "packages": [
+                {
+                    "name": "pkg-a",
+                    "SPDXID": "SPDXRef-pkg-a",
+                    "downloadLocation": "NONE",
+                    "supplier": "Organization: Acme Corp"
+                }
+            ],
+            "relationships": []
+        }"#;
+        let sbom = SpdxReader::read_json(json.as_bytes()).unwrap();
+        assert_eq!(sbom.components[0].supplier, Some("Acme Corp".to_string()));
+    }
+
+    #[test]
+    fn test_copyright_text_parsed() {
+        let json = r#"{
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "test",
+            "documentNamespace": "http://spdx.org/spdxdocs/test",
+            "creationInfo": {
+                "creators": ["Tool: manual"],
+                "created": "2023-01-01T00:00:00Z"
+            },
+            "packages": [
+                {
+                    "name": "pkg-a",
+                    "SPDXID": "SPDXRef-pkg-a",
+                    "downloadLocation": "NONE",
+                    "copyrightText": "Copyright (c) 2016 Acme Corp\n"
+                },
+                {
+                    "name": "pkg-b",
+                    "SPDXID": "SPDXRef-pkg-b",
+                    "downloadLocation": "NONE",
+                    "copyrightText": "NOASSERTION"
+                }
+            ],
+            "relationships": []
+        }"#;
+        let sbom = SpdxReader::read_json(json.as_bytes()).unwrap();
+        let find = |name: &str| sbom.components.values().find(|c| c.name == name).unwrap();
+        assert_eq!(
+            find("pkg-a").copyright.as_deref(),
+            Some("Copyright (c) 2016 Acme Corp")
+        );
+        assert_eq!(find("pkg-b").copyright, None);
+
+        let streamed = SpdxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed.components, sbom.components);
+    }
+
+    #[test]
+    fn test_external_refs_and_cpes_parsed() {
+        let json = r#"{
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "test",
+            "documentNamespace": "http://spdx.org/spdxdocs/test",
+            "creationInfo": {
+                "creators": ["Tool: manual"],
+                "created": "2023-01-01T00:00:00Z"
+            },
+            "packages": [
+                {
+                    "name": "pkg-a",
+                    "SPDXID": "SPDXRef-pkg-a",
+                    "downloadLocation": "git+https://github.com/acme/pkg-a.git@v1.0.0",
+                    "homepage": "https://pkg-a.example",
+                    "externalRefs": [
+                        {
+                            "referenceCategory": "PACKAGE-MANAGER",
+                            "referenceType": "purl",
+                            "referenceLocator": "pkg:npm/pkg-a@1.0.0"
+                        },
+                        {
+                            "referenceCategory": "SECURITY",
+                            "referenceType": "cpe23Type",
+                            "referenceLocator": "cpe:2.3:a:acme:pkg-a:1.0.0:*:*:*:*:*:*:*"
+                        },
+                        {
+                            "referenceCategory": "SECURITY",
+                            "referenceType": "cpe22Type",
+                            "referenceLocator": "cpe:/a:acme:pkg-a:1.0.0"
+                        },
+                        {
+                            "referenceCategory": "SECURITY",
+                            "referenceType": "advisory",
+                            "referenceLocator": "https://nvd.nist.gov/vuln/detail/CVE-2020-0001"
+                        }
+                    ]
+                },
+                {
+                    "name": "pkg-b",
+                    "SPDXID": "SPDXRef-pkg-b",
+                    "downloadLocation": "https://registry.example/pkg-b-1.0.0.tgz",
+                    "homepage": "NOASSERTION"
+                }
+            ],
+            "relationships": []
+        }"#;
+        let sbom = SpdxReader::read_json(json.as_bytes()).unwrap();
+        let find = |name: &str| sbom.components.values().find(|c| c.name == name).unwrap();
+
+        let a = &find("pkg-a").external_refs;
+        assert_eq!(
+            a["vcs"],
+            BTreeSet::from(["git+https://github.com/acme/pkg-a.git@v1.0.0".to_string()])
+        );
+        assert_eq!(
+            a["website"],
+            BTreeSet::from(["https://pkg-a.example".to_string()])
+        );
+        assert_eq!(a["advisories"].len(), 1);
+        assert!(!a.contains_key("purl"));
+        assert!(!a.contains_key("cpe23type"));
+        assert_eq!(find("pkg-a").cpes.len(), 2);
+        assert!(find("pkg-b").cpes.is_empty());
+
+        let b = &find("pkg-b").external_refs;
+        assert_eq!(
+            b["distribution"],
+            BTreeSet::from(["https://registry.example/pkg-b-1.0.0.tgz".to_string()])
+        );
+        assert!(!b.contains_key("website"));
+
+        let streamed = SpdxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed.components, sbom.components);
+    }
+
+    #[test]
+    fn test_package_annotations_become_properties() {
         let json = r#"{
             "spdxVersion": "SPDX-2.3",
             "dataLicense": "CC0-1.0",
@@ -634,13 +1257,114 @@ mod tests {
                     "name": "pkg-a",
                     "SPDXID": "SPDXRef-pkg-a",
                     "downloadLocation": "NONE",
-                    "supplier": "Organization: Acme Corp"
+                    "annotations": [
+                        {
+                            "annotator": "Tool: trivy",
+                            "annotationDate": "2023-01-01T00:00:00Z",
+                            "annotationType": "OTHER",
+                            "comment": "PkgType: npm"
+                        },
+                        {
+                            "annotator": "Tool: trivy",
+                            "annotationDate": "2023-01-01T00:00:00Z",
+                            "annotationType": "OTHER",
+                            "comment": "LayerDigest: sha256:abc"
+                        },
+                        {
+                            "annotator": "Person: Jane Doe",
+                            "annotationDate": "2023-01-01T00:00:00Z",
+                            "annotationType": "REVIEW",
+                            "comment": "Reviewed: no known issues"
+                        },
+                        {
+                            "annotator": "Person: Jane Doe",
+                            "annotationDate": "2023-01-01T00:00:00Z",
+                            "annotationType": "REVIEW",
+                            "comment": "see https://example.com/review/1"
+                        }
+                    ]
                 }
             ],
             "relationships": []
         }"#;
         let sbom = SpdxReader::read_json(json.as_bytes()).unwrap();
-        assert_eq!(sbom.components[0].supplier, Some("Acme Corp".to_string()));
+        let props = &sbom.components.values().next().unwrap().properties;
+        assert_eq!(props["PkgType"], BTreeSet::from(["npm".to_string()]));
+        assert_eq!(
+            props["LayerDigest"],
+            BTreeSet::from(["sha256:abc".to_string()])
+        );
+        assert_eq!(
+            props["Reviewed"],
+            BTreeSet::from(["no known issues".to_string()])
+        );
+        assert_eq!(
+            props["spdx:annotation"],
+            BTreeSet::from(["see https://example.com/review/1".to_string()])
+        );
+
+        let streamed = SpdxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed.components, sbom.components);
+    }
+
+    #[test]
+    fn test_read_tag_value_annotations_attach_to_their_package() {
+        let tv = "\
+SPDXVersion: SPDX-2.3
+DataLicense: CC0-1.0
+SPDXID: SPDXRef-DOCUMENT
+DocumentName: test
+DocumentNamespace: http://spdx.org/spdxdocs/test
+Creator: Tool: manual
+Created: 2023-01-01T00:00:00Z
+
+PackageName: pkg-a
+SPDXID: SPDXRef-pkg-a
+PackageDownloadLocation: NONE
+FilesAnalyzed: false
+
+PackageName: pkg-b
+SPDXID: SPDXRef-pkg-b
+PackageDownloadLocation: NONE
+FilesAnalyzed: false
+
+Annotator: Tool: trivy
+AnnotationDate: 2023-01-01T00:00:00Z
+AnnotationType: OTHER
+SPDXREF: SPDXRef-pkg-b
+AnnotationComment: <text>PkgType: npm</text>
+";
+        let sbom = SpdxReader::read_tag_value(tv.as_bytes()).unwrap();
+        let find = |name: &str| sbom.components.values().find(|c| c.name == name).unwrap();
+        assert!(find("pkg-a").properties.is_empty());
+        assert_eq!(
+            find("pkg-b").properties["PkgType"],
+            BTreeSet::from(["npm".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_read_tag_value_copyright_text() {
+        let tv = "\
+SPDXVersion: SPDX-2.3
+DataLicense: CC0-1.0
+SPDXID: SPDXRef-DOCUMENT
+DocumentName: test
+DocumentNamespace: http://spdx.org/spdxdocs/test
+Creator: Tool: manual
+Created: 2023-01-01T00:00:00Z
+
+PackageName: pkg-a
+SPDXID: SPDXRef-pkg-a
+PackageDownloadLocation: NOASSERTION
+PackageCopyrightText: <text>Copyright 2019 Jane Doe
+Copyright 2021 Acme Corp</text>
+";
+        let sbom = SpdxReader::read_tag_value(tv.as_bytes()).unwrap();
+        assert_eq!(
+            sbom.components[0].copyright.as_deref(),
+            Some("Copyright 2019 Jane Doe\nCopyright 2021 Acme Corp")
+        );
     }
 
     #[test]
@@ -1099,6 +1823,190 @@ mod tests {
         assert!(!dep_names.contains("generated-from"));
     }
 
+    #[test]
+    fn test_document_identity_parsed() {
+        let json = r#"{
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "my-app-2.0.0",
+            "documentNamespace": "https://example.com/spdxdocs/my-app-2.0.0-1234",
+            "creationInfo": {
+                "creators": ["Tool: manual"],
+                "created": "2023-01-01T00:00:00Z"
+            },
+            "packages": [
+                {
+                    "name": "my-app",
+                    "SPDXID": "SPDXRef-app",
+                    "versionInfo": "2.0.0",
+                    "downloadLocation": "NONE"
+                },
+                {
+                    "name": "lib",
+                    "SPDXID": "SPDXRef-lib",
+                    "downloadLocation": "NONE"
+                }
+            ],
+            "relationships": [
+                {
+                    "spdxElementId": "SPDXRef-DOCUMENT",
+                    "relatedSpdxElement": "SPDXRef-app",
+                    "relationshipType": "DESCRIBES"
+                },
+                {
+                    "spdxElementId": "SPDXRef-app",
+                    "relatedSpdxElement": "SPDXRef-lib",
+                    "relationshipType": "DEPENDS_ON"
+                }
+            ]
+        }"#;
+        let sbom = SpdxReader::read_json(json.as_bytes()).unwrap();
+        let meta = &sbom.metadata;
+        assert_eq!(meta.format.as_deref(), Some("spdx"));
+        assert_eq!(meta.spec_version.as_deref(), Some("SPDX-2.3"));
+        assert_eq!(meta.name.as_deref(), Some("my-app-2.0.0"));
+        assert_eq!(
+            meta.namespace.as_deref(),
+            Some("https://example.com/spdxdocs/my-app-2.0.0-1234")
+        );
+        let primary = meta.primary_component.as_ref().unwrap();
+        assert_eq!(sbom.components[primary].name, "my-app");
+
+        let streamed = SpdxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed, sbom);
+
+        // `documentDescribes` and `DESCRIBED_BY` name it just as well.
+        let json = json
+            .replace(
+                r#""documentNamespace""#,
+                r#""documentDescribes": ["SPDXRef-app"], "documentNamespace""#,
+            )
+            .replace(
+                r#""spdxElementId": "SPDXRef-DOCUMENT",
+                    "relatedSpdxElement": "SPDXRef-app",
+                    "relationshipType": "DESCRIBES""#,
+                r#""spdxElementId": "SPDXRef-app",
+                    "relatedSpdxElement": "SPDXRef-DOCUMENT",
+                    "relationshipType": "DESCRIBED_BY""#,
+            );
+        let sbom = SpdxReader::read_json(json.as_bytes()).unwrap();
+        assert_eq!(sbom.metadata.primary_component.as_ref(), Some(primary));
+    }
+
+    #[test]
+    fn test_describing_several_packages_has_no_primary_component() {
+        let json = r#"{
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "test",
+            "documentNamespace": "http://spdx.org/spdxdocs/test",
+            "documentDescribes": ["SPDXRef-a", "SPDXRef-b"],
+            "creationInfo": {
+                "creators": ["Tool: manual"],
+                "created": "2023-01-01T00:00:00Z"
+            },
+            "packages": [
+                { "name": "a", "SPDXID": "SPDXRef-a", "downloadLocation": "NONE" },
+                { "name": "b", "SPDXID": "SPDXRef-b", "downloadLocation": "NONE" }
+            ]
+        }"#;
+        let sbom = SpdxReader::read_json(json.as_bytes()).unwrap();
+        assert_eq!(sbom.metadata.primary_component, None);
+        assert_eq!(sbom.metadata.name.as_deref(), Some("test"));
+
+        let streamed = SpdxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed, sbom);
+    }
+
+    #[test]
+    fn test_files_and_snippets_parsed() {
+        let data = std::fs::read("../../tests/fixtures/firmware-old.spdx.json").unwrap();
+        let sbom = SpdxReader::read_json(data.as_slice()).unwrap();
+        let package = |path: &str| {
+            let file = sbom.files.iter().find(|f| f.path == path).unwrap();
+            sbom.components[file.package.as_ref().unwrap()].name.clone()
+        };
+        assert_eq!(sbom.files.len(), 4);
+        // placed by a CONTAINS relationship
+        assert_eq!(package("/bin/busybox"), "busybox");
+        // placed by the package's hasFiles
+        assert_eq!(package("/etc/inittab"), "base-files");
+        assert_eq!(package("/bin/busybox#bytes=4096-8191"), "busybox");
+
+        let busybox = &sbom.files[0];
+        assert_eq!(busybox.hashes["SHA-256"], "a".repeat(64));
+        assert!(busybox.licenses.contains("GPL-2.0-only"));
+        assert_eq!(busybox.source_ids, vec!["SPDXRef-file-busybox"]);
+        // file relationships are not dependency edges
+        assert!(sbom.dependencies.is_empty());
+        assert!(sbom.warnings.is_empty(), "{:?}", sbom.warnings);
+
+        let streamed = SpdxReader::read_json_streaming(data.as_slice()).unwrap();
+        assert_eq!(streamed, sbom);
+    }
+
+    #[test]
+    fn test_snippet_of_unknown_file_warns() {
+        let json = r#"{
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "test",
+            "documentNamespace": "http://spdx.org/spdxdocs/test",
+            "creationInfo": {"creators": ["Tool: manual"], "created": "2023-01-01T00:00:00Z"},
+            "snippets": [
+                {
+                    "SPDXID": "SPDXRef-snippet",
+                    "snippetFromFile": "SPDXRef-missing",
+                    "ranges": [{
+                        "startPointer": {"lineNumber": 1},
+                        "endPointer": {"lineNumber": 9}
+                    }]
+                }
+            ]
+        }"#;
+        let sbom = SpdxReader::read_json(json.as_bytes()).unwrap();
+        assert!(sbom.files.is_empty());
+        assert_eq!(sbom.warnings.len(), 1);
+        assert!(sbom.warnings[0].contains("SPDXRef-missing"));
+        let streamed = SpdxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed, sbom);
+    }
+
+    #[test]
+    fn test_files_listed_before_packages_stream_alike() {
+        let json = r#"{
+            "relationships": [
+                {"spdxElementId": "SPDXRef-file", "relatedSpdxElement": "SPDXRef-app", "relationshipType": "CONTAINED_BY"}
+            ],
+            "files": [
+                {
+                    "fileName": "./bin/app",
+                    "SPDXID": "SPDXRef-file",
+                    "checksums": [{"algorithm": "SHA1", "checksumValue": "ABCDEF"}]
+                }
+            ],
+            "packages": [
+                {"name": "app", "SPDXID": "SPDXRef-app", "downloadLocation": "NONE"}
+            ],
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "test",
+            "documentNamespace": "http://spdx.org/spdxdocs/test",
+            "creationInfo": {"creators": ["Tool: manual"], "created": "2023-01-01T00:00:00Z"}
+        }"#;
+        let sbom = SpdxReader::read_json(json.as_bytes()).unwrap();
+        let file = &sbom.files[0];
+        assert_eq!(sbom.components[file.package.as_ref().unwrap()].name, "app");
+        assert_eq!(file.hashes["SHA-1"], "ABCDEF");
+        assert!(sbom.warnings.is_empty(), "{:?}", sbom.warnings);
+        let streamed = SpdxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed, sbom);
+    }
+
     #[test]
     fn test_empty_dependency_graph() {
         let json = r#"{
@@ -1274,14 +2182,31 @@ mod tests {
         // both NOASSERTION -> no licenses
         assert!(find("both-noassertion").licenses.is_empty());
 
-        // valid concluded -> uses concluded, ignores declared
+        // valid concluded -> uses concluded, keeps declared apart
         // spdx preserves the canonical id (GPL-3.0-only), not the deprecated
         // short form (GPL-3.0)
-        assert!(find("concluded-present").licenses.contains("GPL-3.0-only"));
-        assert!(!find("concluded-present").licenses.contains("MIT"));
+        let present = find("concluded-present");
+        assert!(present.licenses.contains("GPL-3.0-only"));
+        assert!(!present.licenses.contains("MIT"));
+        assert_eq!(present.declared_licenses, BTreeSet::from(["MIT".into()]));
+        assert_eq!(present.declared_license_expression.as_deref(), Some("MIT"));
+
+        // the declared license is kept whether or not it was also used
+        assert_eq!(
+            find("concluded-noassertion")
+                .declared_license_expression
+                .as_deref(),
+            Some("MIT")
+        );
+        assert!(find("both-noassertion").declared_licenses.is_empty());
+        assert_eq!(find("both-noassertion").declared_license_expression, None);
 
         // no license fields at all -> empty
         assert!(find("no-license-fields").licenses.is_empty());
+        assert!(find("no-license-fields").declared_licenses.is_empty());
+
+        let streamed = SpdxReader::read_json_streaming(json.as_bytes()).unwrap();
+        assert_eq!(streamed.components, sbom.components);
     }
 
     #[test]
@@ -1804,6 +2729,97 @@ PackageCopyrightText: NOASSERTION
         assert_eq!(comp.name, "pkg-a");
         assert_eq!(comp.version, Some("1.0.0".to_string()));
         assert_eq!(sbom.metadata.tools, vec!["manual"]);
+        assert_eq!(sbom.metadata.format.as_deref(), Some("spdx"));
+        assert_eq!(sbom.metadata.spec_version.as_deref(), Some("SPDX-2.3"));
+        assert_eq!(sbom.metadata.name.as_deref(), Some("test"));
+        assert_eq!(
+            sbom.metadata.namespace.as_deref(),
+            Some("http://spdx.org/spdxdocs/test")
+        );
+    }
+
+    #[test]
+    fn test_read_tag_value_files_and_snippets() {
+        let tv = "\
+SPDXVersion: SPDX-2.3
+DataLicense: CC0-1.0
+SPDXID: SPDXRef-DOCUMENT
+DocumentName: test
+DocumentNamespace: http://spdx.org/spdxdocs/test
+Creator: Tool: manual
+Created: 2023-01-01T00:00:00Z
+
+PackageName: pkg-a
+SPDXID: SPDXRef-pkg-a
+PackageDownloadLocation: NOASSERTION
+
+FileName: ./a.c
+SPDXID: SPDXRef-file-a
+FileChecksum: SHA256: 00ff
+LicenseConcluded: MIT
+LicenseInfoInFile: MIT
+FileCopyrightText: NOASSERTION
+
+SnippetSPDXID: SPDXRef-snippet-a
+SnippetFromFileSPDXID: SPDXRef-file-a
+SnippetLineRange: 10:20
+SnippetLicenseConcluded: Apache-2.0
+";
+        let sbom = SpdxReader::read_tag_value(tv.as_bytes()).unwrap();
+        let paths: Vec<_> = sbom.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["./a.c", "./a.c#lines=10-20"]);
+        for file in &sbom.files {
+            assert_eq!(
+                sbom.components[file.package.as_ref().unwrap()].name,
+                "pkg-a"
+            );
+        }
+        assert_eq!(sbom.files[0].hashes["SHA-256"], "00ff");
+        assert!(sbom.files[0].licenses.contains("MIT"));
+        assert!(sbom.files[1].licenses.contains("Apache-2.0"));
+    }
+
+    #[test]
+    fn test_read_tag_value_package_purpose() {
+        let tv = "\
+SPDXVersion: SPDX-2.3
+DataLicense: CC0-1.0
+SPDXID: SPDXRef-DOCUMENT
+DocumentName: test
+DocumentNamespace: http://spdx.org/spdxdocs/test
+Creator: Tool: manual
+Created: 2023-01-01T00:00:00Z
+
+PackageName: debian
+SPDXID: SPDXRef-debian
+PackageDownloadLocation: NOASSERTION
+PrimaryPackagePurpose: OPERATING-SYSTEM
+
+PackageName: pkg-a
+SPDXID: SPDXRef-pkg-a
+PackageDownloadLocation: NOASSERTION
+
+FileName: ./a.txt
+SPDXID: SPDXRef-file-a
+FileChecksum: SHA1: 0000000000000000000000000000000000000000
+
+PackageName: pkg-b
+SPDXID: SPDXRef-pkg-b
+PackageDownloadLocation: NOASSERTION
+PrimaryPackagePurpose: LIBRARY
+";
+        let sbom = SpdxReader::read_tag_value(tv.as_bytes()).unwrap();
+        let kind = |name: &str| {
+            sbom.components
+                .values()
+                .find(|c| c.name == name)
+                .unwrap_or_else(|| panic!("{name} missing"))
+                .kind
+                .as_deref()
+        };
+        assert_eq!(kind("debian"), Some("operating-system"));
+        assert_eq!(kind("pkg-a"), None);
+        assert_eq!(kind("pkg-b"), Some("library"));
     }
 
     #[test]
@@ -3046,4 +4062,74 @@ PackageCopyrightText: NOASSERTION
         );
         assert_eq!(find("unlicensed").license_expression, None);
     }
+
+    #[test]
+    fn test_package_purpose_becomes_kind() {
+        // the spec spells the operating-system purpose with a hyphen, which
+        // spdx-rs 0.5 rejects on its own.
+        let json = br#"{
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "test",
+            "documentNamespace": "http://example.com/test",
+            "creationInfo": { "creators": ["Tool: test"], "created": "2024-01-01T00:00:00Z" },
+            "packages": [
+                {
+                    "name": "debian",
+                    "SPDXID": "SPDXRef-debian",
+                    "downloadLocation": "NOASSERTION",
+                    "primaryPackagePurpose": "OPERATING-SYSTEM"
+                },
+                {
+                    "name": "libc6",
+                    "SPDXID": "SPDXRef-libc6",
+                    "downloadLocation": "NOASSERTION",
+                    "primaryPackagePurpose": "LIBRARY"
+                },
+                {
+                    "name": "untyped",
+                    "SPDXID": "SPDXRef-untyped",
+                    "downloadLocation": "NOASSERTION"
+                }
+            ]
+        }"#;
+
+        let sbom = SpdxReader::read_json(&json[..]).unwrap();
+        let kind = |name: &str| {
+            sbom.components
+                .values()
+                .find(|c| c.name == name)
+                .unwrap_or_else(|| panic!("{name} missing"))
+                .kind
+                .as_deref()
+        };
+        assert_eq!(kind("debian"), Some("operating-system"));
+        assert_eq!(kind("libc6"), Some("library"));
+        assert_eq!(kind("untyped"), None);
+        assert_eq!(SpdxReader::read_json_streaming(&json[..]).unwrap(), sbom);
+    }
+
+    #[test]
+    fn test_read_xml_package_purpose() {
+        let xml = r#"<Document>
+  <spdxVersion>SPDX-2.3</spdxVersion>
+  <dataLicense>CC0-1.0</dataLicense>
+  <SPDXID>SPDXRef-DOCUMENT</SPDXID>
+  <name>test</name>
+  <documentNamespace>http://spdx.org/spdxdocs/test</documentNamespace>
+  <creationInfo>
+    <creators>Tool: manual</creators>
+    <created>2023-01-01T00:00:00Z</created>
+  </creationInfo>
+  <packages>
+    <name>debian</name>
+    <SPDXID>SPDXRef-debian</SPDXID>
+    <downloadLocation>NONE</downloadLocation>
+    <primaryPackagePurpose>OPERATING-SYSTEM</primaryPackagePurpose>
+  </packages>
+</Document>"#;
+        let sbom = SpdxReader::read_xml(xml.as_bytes()).unwrap();
+        assert_eq!(sbom.components[0].kind.as_deref(), Some("operating-system"));
+    }
 }
@@ -0,0 +1,346 @@
+//! streaming JSON reader.
+//!
+//! [`read`] walks the document with a `serde_json` deserializer and maps each
+//! package, file and relationship into the [`Sbom`] as soon as it has been
+//! read, instead of materializing the whole `spdx_rs::models::SPDX` document
+//! that [`SpdxReader::read_json`] converts from. Packages, files and
+//! relationships are still deserialized as `spdx-rs` types, one at a time, so
+//! both paths share one mapping.
+
+use crate::{Error, FileLayer, SpdxReader};
+use sbom_model::{ComponentId, Sbom};
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use spdx_rs::models::{CreationInfo, FileInformation, PackageInformation, Relationship};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read};
+
+/// parses an SPDX JSON document from `reader` without building the
+/// intermediate document model.
+pub(crate) fn read<R: Read>(reader: R) -> Result<Sbom, Error> {
+    let mut reader = BufReader::new(reader);
+    // strip a leading UTF-8 BOM; serde_json does not skip it.
+    if reader.fill_buf()?.starts_with(b"\xef\xbb\xbf") {
+        reader.consume(3);
+    }
+
+    let mut state = State::default();
+    let mut de = serde_json::Deserializer::from_reader(reader);
+    let parsed = DocumentSeed(&mut state)
+        .deserialize(&mut de)
+        .and_then(|()| de.end());
+    if let Some(version) = state.unsupported {
+        return Err(Error::UnsupportedVersion { version });
+    }
+    parsed?;
+    state.finish()
+}
+
+/// what has been mapped so far, plus the bookkeeping needed to resolve
+/// relationship SPDX IDs.
+#[derive(Default)]
+struct State {
+    sbom: Sbom,
+    unsupported: Option<String>,
+    seen_creation_info: bool,
+    seen_packages: bool,
+    seen_files: bool,
+    doc_spdx_id: Option<String>,
+    version: Option<String>,
+    name: Option<String>,
+    namespace: Option<String>,
+    /// SPDX IDs of the elements the document says it describes.
+    described: BTreeSet<String>,
+    ref_map: Option<BTreeMap<String, ComponentId>>,
+    files: FileLayer,
+    /// relationships read before every package, or the document's own
+    /// SPDX ID, was, and ones that may involve a file not read yet.
+    pending: Vec<Relationship>,
+}
+
+impl State {
+    fn add_relationship(&mut self, rel: Relationship) {
+        let Some(doc_spdx_id) = self.doc_spdx_id.as_deref().filter(|_| self.seen_packages) else {
+            self.pending.push(rel);
+            return;
+        };
+        let ref_map = self
+            .ref_map
+            .get_or_insert_with(|| SpdxReader::ref_map(&self.sbom));
+        let known = |id: &str| {
+            id == doc_spdx_id || ref_map.contains_key(id) || self.files.index.contains_key(id)
+        };
+        let resolvable = known(&rel.spdx_element_id) && known(&rel.related_spdx_element);
+        if !self.seen_files && !resolvable {
+            self.pending.push(rel);
+            return;
+        }
+        self.described
+            .extend(crate::described_element(&rel, doc_spdx_id).map(str::to_string));
+        SpdxReader::add_relationship(&mut self.sbom, ref_map, &mut self.files, doc_spdx_id, rel);
+    }
+
+    fn finish(mut self) -> Result<Sbom, Error> {
+        use serde::de::Error as _;
+        if !self.seen_creation_info {
+            return Err(serde_json::Error::missing_field("creationInfo").into());
+        }
+        if self.doc_spdx_id.is_none() {
+            return Err(serde_json::Error::missing_field("SPDXID").into());
+        }
+        self.seen_packages = true;
+        self.seen_files = true;
+        for rel in std::mem::take(&mut self.pending) {
+            self.add_relationship(rel);
+        }
+        SpdxReader::add_document_identity(
+            &mut self.sbom,
+            self.version.unwrap_or_default(),
+            self.name.unwrap_or_default(),
+            self.namespace.unwrap_or_default(),
+        );
+        let ref_map = self
+            .ref_map
+            .unwrap_or_else(|| SpdxReader::ref_map(&self.sbom));
+        SpdxReader::resolve_files(&mut self.sbom, &ref_map, self.files);
+        SpdxReader::set_primary_component(&mut self.sbom, &ref_map, &self.described);
+        self.sbom.rebuild_reverse_deps();
+        Ok(self.sbom)
+    }
+}
+
+struct DocumentSeed<'a>(&'a mut State);
+
+impl<'de> DeserializeSeed<'de> for DocumentSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DocumentSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an SPDX JSON document")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "spdxVersion" => {
+                    let version: String = map.next_value()?;
+                    if SpdxReader::check_version(Some(&version)).is_err() {
+                        let err = de::Error::custom(format!("unsupported spdxVersion '{version}'"));
+                        self.0.unsupported = Some(version);
+                        return Err(err);
+                    }
+                    self.0.version = Some(version);
+                }
+                "name" => self.0.name = Some(map.next_value()?),
+                "documentNamespace" => self.0.namespace = Some(map.next_value()?),
+                "documentDescribes" => {
+                    let described: Vec<String> = map.next_value()?;
+                    self.0.described.extend(described);
+                }
+                "creationInfo" => {
+                    let ci: CreationInfo = map.next_value()?;
+                    SpdxReader::add_creation_info(&mut self.0.sbom, ci);
+                    self.0.seen_creation_info = true;
+                }
+                "SPDXID" => self.0.doc_spdx_id = Some(map.next_value()?),
+                "packages" => {
+                    map.next_value_seed(PackagesSeed(self.0))?;
+                    self.0.seen_packages = true;
+                }
+                "files" => {
+                    map.next_value_seed(FilesSeed(self.0))?;
+                    self.0.seen_files = true;
+                }
+                "snippets" => self.0.files.snippets = map.next_value()?,
+                "relationships" => map.next_value_seed(RelationshipsSeed(self.0))?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// maps each package as soon as it has been read.
+struct PackagesSeed<'a>(&'a mut State);
+
+impl<'de> DeserializeSeed<'de> for PackagesSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for PackagesSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of packages")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        // each package goes through a `Value` so its purpose can be
+        // respelled for spdx-rs, as `read_json` does for the whole document.
+        while let Some(mut pkg) = seq.next_element::<serde_json::Value>()? {
+            crate::respell_package_purpose(&mut pkg);
+            let pkg = PackageInformation::deserialize(pkg).map_err(de::Error::custom)?;
+            self.0.files.add_package_files(&pkg);
+            SpdxReader::add_package(&mut self.0.sbom, pkg);
+        }
+        Ok(())
+    }
+}
+
+/// maps each file as soon as it has been read.
+struct FilesSeed<'a>(&'a mut State);
+
+impl<'de> DeserializeSeed<'de> for FilesSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FilesSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of files")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(file) = seq.next_element::<FileInformation>()? {
+            SpdxReader::add_file(&mut self.0.sbom, &mut self.0.files, file);
+        }
+        Ok(())
+    }
+}
+
+/// maps each relationship as soon as it has been read.
+struct RelationshipsSeed<'a>(&'a mut State);
+
+impl<'de> DeserializeSeed<'de> for RelationshipsSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for RelationshipsSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of relationships")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(rel) = seq.next_element::<Relationship>()? {
+            self.0.add_relationship(rel);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_read_json_on_fixtures() {
+        let mut compared = 0;
+        for entry in std::fs::read_dir("../../tests/fixtures").unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let data = std::fs::read(&path).unwrap();
+            let Ok(expected) = SpdxReader::read_json(data.as_slice()) else {
+                continue;
+            };
+            let streamed =
+                read(data.as_slice()).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+            assert_eq!(streamed, expected, "{}", path.display());
+            let order = |s: &Sbom| s.components.keys().cloned().collect::<Vec<_>>();
+            assert_eq!(order(&streamed), order(&expected), "{}", path.display());
+            compared += 1;
+        }
+        assert!(compared > 5, "only {compared} fixtures compared");
+    }
+
+    #[test]
+    fn test_relationships_before_packages() {
+        let json = r#"{
+            "relationships": [
+                {"spdxElementId": "SPDXRef-DOCUMENT", "relatedSpdxElement": "SPDXRef-app", "relationshipType": "DESCRIBES"},
+                {"spdxElementId": "SPDXRef-app", "relatedSpdxElement": "SPDXRef-lib", "relationshipType": "DEPENDS_ON"},
+                {"spdxElementId": "SPDXRef-app", "relatedSpdxElement": "SPDXRef-missing", "relationshipType": "DEPENDS_ON"}
+            ],
+            "packages": [
+                {"name": "app", "SPDXID": "SPDXRef-app", "downloadLocation": "NONE"},
+                {"name": "lib", "SPDXID": "SPDXRef-lib", "downloadLocation": "NONE"}
+            ],
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "test",
+            "documentNamespace": "http://spdx.org/spdxdocs/test",
+            "creationInfo": {"creators": ["Tool: manual"], "created": "2023-01-01T00:00:00Z"}
+        }"#;
+        let sbom = read(json.as_bytes()).unwrap();
+        let app = &sbom
+            .components
+            .values()
+            .find(|c| c.name == "app")
+            .unwrap()
+            .id;
+        let lib = &sbom
+            .components
+            .values()
+            .find(|c| c.name == "lib")
+            .unwrap()
+            .id;
+        assert!(sbom.dependencies[app].contains_key(lib));
+        assert_eq!(sbom.warnings.len(), 1);
+        assert!(sbom.warnings[0].contains("SPDXRef-missing"));
+        assert_eq!(sbom.metadata.tools, vec!["manual".to_string()]);
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let json = r#"{"spdxVersion": "SPDX-3.0", "packages": []}"#;
+        let err = read(json.as_bytes()).unwrap_err();
+        assert!(
+            matches!(err, Error::UnsupportedVersion { ref version } if version == "SPDX-3.0"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_missing_creation_info() {
+        let json = r#"{"spdxVersion": "SPDX-2.3", "SPDXID": "SPDXRef-DOCUMENT", "packages": []}"#;
+        let err = read(json.as_bytes()).unwrap_err();
+        assert!(matches!(err, Error::Parse(_)), "{err}");
+        assert!(err.to_string().contains("creationInfo"), "{err}");
+    }
+
+    #[test]
+    fn test_utf8_bom_stripped() {
+        let mut json = b"\xef\xbb\xbf".to_vec();
+        json.extend_from_slice(
+            br#"{"SPDXID": "SPDXRef-DOCUMENT", "creationInfo": {"creators": [], "created": "2023-01-01T00:00:00Z"}}"#,
+        );
+        assert!(read(json.as_slice()).unwrap().components.is_empty());
+    }
+}
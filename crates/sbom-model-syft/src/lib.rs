@@ -0,0 +1,620 @@
+#![doc = include_str!("../readme.md")]
+
+use sbom_model::{
+    canonical_algorithm_name, parse_license_expression, Component, ComponentId, DependencyKind,
+    Sbom,
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+use thiserror::Error;
+
+/// errors that can occur when parsing Syft JSON documents.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// the JSON structure doesn't match the Syft schema.
+    #[error("Syft JSON parse error: {0}")]
+    Parse(#[from] serde_json::Error),
+    /// an I/O error occurred while reading the input.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// the subset of a Syft JSON document that maps onto [`Sbom`].
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    artifacts: Vec<Artifact>,
+    #[serde(default)]
+    artifact_relationships: Vec<Relationship>,
+    descriptor: Option<Descriptor>,
+    schema: Option<Schema>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Artifact {
+    id: String,
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    purl: String,
+    #[serde(default)]
+    licenses: Vec<License>,
+    #[serde(default)]
+    cpes: Vec<Cpe>,
+    #[serde(default, rename = "type")]
+    package_type: String,
+    #[serde(default, rename = "foundBy")]
+    found_by: String,
+    #[serde(default)]
+    language: String,
+    metadata: Option<serde_json::Value>,
+}
+
+/// schema 12 and later record cpes as objects naming their source; older
+/// schemas as strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Cpe {
+    Object { cpe: String },
+    Plain(String),
+}
+
+impl Cpe {
+    fn as_str(&self) -> &str {
+        match self {
+            Cpe::Object { cpe } | Cpe::Plain(cpe) => cpe,
+        }
+    }
+}
+
+/// schema 10 and later record licenses as objects; older schemas as strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum License {
+    Object {
+        #[serde(default)]
+        value: String,
+        #[serde(default, rename = "spdxExpression")]
+        spdx_expression: String,
+        #[serde(default, rename = "type")]
+        license_type: String,
+    },
+    Plain(String),
+}
+
+impl License {
+    /// the SPDX expression when syft resolved one, otherwise the raw value.
+    fn expression(&self) -> &str {
+        match self {
+            License::Object {
+                value,
+                spdx_expression,
+                ..
+            } => {
+                if spdx_expression.is_empty() {
+                    value
+                } else {
+                    spdx_expression
+                }
+            }
+            License::Plain(value) => value,
+        }
+    }
+
+    /// whether syft found this license declared by the package itself.
+    fn is_declared(&self) -> bool {
+        matches!(self, License::Object { license_type, .. } if license_type == "declared")
+    }
+}
+
+/// flattens licenses into an identifier set, plus the expression when there
+/// is a single one: several are separate findings (e.g. from different files)
+/// with no stated operator.
+fn license_fields(licenses: &[&License]) -> (BTreeSet<String>, Option<String>) {
+    let expressions: Vec<&str> = licenses
+        .iter()
+        .map(|l| l.expression())
+        .filter(|l| !l.is_empty())
+        .collect();
+    let ids = expressions
+        .iter()
+        .flat_map(|l| parse_license_expression(l))
+        .collect();
+    let expression = match expressions.as_slice() {
+        [only] => Some(only.to_string()),
+        _ => None,
+    };
+    (ids, expression)
+}
+
+#[derive(Deserialize)]
+struct Relationship {
+    parent: String,
+    child: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+struct Descriptor {
+    name: String,
+    #[serde(default)]
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct Schema {
+    version: Option<String>,
+}
+
+/// parser for Syft's native JSON output.
+///
+/// converts the `artifacts` and `artifactRelationships` of `syft -o json`
+/// into the format-agnostic [`Sbom`] type.
+pub struct SyftReader;
+
+impl SyftReader {
+    /// parses a Syft JSON document from a reader.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use sbom_model_syft::SyftReader;
+    /// use std::fs::File;
+    ///
+    /// let file = File::open("sbom.syft.json").unwrap();
+    /// let sbom = SyftReader::read_json(file).unwrap();
+    /// ```
+    pub fn read_json<R: Read>(mut reader: R) -> Result<Sbom, Error> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        // strip a leading UTF-8 BOM; serde_json does not skip it.
+        let buf = buf.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&buf);
+
+        let doc: Document = serde_json::from_slice(buf)?;
+        Ok(Self::syft_to_sbom(doc))
+    }
+
+    fn syft_to_sbom(doc: Document) -> Sbom {
+        let mut sbom = Sbom::default();
+        sbom.metadata.format = Some("syft".to_string());
+        sbom.metadata.spec_version = doc.schema.and_then(|schema| schema.version);
+
+        if let Some(descriptor) = doc.descriptor {
+            let tool = format!("{} {}", descriptor.name, descriptor.version);
+            sbom.metadata.tools.push(tool.trim().to_string());
+        }
+
+        for artifact in doc.artifacts {
+            let name = artifact.name;
+            let version = Some(artifact.version).filter(|v| !v.is_empty());
+            let purl = Some(artifact.purl).filter(|p| !p.is_empty());
+
+            let mut props = vec![("name", name.as_str())];
+            if let Some(ref v) = version {
+                props.push(("version", v.as_str()));
+            }
+            let id = ComponentId::new(purl.as_deref(), &props);
+            let ecosystem = purl.as_deref().and_then(sbom_model::ecosystem_from_purl);
+
+            let mut comp = Component {
+                id: id.clone(),
+                name,
+                version,
+                ecosystem,
+                // syft's artifact `type` is a package type, not what the
+                // component is.
+                kind: None,
+                supplier: None,
+                // syft records no copyright notices.
+                copyright: None,
+                description: None,
+                purl,
+                cpes: artifact
+                    .cpes
+                    .iter()
+                    .map(|cpe| cpe.as_str().trim())
+                    .filter(|cpe| !cpe.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                // syft's artifacts carry no external references.
+                external_refs: BTreeMap::new(),
+                licenses: BTreeSet::new(),
+                license_expression: None,
+                declared_licenses: BTreeSet::new(),
+                declared_license_expression: None,
+                hashes: BTreeMap::new(),
+                // named as syft names them in its CycloneDX output, so the
+                // two agree when diffed against each other.
+                properties: [
+                    ("syft:package:foundBy", artifact.found_by),
+                    ("syft:package:language", artifact.language),
+                    ("syft:package:type", artifact.package_type),
+                ]
+                .into_iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(name, value)| (name.to_string(), BTreeSet::from([value])))
+                .collect(),
+                source_ids: vec![artifact.id],
+            };
+
+            let (declared, concluded): (Vec<&License>, Vec<&License>) =
+                artifact.licenses.iter().partition(|l| l.is_declared());
+            (comp.declared_licenses, comp.declared_license_expression) = license_fields(&declared);
+            // licenses of other types (concluded, or none on older schemas)
+            // are the concluded set, falling back to the declared ones.
+            (comp.licenses, comp.license_expression) = if concluded.is_empty() {
+                (
+                    comp.declared_licenses.clone(),
+                    comp.declared_license_expression.clone(),
+                )
+            } else {
+                license_fields(&concluded)
+            };
+
+            // java archives carry the digests syft computed for the jar itself.
+            let digests = artifact
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("digest"))
+                .and_then(|d| d.as_array());
+            for digest in digests.into_iter().flatten() {
+                let algorithm = digest.get("algorithm").and_then(|a| a.as_str());
+                let value = digest.get("value").and_then(|v| v.as_str());
+                if let (Some(algorithm), Some(value)) = (algorithm, value) {
+                    comp.hashes
+                        .insert(canonical_algorithm_name(algorithm), value.to_string());
+                }
+            }
+
+            if let Some(existing) = sbom.components.get(&id) {
+                sbom.warnings.push(format!(
+                    "Syft: duplicate component id '{}' (name '{}'); \
+                     earlier entry '{}' will be overwritten",
+                    id, comp.name, existing.name,
+                ));
+                // keep the earlier artifact id resolvable for relationships.
+                comp.source_ids.splice(0..0, existing.source_ids.clone());
+            }
+            sbom.components.insert(id, comp);
+        }
+
+        // map artifact ids -> ComponentId
+        let mut ref_map = BTreeMap::new();
+        for (id, comp) in &sbom.components {
+            for src_id in &comp.source_ids {
+                ref_map.insert(src_id.clone(), id.clone());
+            }
+        }
+
+        for rel in doc.artifact_relationships {
+            // `dependency-of`: the parent is a dependency of the child.
+            // `contains`: the parent contains the child.
+            let (from, to) = match rel.kind.as_str() {
+                "dependency-of" => (&rel.child, &rel.parent),
+                "contains" => (&rel.parent, &rel.child),
+                // evident-by, ownership-by-file-overlap, description-of, ...
+                _ => continue,
+            };
+
+            match (ref_map.get(from), ref_map.get(to)) {
+                (Some(pid), Some(cid)) => {
+                    let edges = sbom.dependencies.entry(pid.clone()).or_default();
+                    // a `dependency-of` restating a containment wins over it.
+                    if rel.kind == "contains" {
+                        edges.entry(cid.clone()).or_insert(DependencyKind::Contains);
+                    } else {
+                        edges.insert(cid.clone(), DependencyKind::Runtime);
+                    }
+                }
+                // packages routinely contain files, which are not artifacts.
+                _ if rel.kind == "contains" => {}
+                (None, _) => {
+                    sbom.warnings.push(format!(
+                        "Syft: relationship source '{}' does not match any artifact",
+                        from
+                    ));
+                }
+                (_, None) => {
+                    sbom.warnings.push(format!(
+                        "Syft: relationship target '{}' (from '{}') does not match any artifact",
+                        to, from
+                    ));
+                }
+            }
+        }
+
+        sbom.rebuild_reverse_deps();
+        sbom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_minimal_json() {
+        let json = r#"{
+            "artifacts": [
+                { "id": "a1", "name": "pkg-a", "version": "1.0.0", "type": "npm" }
+            ],
+            "artifactRelationships": []
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        assert_eq!(sbom.components.len(), 1);
+        assert_eq!(sbom.components[0].name, "pkg-a");
+        assert_eq!(sbom.components[0].source_ids, vec!["a1".to_string()]);
+        assert!(sbom.components[0].id.as_str().starts_with("h:"));
+    }
+
+    #[test]
+    fn test_purl_ecosystem_and_descriptor() {
+        let json = r#"{
+            "artifacts": [
+                {
+                    "id": "a1",
+                    "name": "serde",
+                    "version": "1.0.0",
+                    "purl": "pkg:cargo/serde@1.0.0"
+                }
+            ],
+            "descriptor": { "name": "syft", "version": "1.14.0" },
+            "schema": { "version": "16.0.18", "url": "https://raw.githubusercontent.com/anchore/syft/main/schema/json/schema-16.0.18.json" }
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        let comp = &sbom.components[0];
+        assert_eq!(comp.id.as_str(), "pkg:cargo/serde@1.0.0");
+        assert_eq!(comp.ecosystem.as_deref(), Some("cargo"));
+        assert_eq!(sbom.metadata.tools, vec!["syft 1.14.0".to_string()]);
+        assert_eq!(sbom.metadata.format.as_deref(), Some("syft"));
+        assert_eq!(sbom.metadata.spec_version.as_deref(), Some("16.0.18"));
+        assert!(sbom.metadata.timestamp.is_none());
+    }
+
+    #[test]
+    fn test_empty_version_and_purl_are_absent() {
+        let json = r#"{
+            "artifacts": [{ "id": "a1", "name": "busybox", "version": "", "purl": "" }]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        assert_eq!(sbom.components[0].version, None);
+        assert_eq!(sbom.components[0].purl, None);
+    }
+
+    #[test]
+    fn test_license_object_form() {
+        let json = r#"{
+            "artifacts": [{
+                "id": "a1",
+                "name": "pkg-a",
+                "licenses": [
+                    { "value": "MIT or Apache 2", "spdxExpression": "MIT OR Apache-2.0", "type": "declared" }
+                ]
+            }]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        let comp = &sbom.components[0];
+        assert_eq!(
+            comp.license_expression.as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+        assert!(comp.licenses.contains("MIT"));
+        assert!(comp.licenses.contains("Apache-2.0"));
+    }
+
+    #[test]
+    fn test_license_type_separates_declared() {
+        let json = r#"{
+            "artifacts": [{
+                "id": "a1",
+                "name": "pkg-a",
+                "licenses": [
+                    { "value": "MIT", "spdxExpression": "MIT", "type": "declared" },
+                    { "value": "GPL-2.0-only", "spdxExpression": "GPL-2.0-only", "type": "concluded" }
+                ]
+            }]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        let comp = &sbom.components[0];
+        assert_eq!(comp.licenses, BTreeSet::from(["GPL-2.0-only".into()]));
+        assert_eq!(comp.license_expression.as_deref(), Some("GPL-2.0-only"));
+        assert_eq!(comp.declared_licenses, BTreeSet::from(["MIT".into()]));
+        assert_eq!(comp.declared_license_expression.as_deref(), Some("MIT"));
+    }
+
+    #[test]
+    fn test_license_legacy_strings() {
+        let json = r#"{
+            "artifacts": [{ "id": "a1", "name": "pkg-a", "licenses": ["MIT", "BSD-3-Clause"] }]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        let comp = &sbom.components[0];
+        assert_eq!(comp.licenses.len(), 2);
+        // several findings carry no operator, so no expression is claimed.
+        assert_eq!(comp.license_expression, None);
+    }
+
+    #[test]
+    fn test_license_without_spdx_expression_uses_value() {
+        let json = r#"{
+            "artifacts": [{
+                "id": "a1",
+                "name": "pkg-a",
+                "licenses": [{ "value": "GPL-2.0-only", "spdxExpression": "" }]
+            }]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        assert_eq!(
+            sbom.components[0].license_expression.as_deref(),
+            Some("GPL-2.0-only")
+        );
+    }
+
+    #[test]
+    fn test_cpes_object_and_legacy_forms() {
+        let json = r#"{
+            "artifacts": [
+                {
+                    "id": "a1",
+                    "name": "busybox",
+                    "cpes": [
+                        { "cpe": "cpe:2.3:a:busybox:busybox:1.36.1:*:*:*:*:*:*:*", "source": "nvd-cpe-dictionary" },
+                        { "cpe": "", "source": "syft-generated" }
+                    ]
+                },
+                { "id": "a2", "name": "zlib", "cpes": ["cpe:2.3:a:zlib:zlib:1.3:*:*:*:*:*:*:*"] }
+            ]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        let find = |name: &str| sbom.components.values().find(|c| c.name == name).unwrap();
+        assert_eq!(
+            find("busybox").cpes,
+            BTreeSet::from(["cpe:2.3:a:busybox:busybox:1.36.1:*:*:*:*:*:*:*".into()])
+        );
+        assert_eq!(find("zlib").cpes.len(), 1);
+    }
+
+    #[test]
+    fn test_cataloger_type_and_language_become_properties() {
+        let json = r#"{
+            "artifacts": [{
+                "id": "a1",
+                "name": "lodash",
+                "type": "npm",
+                "foundBy": "javascript-lock-cataloger",
+                "language": "javascript"
+            }]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        let props = &sbom.components[0].properties;
+        assert_eq!(
+            props["syft:package:foundBy"],
+            BTreeSet::from(["javascript-lock-cataloger".into()])
+        );
+        assert_eq!(props["syft:package:type"], BTreeSet::from(["npm".into()]));
+        assert_eq!(
+            props["syft:package:language"],
+            BTreeSet::from(["javascript".into()])
+        );
+    }
+
+    #[test]
+    fn test_java_archive_digests() {
+        let json = r#"{
+            "artifacts": [{
+                "id": "a1",
+                "name": "jackson-core",
+                "version": "2.15.2",
+                "type": "java-archive",
+                "metadataType": "java-archive",
+                "metadata": {
+                    "virtualPath": "/app/lib/jackson-core-2.15.2.jar",
+                    "digest": [{ "algorithm": "sha1", "value": "a6fe1836469a69b3ff66037c324d75fc66ef137c" }]
+                }
+            }]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        let hashes = &sbom.components[0].hashes;
+        assert_eq!(
+            hashes
+                .get(&canonical_algorithm_name("sha1"))
+                .map(String::as_str),
+            Some("a6fe1836469a69b3ff66037c324d75fc66ef137c")
+        );
+    }
+
+    #[test]
+    fn test_relationships() {
+        let json = r#"{
+            "artifacts": [
+                { "id": "app", "name": "app", "purl": "pkg:npm/app@1.0.0" },
+                { "id": "lib", "name": "lib", "purl": "pkg:npm/lib@2.0.0" },
+                { "id": "os", "name": "alpine-baselayout", "purl": "pkg:apk/alpine/alpine-baselayout@3.4.0" }
+            ],
+            "artifactRelationships": [
+                { "parent": "lib", "child": "app", "type": "dependency-of" },
+                { "parent": "os", "child": "app", "type": "contains" },
+                { "parent": "app", "child": "file-1234", "type": "contains" },
+                { "parent": "app", "child": "file-5678", "type": "evident-by" }
+            ]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        let app = ComponentId::new(Some("pkg:npm/app@1.0.0"), &[]);
+        let lib = ComponentId::new(Some("pkg:npm/lib@2.0.0"), &[]);
+        let os = ComponentId::new(Some("pkg:apk/alpine/alpine-baselayout@3.4.0"), &[]);
+        assert_eq!(sbom.dependencies[&app][&lib], DependencyKind::Runtime);
+        assert_eq!(sbom.dependencies[&os][&app], DependencyKind::Contains);
+        assert!(sbom.warnings.is_empty(), "{:?}", sbom.warnings);
+    }
+
+    #[test]
+    fn test_dependency_of_wins_over_contains() {
+        let json = r#"{
+            "artifacts": [
+                { "id": "app", "name": "app", "purl": "pkg:npm/app@1.0.0" },
+                { "id": "lib", "name": "lib", "purl": "pkg:npm/lib@2.0.0" }
+            ],
+            "artifactRelationships": [
+                { "parent": "app", "child": "lib", "type": "contains" },
+                { "parent": "lib", "child": "app", "type": "dependency-of" },
+                { "parent": "app", "child": "lib", "type": "contains" }
+            ]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        let app = ComponentId::new(Some("pkg:npm/app@1.0.0"), &[]);
+        let lib = ComponentId::new(Some("pkg:npm/lib@2.0.0"), &[]);
+        assert_eq!(sbom.dependencies[&app][&lib], DependencyKind::Runtime);
+    }
+
+    #[test]
+    fn test_dangling_dependency_warns() {
+        let json = r#"{
+            "artifacts": [{ "id": "app", "name": "app" }],
+            "artifactRelationships": [
+                { "parent": "missing", "child": "app", "type": "dependency-of" }
+            ]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        assert!(sbom.dependencies.is_empty());
+        assert_eq!(sbom.warnings.len(), 1);
+        assert!(sbom.warnings[0].contains("'missing'"));
+    }
+
+    #[test]
+    fn test_duplicate_keeps_both_artifact_ids() {
+        let json = r#"{
+            "artifacts": [
+                { "id": "a1", "name": "lib", "purl": "pkg:npm/lib@1.0.0" },
+                { "id": "a2", "name": "lib", "purl": "pkg:npm/lib@1.0.0" },
+                { "id": "app", "name": "app", "purl": "pkg:npm/app@1.0.0" }
+            ],
+            "artifactRelationships": [
+                { "parent": "a1", "child": "app", "type": "dependency-of" }
+            ]
+        }"#;
+        let sbom = SyftReader::read_json(json.as_bytes()).unwrap();
+        assert_eq!(sbom.components.len(), 2);
+        assert_eq!(sbom.warnings.len(), 1);
+        let app = ComponentId::new(Some("pkg:npm/app@1.0.0"), &[]);
+        assert_eq!(sbom.dependencies[&app].len(), 1);
+    }
+
+    #[test]
+    fn test_missing_artifacts_is_error() {
+        let err = SyftReader::read_json(&b"{\"bomFormat\": \"CycloneDX\"}"[..]).unwrap_err();
+        assert!(matches!(err, Error::Parse(_)));
+    }
+
+    #[test]
+    fn test_utf8_bom_stripped() {
+        let mut json = b"\xef\xbb\xbf".to_vec();
+        json.extend_from_slice(br#"{"artifacts": []}"#);
+        let sbom = SyftReader::read_json(json.as_slice()).unwrap();
+        assert!(sbom.components.is_empty());
+    }
+}
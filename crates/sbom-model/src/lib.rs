@@ -26,7 +26,8 @@ use std::str::FromStr;
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sbom {
-    /// document-level metadata (creation time, tools, authors).
+    /// document-level metadata (creation time, tools, authors, and what the
+    /// document describes).
     pub metadata: Metadata,
     /// all components indexed by their stable identifier.
     pub components: IndexMap<ComponentId, Component>,
@@ -38,6 +39,10 @@ pub struct Sbom {
     /// after modifying `dependencies` to keep it in sync.
     #[serde(skip)]
     pub reverse_deps: BTreeMap<ComponentId, BTreeSet<ComponentId>>,
+    /// files and snippets the document lists, for formats with a file layer
+    /// (SPDX `files` and `snippets`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileEntry>,
     /// non-fatal warnings produced during parsing (e.g. orphaned dependency refs).
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub warnings: Vec<String>,
@@ -48,6 +53,7 @@ impl PartialEq for Sbom {
         self.metadata == other.metadata
             && self.components == other.components
             && self.dependencies == other.dependencies
+            && self.files == other.files
             && self.warnings == other.warnings
     }
 }
@@ -61,6 +67,7 @@ impl Default for Sbom {
             components: IndexMap::new(),
             dependencies: BTreeMap::new(),
             reverse_deps: BTreeMap::new(),
+            files: Vec::new(),
             warnings: Vec::new(),
         }
     }
@@ -68,17 +75,55 @@ impl Default for Sbom {
 
 /// SBOM document metadata.
 ///
-/// contains information about when and how the SBOM was created.
-/// this data is stripped during normalization since it varies between
-/// tool runs and shouldn't affect diff comparisons.
+/// contains information about when and how the SBOM was created, and what
+/// it describes. the parts that vary between tool runs are stripped during
+/// normalization since they shouldn't affect diff comparisons.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct Metadata {
+    /// the format the document was read from (`"cyclonedx"`, `"spdx"`, or
+    /// `"syft"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// the version of that format's specification or schema (e.g. `"1.5"`,
+    /// `"SPDX-2.3"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec_version: Option<String>,
+    /// document name (SPDX `name`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// document namespace (SPDX `documentNamespace`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// serial number (CycloneDX `serialNumber`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+    /// document revision (CycloneDX `version`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bom_version: Option<u32>,
+    /// the component the document describes: the CycloneDX
+    /// `metadata.component`, or the single package an SPDX document
+    /// describes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_component: Option<ComponentId>,
     /// ISO 8601 timestamp of document creation.
     pub timestamp: Option<String>,
     /// tools used to generate the SBOM (e.g., "syft", "trivy").
     pub tools: Vec<String>,
     /// document authors or organizations.
     pub authors: Vec<String>,
+    /// artifacts the SBOM was attested for, when it was read out of an
+    /// in-toto attestation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subjects: Vec<AttestationSubject>,
+}
+
+/// an in-toto statement subject: the artifact an attested SBOM describes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct AttestationSubject {
+    /// artifact name (e.g. an image reference or file name).
+    pub name: String,
+    /// digests of the artifact keyed by algorithm (e.g. "sha256").
+    pub digests: BTreeMap<String, String>,
 }
 
 /// the semantic type of a dependency relationship.
@@ -86,10 +131,14 @@ pub struct Metadata {
 /// SPDX distinguishes between runtime, dev, build, test, optional, and
 /// provided dependencies via typed relationship names. CycloneDX encodes
 /// scope on the component itself (`required` / `optional` / `excluded`),
-/// which is mapped to the appropriate variant when constructing edges.
+/// and generators add dev/test status as properties; both are mapped to the
+/// appropriate variant when constructing edges.
 ///
 /// the default is `Runtime`, which also covers generic relationships
-/// like `DEPENDS_ON` or `CONTAINS` that don't specify a scope.
+/// like `DEPENDS_ON` or SPDX `CONTAINS` that don't specify a scope.
+/// `Contains` marks CycloneDX component nesting and syft `contains`
+/// relationships, where the parent bundles the child rather than depending
+/// on it.
 #[derive(
     Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
 )]
@@ -108,6 +157,9 @@ pub enum DependencyKind {
     Optional,
     /// provided by the runtime environment.
     Provided,
+    /// bundled inside the parent (a nested CycloneDX component, or a syft
+    /// `contains` relationship).
+    Contains,
 }
 
 impl fmt::Display for DependencyKind {
@@ -119,6 +171,7 @@ impl fmt::Display for DependencyKind {
             Self::Test => write!(f, "test"),
             Self::Optional => write!(f, "optional"),
             Self::Provided => write!(f, "provided"),
+            Self::Contains => write!(f, "contains"),
         }
     }
 }
@@ -148,14 +201,12 @@ pub struct ComponentId(String);
 impl ComponentId {
     /// creates a new identifier from a purl or property hash.
     ///
-    /// if a purl is provided, it will be canonicalized. otherwise, a deterministic
-    /// SHA-256 hash is computed from the provided key-value properties.
+    /// if a purl is provided, it will be canonicalized with
+    /// [`canonical_purl`]. otherwise, a deterministic SHA-256 hash is computed
+    /// from the provided key-value properties.
     pub fn new(purl: Option<&str>, properties: &[(&str, &str)]) -> Self {
         if let Some(purl) = purl {
-            if let Ok(parsed) = PackageUrl::from_str(purl) {
-                return ComponentId(parsed.to_string());
-            }
-            return ComponentId(purl.to_string());
+            return ComponentId(canonical_purl(purl).unwrap_or_else(|| purl.to_string()));
         }
 
         // deterministic hash fallback
@@ -182,6 +233,37 @@ impl std::fmt::Display for ComponentId {
     }
 }
 
+/// a file an SBOM lists, or a snippet of one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEntry {
+    /// the path the document gives the file (e.g. `./bin/busybox`). a
+    /// snippet's is its file's path followed by `#bytes=START-END` or
+    /// `#lines=START-END`.
+    pub path: String,
+    /// the package that contains the file, when the document says.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<ComponentId>,
+    /// checksums keyed by algorithm.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hashes: BTreeMap<String, String>,
+    /// license identifiers concluded for (or found in) the file.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub licenses: BTreeSet<String>,
+    /// original identifiers from the source document (e.g. SPDX SPDXRef).
+    pub source_ids: Vec<String>,
+}
+
+impl FileEntry {
+    /// lowercases hash algorithms and values, as [`Component::normalize`]
+    /// does.
+    pub fn normalize(&mut self) {
+        self.hashes = std::mem::take(&mut self.hashes)
+            .into_iter()
+            .map(|(k, v)| (k.to_lowercase(), v.to_lowercase()))
+            .collect();
+    }
+}
+
 /// a software component (package, library, or application).
 ///
 /// represents a single entry in the SBOM with all its metadata.
@@ -197,12 +279,34 @@ pub struct Component {
     pub version: Option<String>,
     /// package ecosystem (e.g., "cargo", "npm", "pypi").
     pub ecosystem: Option<String>,
+    /// what the component is, in CycloneDX component-type vocabulary
+    /// (e.g., "library", "application", "operating-system", "container").
+    ///
+    /// SPDX `primaryPackagePurpose` values are lowercased into the same
+    /// vocabulary, so they also include "source", "archive", "install" and
+    /// "other". `None` when the source document does not say.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
     /// package supplier or publisher.
     pub supplier: Option<String>,
+    /// the copyright notice, as the source document states it (e.g.,
+    /// "Copyright (c) 2016 Example Inc."), for attribution.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
     /// human-readable description.
     pub description: Option<String>,
     /// package URL per the [purl spec](https://github.com/package-url/purl-spec).
     pub purl: Option<String>,
+    /// CPE names (2.2 URI or 2.3 formatted string, e.g.
+    /// "cpe:2.3:a:openssl:openssl:3.0.2:*:*:*:*:*:*:*") as the document
+    /// states them; see [`cpe_vendor_product`].
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub cpes: BTreeSet<String>,
+    /// references to resources outside the sbom, keyed by type: the
+    /// CycloneDX external reference types (`vcs`, `website`, `distribution`,
+    /// `advisories`, `issue-tracker`, ...) mapped to the URLs of that type.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub external_refs: BTreeMap<String, BTreeSet<String>>,
     /// SPDX license identifiers (e.g., "MIT", "Apache-2.0").
     pub licenses: BTreeSet<String>,
     /// the SPDX license expression the source document declared, when it
@@ -213,8 +317,27 @@ pub struct Component {
     /// through [`licensing`](Self::licensing), which keeps the operators.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub license_expression: Option<String>,
+    /// SPDX license identifiers the package's authors declared, kept apart
+    /// from the concluded [`licenses`](Self::licenses).
+    ///
+    /// filled from SPDX `licenseDeclared`, CycloneDX license entries marked
+    /// `acknowledgement: declared`, and syft licenses of type `declared`.
+    /// when a document states no concluded license, the declared one is
+    /// used for [`licenses`](Self::licenses) as well.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub declared_licenses: BTreeSet<String>,
+    /// the declared SPDX license expression, when there is one.
+    /// [`declared_licenses`](Self::declared_licenses) is its flattened
+    /// identifier set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub declared_license_expression: Option<String>,
     /// checksums keyed by algorithm (e.g., "sha256" -> "abc123...").
     pub hashes: BTreeMap<String, String>,
+    /// free-form name/value annotations (CycloneDX `properties`, SPDX
+    /// package annotations), e.g. "syft:package:foundBy" ->
+    /// {"javascript-lock-cataloger"}. a name may carry several values.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, BTreeSet<String>>,
     /// original identifiers from the source document (e.g., SPDX SPDXRef, CycloneDX bom-ref).
     pub source_ids: Vec<String>,
 }
@@ -236,23 +359,126 @@ impl Component {
             name,
             version,
             ecosystem: None,
+            kind: None,
             supplier: None,
+            copyright: None,
             description: None,
             purl: None,
+            cpes: BTreeSet::new(),
+            external_refs: BTreeMap::new(),
             licenses: BTreeSet::new(),
             license_expression: None,
+            declared_licenses: BTreeSet::new(),
+            declared_license_expression: None,
             hashes: BTreeMap::new(),
+            properties: BTreeMap::new(),
             source_ids: Vec::new(),
         }
     }
 
-    /// returns the licensing this component declares, for policy evaluation.
+    /// returns the licensing this component is under (the concluded license,
+    /// or the declared one when none was concluded), for policy evaluation.
     pub fn licensing(&self) -> Licensing<'_> {
         Licensing {
             expression: self.license_expression.as_deref(),
             ids: &self.licenses,
         }
     }
+
+    /// returns the licensing the package's authors declared, for policy
+    /// evaluation; empty when the document does not separate it.
+    pub fn declared_licensing(&self) -> Licensing<'_> {
+        Licensing {
+            expression: self.declared_license_expression.as_deref(),
+            ids: &self.declared_licenses,
+        }
+    }
+
+    /// the name this component is reconciled by across SBOMs, spelled the
+    /// way its ecosystem compares names: pypi names per PEP 503 (lowercased,
+    /// runs of `-`, `_` and `.` as one `-`), nuget names lowercased, and
+    /// maven names qualified with their group (`groupId:artifactId`), taken
+    /// from the purl namespace when the name is bare. other ecosystems'
+    /// names are compared as they are.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sbom_model::Component;
+    ///
+    /// let mut c = Component::new("PyYAML".into(), None);
+    /// c.ecosystem = Some("pypi".into());
+    /// assert_eq!(c.identity_name(), "pyyaml");
+    ///
+    /// let mut c = Component::new("slf4j-api".into(), None);
+    /// c.ecosystem = Some("maven".into());
+    /// c.purl = Some("pkg:maven/org.slf4j/slf4j-api@2.0.9".into());
+    /// assert_eq!(c.identity_name(), "org.slf4j:slf4j-api");
+    /// ```
+    pub fn identity_name(&self) -> String {
+        match self.ecosystem.as_deref() {
+            Some("maven") if !self.name.contains(':') => {
+                let group = self
+                    .purl
+                    .as_deref()
+                    .and_then(|p| PackageUrl::from_str(p).ok())
+                    .and_then(|p| p.namespace().map(str::to_string));
+                match group {
+                    Some(group) => format!("{group}:{}", self.name),
+                    None => self.name.clone(),
+                }
+            }
+            ecosystem => spell_name(ecosystem, &self.name),
+        }
+    }
+
+    /// the name this component is compared by against one whose ecosystem
+    /// is unknown, spelled the way `ecosystem` (the ecosystem the pair does
+    /// state, if any) compares names, as in [`identity_name`](Self::identity_name).
+    /// maven names are reduced to their artifactId, since the side without an
+    /// ecosystem has no group to compare.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use sbom_model::Component;
+    ///
+    /// let bare = Component::new("PyYAML".into(), None);
+    /// assert_eq!(bare.wildcard_name(Some("pypi")), "pyyaml");
+    ///
+    /// let mut c = Component::new("slf4j-api".into(), None);
+    /// c.ecosystem = Some("maven".into());
+    /// c.purl = Some("pkg:maven/org.slf4j/slf4j-api@2.0.9".into());
+    /// assert_eq!(c.wildcard_name(Some("maven")), "slf4j-api");
+    /// ```
+    pub fn wildcard_name(&self, ecosystem: Option<&str>) -> String {
+        match ecosystem {
+            Some("maven") => self.name.rsplit(':').next().unwrap_or_default().to_string(),
+            ecosystem => spell_name(ecosystem, &self.name),
+        }
+    }
+}
+
+/// `name` spelled the way `ecosystem` compares names: pypi per PEP 503,
+/// nuget lowercased, any other as it is.
+fn spell_name(ecosystem: Option<&str>, name: &str) -> String {
+    match ecosystem {
+        Some("pypi") => {
+            let mut out = String::with_capacity(name.len());
+            for c in name.chars() {
+                if matches!(c, '-' | '_' | '.') {
+                    if !out.ends_with('-') {
+                        out.push('-');
+                    }
+                } else {
+                    out.extend(c.to_lowercase());
+                }
+            }
+            out
+        }
+        Some("nuget") => name.to_lowercase(),
+        _ => name.to_string(),
+    }
 }
 
 impl Sbom {
@@ -262,7 +488,8 @@ impl Sbom {
     /// - sorts components by ID
     /// - deduplicates and sorts licenses within each component
     /// - lowercases hash algorithms and values
-    /// - clears volatile metadata (timestamps, tools, authors)
+    /// - clears volatile metadata (timestamps, tools, authors, document
+    ///   identifiers), keeping only the primary component
     ///
     /// call this before comparing two SBOMs to ignore irrelevant differences.
     pub fn normalize(&mut self) {
@@ -273,15 +500,35 @@ impl Sbom {
         for component in self.components.values_mut() {
             component.normalize();
         }
+        for file in &mut self.files {
+            file.normalize();
+        }
+        self.files
+            .sort_by(|a, b| (&a.package, &a.path).cmp(&(&b.package, &b.path)));
 
         // strip volatile metadata
         self.metadata.timestamp = None;
         self.metadata.tools.clear();
         self.metadata.authors.clear();
+        self.metadata.subjects.clear();
+        self.metadata.format = None;
+        self.metadata.spec_version = None;
+        self.metadata.name = None;
+        self.metadata.namespace = None;
+        self.metadata.serial_number = None;
+        self.metadata.bom_version = None;
 
         self.rebuild_reverse_deps();
     }
 
+    /// drops every component property whose name `keep` rejects, so only
+    /// the properties a caller cares about take part in a diff.
+    pub fn retain_properties<F: Fn(&str) -> bool>(&mut self, keep: F) {
+        for component in self.components.values_mut() {
+            component.properties.retain(|name, _| keep(name));
+        }
+    }
+
     /// rebuilds the reverse dependency index from the forward `dependencies` map.
     ///
     /// must be called after modifying `dependencies` for `rdeps()` and `roots()`
@@ -473,6 +720,279 @@ pub fn ecosystem_from_purl(purl: &str) -> Option<String> {
     PackageUrl::from_str(purl).ok().map(|p| p.ty().to_string())
 }
 
+/// qualifiers whose value is the default for their package type, per type:
+/// `(type, qualifier, default value)`. a purl stating the default is the
+/// same package as one leaving it out.
+const DEFAULT_QUALIFIERS: &[(&str, &str, &str)] = &[
+    ("cargo", "repository_url", "https://crates.io"),
+    ("composer", "repository_url", "https://packagist.org"),
+    ("gem", "platform", "ruby"),
+    ("gem", "repository_url", "https://rubygems.org"),
+    ("hex", "repository_url", "https://repo.hex.pm"),
+    (
+        "maven",
+        "repository_url",
+        "https://repo.maven.apache.org/maven2",
+    ),
+    ("maven", "type", "jar"),
+    ("npm", "repository_url", "https://registry.npmjs.org"),
+    ("nuget", "repository_url", "https://www.nuget.org"),
+    ("pub", "repository_url", "https://pub.dartlang.org"),
+    ("pypi", "repository_url", "https://pypi.org"),
+];
+
+/// the canonical form of a purl, so that two tools spelling one package
+/// differently agree on it. on top of what parsing already applies
+/// (lowercased type and qualifier keys, the per-type lowercasing of
+/// names and namespaces, pypi `_` -> `-`, sorted qualifiers, empty
+/// qualifiers dropped), this lowercases npm scopes and drops qualifiers
+/// stating their type's default, e.g. maven `type=jar` or a
+/// `repository_url` naming the public registry.
+///
+/// returns `None` if the purl cannot be parsed.
+///
+/// # Example
+///
+/// ```
+/// use sbom_model::canonical_purl;
+///
+/// assert_eq!(
+///     canonical_purl("pkg:npm/%40Angular/Core@17.0.0").as_deref(),
+///     Some("pkg:npm/%40angular/core@17.0.0")
+/// );
+/// assert_eq!(
+///     canonical_purl("pkg:maven/org.apache/commons-io@2.15.0?type=jar").as_deref(),
+///     Some("pkg:maven/org.apache/commons-io@2.15.0")
+/// );
+/// assert_eq!(canonical_purl("not a purl"), None);
+/// ```
+pub fn canonical_purl(purl: &str) -> Option<String> {
+    let mut parsed = PackageUrl::from_str(purl).ok()?;
+    let ty = parsed.ty().to_string();
+
+    if ty == "npm" {
+        if let Some(scope) = parsed.namespace().map(str::to_lowercase) {
+            parsed.with_namespace(scope).ok()?;
+        }
+    }
+
+    let is_default = |key: &str, value: &str| {
+        let value = value.trim_end_matches('/');
+        DEFAULT_QUALIFIERS
+            .iter()
+            .any(|&(t, k, v)| t == ty && k == key && v.eq_ignore_ascii_case(value))
+    };
+    let qualifiers: Vec<(String, String)> = parsed
+        .qualifiers()
+        .iter()
+        .filter(|(k, v)| !is_default(k, v))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    if qualifiers.len() != parsed.qualifiers().len() {
+        parsed.clear_qualifiers();
+        for (k, v) in qualifiers {
+            parsed.add_qualifier(k, v).ok()?;
+        }
+    }
+
+    Some(parsed.to_string())
+}
+
+/// the qualifiers of a purl, in canonical form (see [`canonical_purl`]).
+/// empty when the purl cannot be parsed.
+///
+/// # Example
+///
+/// ```
+/// use sbom_model::purl_qualifiers;
+///
+/// let q = purl_qualifiers("pkg:deb/debian/curl@7.88.1?arch=amd64&distro=debian-12");
+/// assert_eq!(q["distro"], "debian-12");
+/// assert!(purl_qualifiers("not a purl").is_empty());
+/// ```
+pub fn purl_qualifiers(purl: &str) -> BTreeMap<String, String> {
+    canonical_purl(purl)
+        .and_then(|p| PackageUrl::from_str(&p).ok().map(|p| qualifier_map(&p)))
+        .unwrap_or_default()
+}
+
+fn qualifier_map(purl: &PackageUrl) -> BTreeMap<String, String> {
+    purl.qualifiers()
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// the canonical form of a purl (see [`canonical_purl`]) with the named
+/// qualifiers left out. returns `None` if the purl cannot be parsed.
+///
+/// # Example
+///
+/// ```
+/// use sbom_model::purl_without_qualifiers;
+/// use std::collections::BTreeSet;
+///
+/// let distro = BTreeSet::from(["distro".to_string()]);
+/// assert_eq!(
+///     purl_without_qualifiers("pkg:deb/debian/curl@7.88.1?arch=amd64&distro=debian-12", &distro)
+///         .as_deref(),
+///     Some("pkg:deb/debian/curl@7.88.1?arch=amd64")
+/// );
+/// ```
+pub fn purl_without_qualifiers(purl: &str, names: &BTreeSet<String>) -> Option<String> {
+    let canonical = canonical_purl(purl)?;
+    let mut parsed = PackageUrl::from_str(&canonical).ok()?;
+    let kept: Vec<(String, String)> = qualifier_map(&parsed)
+        .into_iter()
+        .filter(|(k, _)| !names.contains(k))
+        .collect();
+    parsed.clear_qualifiers();
+    for (k, v) in kept {
+        parsed.add_qualifier(k, v).ok()?;
+    }
+    Some(parsed.to_string())
+}
+
+/// the canonical form of a purl (see [`canonical_purl`]) without its
+/// version, so two purls that differ only in the version they pin compare
+/// equal. returns `None` if the purl cannot be parsed.
+///
+/// # Example
+///
+/// ```
+/// use sbom_model::purl_without_version;
+///
+/// assert_eq!(
+///     purl_without_version("pkg:npm/lodash@4.17.21").as_deref(),
+///     Some("pkg:npm/lodash")
+/// );
+/// ```
+pub fn purl_without_version(purl: &str) -> Option<String> {
+    let canonical = canonical_purl(purl)?;
+    let mut parsed = PackageUrl::from_str(&canonical).ok()?;
+    parsed.without_version();
+    Some(parsed.to_string())
+}
+
+/// extracts the lowercased `(vendor, product)` pair a CPE name identifies,
+/// from either the 2.3 formatted string (`cpe:2.3:a:vendor:product:...`) or
+/// the 2.2 URI binding (`cpe:/a:vendor:product:...`).
+///
+/// returns `None` for anything else, or when vendor or product is a
+/// wildcard (`*`, `-` or empty), since such a name identifies no product.
+///
+/// # Example
+///
+/// ```
+/// use sbom_model::cpe_vendor_product;
+///
+/// assert_eq!(
+///     cpe_vendor_product("cpe:2.3:a:OpenSSL:openssl:3.0.2:*:*:*:*:*:*:*"),
+///     Some(("openssl".to_string(), "openssl".to_string()))
+/// );
+/// assert_eq!(
+///     cpe_vendor_product("cpe:/o:debian:debian_linux:12"),
+///     Some(("debian".to_string(), "debian_linux".to_string()))
+/// );
+/// assert_eq!(cpe_vendor_product("cpe:2.3:a:*:openssl:*:*:*:*:*:*:*:*"), None);
+/// ```
+pub fn cpe_vendor_product(cpe: &str) -> Option<(String, String)> {
+    let cpe = cpe.trim();
+    let (fields, unescape): (Vec<&str>, fn(&str) -> String) =
+        if let Some(rest) = strip_prefix_ignore_case(cpe, "cpe:2.3:") {
+            (split_unescaped_colons(rest), |s| s.replace('\\', ""))
+        } else if let Some(rest) = strip_prefix_ignore_case(cpe, "cpe:/") {
+            (rest.split(':').collect(), percent_decode)
+        } else {
+            return None;
+        };
+    let field = |i: usize| -> Option<String> {
+        let value = unescape(fields.get(i)?).to_lowercase();
+        (!matches!(value.as_str(), "" | "*" | "-")).then_some(value)
+    };
+    Some((field(1)?, field(2)?))
+}
+
+/// a CPE name with its version component masked and the rest lowercased,
+/// so two names that differ only in the version they pin compare equal.
+/// anything that is not a CPE name is returned lowercased.
+///
+/// # Example
+///
+/// ```
+/// use sbom_model::cpe_without_version;
+///
+/// assert_eq!(
+///     cpe_without_version("cpe:2.3:a:OpenSSL:openssl:3.0.2:*:*:*:*:*:*:*"),
+///     cpe_without_version("cpe:2.3:a:openssl:openssl:3.0.7:*:*:*:*:*:*:*"),
+/// );
+/// assert_eq!(cpe_without_version("cpe:/a:haxx:curl:7.88.1"), "cpe:/a:haxx:curl:");
+/// ```
+pub fn cpe_without_version(cpe: &str) -> String {
+    let cpe = cpe.trim().to_lowercase();
+    let (prefix, mut fields, any) = if let Some(rest) = cpe.strip_prefix("cpe:2.3:") {
+        ("cpe:2.3:", split_unescaped_colons(rest), "*")
+    } else if let Some(rest) = cpe.strip_prefix("cpe:/") {
+        ("cpe:/", rest.split(':').collect(), "")
+    } else {
+        return cpe;
+    };
+    if let Some(version) = fields.get_mut(3) {
+        *version = any;
+    }
+    format!("{}{}", prefix, fields.join(":"))
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = s.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &s[prefix.len()..])
+}
+
+/// splits a CPE 2.3 formatted string on the colons that are not escaped.
+fn split_unescaped_colons(s: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ':' => {
+                fields.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    fields.push(&s[start..]);
+    fields
+}
+
+/// decodes the `%xx` escapes of a CPE 2.2 URI component.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = (bytes[i] == b'%')
+            .then(|| s.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match decoded {
+            Some(b) => {
+                out.push(b);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
 /// extracts individual license IDs from an SPDX expression.
 ///
 /// parses the expression and returns all license IDs found, including
@@ -976,6 +1496,45 @@ pub fn is_hash_algorithm_downgrade(
     }
 }
 
+/// the algorithms both hash sets carry a digest for, where the digests
+/// differ, by canonical name (see [`canonical_algorithm_name`]).
+///
+/// an algorithm only one side lists is no mismatch: it says nothing about
+/// whether the bytes changed.
+///
+/// # Example
+///
+/// ```
+/// use sbom_model::mismatched_digests;
+/// use std::collections::BTreeMap;
+///
+/// let old: BTreeMap<String, String> = [("sha512".into(), "abc".into())].into();
+/// let new: BTreeMap<String, String> = [
+///     ("SHA-512".into(), "def".into()),
+///     ("md5".into(), "123".into()),
+/// ]
+/// .into();
+/// assert_eq!(mismatched_digests(&old, &new), vec!["SHA-512"]);
+/// assert!(mismatched_digests(&old, &old).is_empty());
+/// ```
+pub fn mismatched_digests(
+    old_hashes: &BTreeMap<String, String>,
+    new_hashes: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let canonical = |hashes: &BTreeMap<String, String>| -> BTreeMap<String, String> {
+        hashes
+            .iter()
+            .map(|(k, v)| (canonical_algorithm_name(k), v.to_lowercase()))
+            .collect()
+    };
+    let new_hashes = canonical(new_hashes);
+    canonical(old_hashes)
+        .into_iter()
+        .filter(|(algo, digest)| new_hashes.get(algo).is_some_and(|d| d != digest))
+        .map(|(algo, _)| algo)
+        .collect()
+}
+
 /// classifies an SPDX license identifier as copyleft.
 ///
 /// looks the ID up in the compile-time SPDX license list and returns whether
@@ -1062,6 +1621,29 @@ mod tests {
         assert_eq!(comp.hashes.get("sha-256").unwrap(), "abc");
     }
 
+    #[test]
+    fn test_sbom_normalize_orders_files_by_package_then_path() {
+        let file = |path: &str, package: Option<&str>| FileEntry {
+            path: path.to_string(),
+            package: package.map(|p| ComponentId::new(Some(p), &[])),
+            hashes: BTreeMap::from([("SHA-256".to_string(), "ABC".to_string())]),
+            licenses: BTreeSet::new(),
+            source_ids: Vec::new(),
+        };
+        let mut sbom = Sbom {
+            files: vec![
+                file("b", Some("pkg:npm/b@1")),
+                file("z", None),
+                file("a", Some("pkg:npm/b@1")),
+            ],
+            ..Default::default()
+        };
+        sbom.normalize();
+        let paths: Vec<_> = sbom.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["z", "a", "b"]);
+        assert_eq!(sbom.files[0].hashes["sha-256"], "abc");
+    }
+
     fn licensing<'a>(expression: &'a str, ids: &'a BTreeSet<String>) -> Licensing<'a> {
         Licensing {
             expression: Some(expression),
@@ -1630,6 +2212,121 @@ mod tests {
         assert!(sbom.by_purl("pkg:npm/nonexistent@1.0").is_none());
     }
 
+    #[test]
+    fn test_canonical_purl_per_type() {
+        let canonical = |purl: &str| canonical_purl(purl).unwrap();
+        // npm scopes are case-insensitive, and `@` may or may not be encoded
+        assert_eq!(
+            canonical("pkg:npm/@Angular/Core@17.0.0"),
+            canonical("pkg:npm/%40angular/core@17.0.0")
+        );
+        assert_eq!(
+            canonical("pkg:npm/%40angular/core@17.0.0?repository_url=https://registry.npmjs.org/"),
+            "pkg:npm/%40angular/core@17.0.0"
+        );
+        // pypi names are lowercased and use `-` for `_`
+        assert_eq!(
+            canonical("pkg:pypi/Django_Rest_Framework@3.14.0"),
+            "pkg:pypi/django-rest-framework@3.14.0"
+        );
+        // golang namespaces are lowercased; the module name keeps its case
+        assert_eq!(
+            canonical("pkg:golang/GitHub.com/Sirupsen/logrus@v1.9.3"),
+            "pkg:golang/github.com/sirupsen/logrus@v1.9.3"
+        );
+        // qualifiers are sorted, and defaults and empty values dropped
+        assert_eq!(
+            canonical("pkg:maven/org.apache/commons-io@2.15.0?type=jar&classifier=sources"),
+            "pkg:maven/org.apache/commons-io@2.15.0?classifier=sources"
+        );
+        assert_eq!(
+            canonical("pkg:maven/org.apache/commons-io@2.15.0?type=pom"),
+            "pkg:maven/org.apache/commons-io@2.15.0?type=pom"
+        );
+        assert_eq!(
+            canonical("pkg:deb/debian/curl@7.88.1?distro=bookworm&arch=amd64&epoch="),
+            "pkg:deb/debian/curl@7.88.1?arch=amd64&distro=bookworm"
+        );
+        assert_eq!(
+            canonical("pkg:gem/rails@7.1.0?platform=ruby"),
+            "pkg:gem/rails@7.1.0"
+        );
+        // a default only applies to its own type
+        assert_eq!(
+            canonical("pkg:npm/foo@1.0.0?type=jar"),
+            "pkg:npm/foo@1.0.0?type=jar"
+        );
+    }
+
+    #[test]
+    fn test_identity_name_per_ecosystem() {
+        let named = |ecosystem: &str, name: &str, purl: Option<&str>| {
+            let mut c = Component::new(name.into(), None);
+            c.ecosystem = Some(ecosystem.into());
+            c.purl = purl.map(str::to_string);
+            c.identity_name()
+        };
+        assert_eq!(
+            named("pypi", "typing_extensions", None),
+            named("pypi", "Typing-Extensions", None)
+        );
+        assert_eq!(named("pypi", "zope.interface", None), "zope-interface");
+        assert_eq!(named("pypi", "a_-.b", None), "a-b");
+        assert_eq!(named("nuget", "Newtonsoft.Json", None), "newtonsoft.json");
+        assert_eq!(
+            named("maven", "org.slf4j:slf4j-api", None),
+            named(
+                "maven",
+                "slf4j-api",
+                Some("pkg:maven/org.slf4j/slf4j-api@2.0.9")
+            )
+        );
+        // without a group to qualify it, a bare maven name stays bare
+        assert_eq!(named("maven", "slf4j-api", None), "slf4j-api");
+        // npm names are case-sensitive
+        assert_eq!(named("npm", "JSONStream", None), "JSONStream");
+    }
+
+    #[test]
+    fn test_purl_qualifier_helpers() {
+        let purl = "pkg:deb/debian/openssl@3.0.11?Distro=debian-12&arch=amd64";
+        assert_eq!(
+            purl_qualifiers(purl),
+            BTreeMap::from([
+                ("arch".to_string(), "amd64".to_string()),
+                ("distro".to_string(), "debian-12".to_string()),
+            ])
+        );
+        assert!(purl_qualifiers("not a purl").is_empty());
+        let both = BTreeSet::from(["arch".to_string(), "distro".to_string()]);
+        assert_eq!(
+            purl_without_qualifiers(purl, &both).as_deref(),
+            Some("pkg:deb/debian/openssl@3.0.11")
+        );
+        // canonicalization happens before ignoring, so defaults stay dropped
+        assert_eq!(
+            purl_without_qualifiers("pkg:gem/rails@7.1.0?platform=ruby", &both).as_deref(),
+            Some("pkg:gem/rails@7.1.0")
+        );
+        assert_eq!(
+            purl_without_version(purl).as_deref(),
+            Some("pkg:deb/debian/openssl?arch=amd64&distro=debian-12")
+        );
+        assert_eq!(purl_without_version("not a purl"), None);
+    }
+
+    #[test]
+    fn test_component_id_canonicalizes_purl_spellings() {
+        let a = ComponentId::new(Some("pkg:npm/%40Babel/core@7.0.0"), &[]);
+        let b = ComponentId::new(Some("pkg:npm/@babel/core@7.0.0"), &[]);
+        assert_eq!(a, b);
+        let a = ComponentId::new(
+            Some("pkg:maven/org.slf4j/slf4j-api@2.0.9?type=jar&repository_url=https://repo.maven.apache.org/maven2"),
+            &[],
+        );
+        assert_eq!(a.as_str(), "pkg:maven/org.slf4j/slf4j-api@2.0.9");
+    }
+
     #[test]
     fn test_component_id_unparseable_purl() {
         // a purl string that can't be parsed should still be used as-is
@@ -1649,8 +2346,12 @@ mod tests {
         sbom.metadata.timestamp = Some("2024-01-01T00:00:00Z".into());
         sbom.metadata.tools.push("syft".into());
         sbom.metadata.authors.push("alice".into());
+        sbom.metadata.format = Some("cyclonedx".into());
+        sbom.metadata.serial_number = Some("urn:uuid:1".into());
+        sbom.metadata.bom_version = Some(2);
 
         let c = Component::new("a".into(), Some("1".into()));
+        sbom.metadata.primary_component = Some(c.id.clone());
         sbom.components.insert(c.id.clone(), c);
 
         sbom.normalize();
@@ -1658,6 +2359,10 @@ mod tests {
         assert!(sbom.metadata.timestamp.is_none());
         assert!(sbom.metadata.tools.is_empty());
         assert!(sbom.metadata.authors.is_empty());
+        assert!(sbom.metadata.format.is_none());
+        assert!(sbom.metadata.serial_number.is_none());
+        assert!(sbom.metadata.bom_version.is_none());
+        assert!(sbom.metadata.primary_component.is_some());
     }
 
     #[test]
@@ -1675,6 +2380,64 @@ mod tests {
         assert_eq!(missing.len(), 1);
     }
 
+    #[test]
+    fn test_retain_properties() {
+        let mut sbom = Sbom::default();
+        let mut comp = Component::new("pkg".into(), Some("1.0".into()));
+        comp.properties
+            .insert("syft:package:foundBy".into(), BTreeSet::from(["a".into()]));
+        comp.properties
+            .insert("syft:location:0:path".into(), BTreeSet::from(["/x".into()]));
+        sbom.components.insert(comp.id.clone(), comp);
+
+        sbom.retain_properties(|name| name.starts_with("syft:package:"));
+        let comp = sbom.components.values().next().unwrap();
+        assert_eq!(
+            comp.properties.keys().collect::<Vec<_>>(),
+            vec!["syft:package:foundBy"]
+        );
+    }
+
+    #[test]
+    fn test_cpe_vendor_product() {
+        let vp = |v: &str, p: &str| Some((v.to_string(), p.to_string()));
+
+        assert_eq!(
+            cpe_vendor_product("cpe:2.3:a:nodejs:node\\.js:18.0.0:*:*:*:*:*:*:*"),
+            vp("nodejs", "node.js")
+        );
+        // an escaped colon stays inside the product
+        assert_eq!(
+            cpe_vendor_product("cpe:2.3:a:acme:foo\\:bar:1.0:*:*:*:*:*:*:*"),
+            vp("acme", "foo:bar")
+        );
+        assert_eq!(
+            cpe_vendor_product("CPE:/a:Haxx:curl:7.88.1"),
+            vp("haxx", "curl")
+        );
+        assert_eq!(
+            cpe_vendor_product("cpe:/a:acme:foo%7ebar"),
+            vp("acme", "foo~bar")
+        );
+        assert_eq!(cpe_vendor_product("cpe:/a:acme"), None);
+        assert_eq!(cpe_vendor_product("cpe:2.3:a:acme:-:1.0"), None);
+        assert_eq!(cpe_vendor_product("pkg:npm/lodash@4.17.21"), None);
+    }
+
+    #[test]
+    fn test_cpe_without_version() {
+        assert_eq!(
+            cpe_without_version("cpe:2.3:a:acme:foo\\:bar:1.0:*:*:*:*:linux:*:*"),
+            "cpe:2.3:a:acme:foo\\:bar:*:*:*:*:*:linux:*:*"
+        );
+        assert_ne!(
+            cpe_without_version("cpe:2.3:a:acme:foo:1.0:*:*:*:*:linux:*:*"),
+            cpe_without_version("cpe:2.3:a:acme:foo:1.0:*:*:*:*:windows:*:*")
+        );
+        assert_eq!(cpe_without_version("cpe:/a:acme"), "cpe:/a:acme");
+        assert_eq!(cpe_without_version("NOT-A-CPE"), "not-a-cpe");
+    }
+
     #[test]
     fn test_ecosystem_from_purl() {
         use super::ecosystem_from_purl;
@@ -1862,6 +2625,26 @@ mod tests {
         assert!(!is_hash_algorithm_downgrade(&old, &new));
     }
 
+    #[test]
+    fn test_mismatched_digests() {
+        let old: BTreeMap<String, String> = [
+            ("sha-256".into(), "ABC".into()),
+            ("sha-1".into(), "xyz".into()),
+        ]
+        .into();
+        // same digest in another case and spelling: no mismatch
+        let new: BTreeMap<String, String> = [("SHA256".into(), "abc".into())].into();
+        assert!(mismatched_digests(&old, &new).is_empty());
+
+        let new: BTreeMap<String, String> = [
+            ("sha-256".into(), "def".into()),
+            ("sha-1".into(), "uvw".into()),
+            ("md5".into(), "123".into()),
+        ]
+        .into();
+        assert_eq!(mismatched_digests(&old, &new), vec!["SHA-1", "SHA-256"]);
+    }
+
     #[test]
     fn test_detect_cycles_none() {
         let mut sbom = Sbom::default();